@@ -1,3 +1,4 @@
+/// Running totals of values, per key and overall.
 #[derive(Debug, Clone)]
 pub struct Aggregate<K, V> {
     m: std::collections::HashMap<K, V>,
@@ -34,6 +35,7 @@ where
 }
 
 impl<K, V> Aggregate<K, V> {
+    /// Returns the total of all values added.
     pub fn sum(&self) -> V
     where
         V: Copy,
@@ -41,10 +43,12 @@ impl<K, V> Aggregate<K, V> {
         self.sum
     }
 
+    /// Returns whether no value was added yet.
     pub fn is_empty(&self) -> bool {
         self.m.is_empty()
     }
 
+    /// Adds `value` to the total of `key`.
     pub fn add(&mut self, key: K, value: V)
     where
         K: Copy + Eq + std::hash::Hash,
@@ -54,6 +58,7 @@ impl<K, V> Aggregate<K, V> {
         self.sum += value;
     }
 
+    /// Returns the total of `key`, if any value was added to it.
     pub fn get(&self, key: K) -> Option<V>
     where
         K: Copy + Eq + std::hash::Hash,
@@ -62,6 +67,7 @@ impl<K, V> Aggregate<K, V> {
         self.m.get(&key).copied()
     }
 
+    /// Iterates over each key with its total, in no particular order.
     pub fn iter(&self) -> impl Iterator<Item = (K, V)> + '_
     where
         K: Copy,
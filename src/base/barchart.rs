@@ -1,9 +1,15 @@
+//! Bar charts of totals over time.
+
 use crate::base;
 
+/// Horizontal bar chart of totals per period, ready to be displayed.
 pub struct Barchart {
     charset: base::Charset,
     bounds: base::Interval,
     unit: base::Datepart,
+    cal: base::Calendar,
+    projected: base::Interval,
+    net: bool,
     pos: base::Aggregate<base::Date, base::Cents>,
     neg: base::Aggregate<base::Date, base::Cents>,
     label_charlen: usize,
@@ -11,21 +17,35 @@ pub struct Barchart {
     max_barlen: usize,
 }
 
+/// Settings for building a [`Barchart`].
 #[derive(Debug, PartialEq, Eq)]
 pub struct Config {
+    /// Characters to draw the chart with.
     pub charset: base::Charset,
+    /// Only records within this interval are charted.
     pub bounds: base::Interval,
+    /// Length of each period.
     pub unit: base::Datepart,
+    /// Determines where weeks and years start.
+    pub cal: base::Calendar,
+    /// Periods starting within this interval are drawn with the projected bar
+    /// characters of `charset`.
+    pub projected: base::Interval,
+    /// Whether to draw the net total below the bars of each period.
+    pub net: bool,
+    /// Number of columns available to draw the chart in.
     pub term_width: usize,
+    /// Records to chart.
     pub rl: base::Recordlist,
 }
 
 impl Config {
+    /// Computes the totals of each period and lays out the chart.
     pub fn to_barchart(&self) -> Barchart {
         let bounds = self.rl.spanned_interval().intersection(self.bounds);
         let mut pos = base::Aggregate::<base::Date, base::Cents>::default();
         let mut neg = base::Aggregate::<base::Date, base::Cents>::default();
-        for interval in bounds.iter(self.unit) {
+        for interval in bounds.iter(self.unit, self.cal) {
             for r in self.rl.slice_spanning_interval(interval) {
                 match r.amount().cmp(&base::Cents(0)) {
                     std::cmp::Ordering::Greater => pos.add(interval.start, r.amount()),
@@ -36,9 +56,12 @@ impl Config {
         }
 
         let label_charlen = match self.unit {
-            base::Datepart::Year => 4,  // yyyy
-            base::Datepart::Month => 8, // yyyy mmm
-            base::Datepart::Day => 10,  // yyyy-mm-dd
+            base::Datepart::Year if self.cal.has_fiscal_year() => 7, // yyyy/yy
+            base::Datepart::Year => 4,                               // yyyy
+            base::Datepart::Quarter => 7,                            // yyyy Qn
+            base::Datepart::Month => 8,                              // yyyy mmm
+            base::Datepart::Week => 11,                              // yyyy mmm dd
+            base::Datepart::Day => 10,                               // yyyy-mm-dd
         };
         let max_abs_val = base::Cents::max(
             pos.iter().map(|(_, v)| v.abs()).max().unwrap_or_default(),
@@ -50,7 +73,7 @@ impl Config {
         // was sourced from a positive entry, the overall chart may end up with
         // a width of `term_width - 2` instead of `term_width`.
         let max_barlen = self.term_width.max(base::util::MIN_TERM_WIDTH)
-            - label_charlen // max 10
+            - label_charlen // max 11
             - base::util::BOUNDING_SPACES_COUNT
             - 1 // vertical divider just before bar
             - (-max_abs_val).charlen(); // max 27
@@ -59,6 +82,9 @@ impl Config {
             charset: self.charset.clone(),
             bounds,
             unit: self.unit,
+            cal: self.cal,
+            projected: self.projected,
+            net: self.net,
             pos,
             neg,
             label_charlen,
@@ -69,10 +95,35 @@ impl Config {
 }
 
 impl Barchart {
+    /// Returns every period charted, with its totals of incoming and of
+    /// outgoing amounts.
+    pub fn periods(&self) -> impl Iterator<Item = (base::Interval, base::Cents, base::Cents)> + '_ {
+        self.bounds.iter(self.unit, self.cal).map(|interval| {
+            let pos = self.pos.get(interval.start).unwrap_or_default();
+            let neg = self.neg.get(interval.start).unwrap_or_default();
+            (interval, pos, neg)
+        })
+    }
+
     fn label(&self, dt: base::Date) -> String {
         let fmt = match self.unit {
+            base::Datepart::Year if self.cal.has_fiscal_year() => {
+                // Years that don't start in January are labeled with the
+                // calendar years they span, e.g. 2015/16.
+                let year = dt.first_of(base::Datepart::Year, self.cal).year();
+                return format!("{:0>4}/{:0>2}", year, (year + 1) % 100);
+            }
             base::Datepart::Year => time::macros::format_description!("[year]"),
+            base::Datepart::Quarter => {
+                let year = dt
+                    .format(time::macros::format_description!("[year]"))
+                    .expect("formatting should succeed");
+                return format!("{} Q{}", year, (dt.month() - 1) / 3 + 1);
+            }
             base::Datepart::Month => time::macros::format_description!("[year] [month repr:short]"),
+            base::Datepart::Week => {
+                time::macros::format_description!("[year] [month repr:short] [day]")
+            }
             base::Datepart::Day => time::macros::format_description!("[year]-[month]-[day]"),
         };
         dt.format(fmt).expect("formatting should succeed")
@@ -87,39 +138,54 @@ impl Barchart {
         if self.pos.is_empty() && self.neg.is_empty() {
             return Ok(());
         }
-        write!(w, "{} |", self.label(dt))?;
+        let projected = self.projected.start <= dt && dt <= self.projected.end;
+        let (bar_pos, bar_neg) = if projected {
+            (
+                self.charset.chart_bar_pos_projected,
+                self.charset.chart_bar_neg_projected,
+            )
+        } else {
+            (self.charset.chart_bar_pos, self.charset.chart_bar_neg)
+        };
+        let pos = self.pos.get(dt).unwrap_or_default();
+        let neg = self.neg.get(dt).unwrap_or_default();
+
+        let mut lines = Vec::with_capacity(2);
         if !self.pos.is_empty() {
-            let val = self.pos.get(dt).unwrap_or_default();
+            lines.push((bar_pos, colored::Color::Green, pos));
+        }
+        if !self.neg.is_empty() {
+            lines.push((bar_neg, colored::Color::Red, neg));
+        }
+        for (i, (bar, color, val)) in lines.into_iter().enumerate() {
+            if i == 0 {
+                w.write_str(&self.label(dt))?;
+            } else {
+                self.pad(w)?;
+            }
+            w.write_str(" |")?;
             let barlen = self.barlen(val);
             if barlen > 0 {
-                let mut bars = self.charset.chart_bar_pos.to_string().repeat(barlen);
+                let mut bars = bar.to_string().repeat(barlen);
                 if self.charset.color {
-                    bars = colored::Colorize::green(bars.as_str()).to_string();
+                    bars = colored::Colorize::color(bars.as_str(), color).to_string();
                 }
                 w.write_str(&bars)?;
                 w.write_char(' ')?;
             }
             writeln!(w, "{}", val)?;
-
-            if self.neg.is_empty() {
-                return Ok(());
-            }
-            for _ in 0..(self.label_charlen) {
-                w.write_char(' ')?;
-            }
-            w.write_str(" |")?;
         }
-        let val = self.neg.get(dt).unwrap_or_default();
-        let barlen = self.barlen(val);
-        if barlen > 0 {
-            let mut bars = self.charset.chart_bar_neg.to_string().repeat(barlen);
-            if self.charset.color {
-                bars = colored::Colorize::red(bars.as_str()).to_string();
-            }
-            w.write_str(&bars)?;
+        if self.net {
+            self.pad(w)?;
+            writeln!(w, " |= {}", pos + neg)?;
+        }
+        Ok(())
+    }
+
+    fn pad(&self, w: &mut impl std::fmt::Write) -> std::fmt::Result {
+        for _ in 0..(self.label_charlen) {
             w.write_char(' ')?;
         }
-        writeln!(w, "{}", val)?;
         Ok(())
     }
 }
@@ -127,7 +193,7 @@ impl Barchart {
 impl std::fmt::Display for Barchart {
     /// Writes a terminating newline.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        for interval in self.bounds.iter(self.unit) {
+        for interval in self.bounds.iter(self.unit, self.cal) {
             self.draw(f, interval.start)?;
         }
         Ok(())
@@ -185,6 +251,24 @@ mod tests {
         2015-05-05 |++++++++++++++++++++++++++++++++++++++++++++++++++++++++++++ 20.00
         2015-05-06 |++++++++++++++++++++++++++++++++++++++++++++++++++++++++++++ 20.00
     "))]
+    #[case(":", Datepart::Quarter, indoc!("
+        2015 Q1 |++++++++++++++++++++++++++++++++++++++++++++++++++++++++++++++ 170.00
+                |-------------------------- (70.00)
+        2015 Q2 |+++++++++++++++ 40.00
+                |--------------- (40.00)
+    "))]
+    #[case("2015-03-30:2015-04-29", Datepart::Week, indoc!("
+        2015 Mar 30 |++++++++++++++++++++++++++++++++++++++++++++++++++++++++++ 170.00
+                    |------------------------ (70.00)
+        2015 Apr 06 |0.00
+                    |0.00
+        2015 Apr 13 |0.00
+                    |0.00
+        2015 Apr 20 |0.00
+                    |0.00
+        2015 Apr 27 |0.00
+                    |------- (20.00)
+    "))]
     #[case(":", Datepart::Month, indoc!("
         2015 Mar |+++++++++++++++++++++++++++++++++++++++++++++++++++++++++++++ 170.00
                  |------------------------- (70.00)
@@ -203,10 +287,75 @@ mod tests {
             charset: Charset::default(),
             bounds,
             unit,
+            cal: base::Calendar::default(),
+            projected: Interval::EMPTY,
+            net: false,
             rl,
             term_width: 80,
         };
         let chart = config.to_barchart();
         assert_eq!(chart.to_string(), want)
     }
+
+    #[test]
+    fn test_barchart_fiscal_year() {
+        let config = Config {
+            charset: Charset::default(),
+            bounds: Interval::MAX,
+            unit: Datepart::Year,
+            cal: base::Calendar {
+                first_month: base::Month::April,
+                ..Default::default()
+            },
+            projected: Interval::EMPTY,
+            net: false,
+            rl: r#"
+                {"d":"2015-03-31","c":"aaa","a":2000}
+                {"d":"2015-04-01","c":"aaa","a":-2000}
+            "#
+            .parse()
+            .unwrap(),
+            term_width: 80,
+        };
+        let want = indoc!(
+            "
+            2014/15 |+++++++++++++++++++++++++++++++++++++++++++++++++++++++++++++++ 20.00
+                    |0.00
+            2015/16 |0.00
+                    |--------------------------------------------------------------- (20.00)
+        "
+        );
+        assert_eq!(config.to_barchart().to_string(), want)
+    }
+
+    #[test]
+    fn test_barchart_projected_with_net() {
+        let config = Config {
+            charset: Charset::default(),
+            bounds: Interval::MAX,
+            unit: Datepart::Month,
+            cal: base::Calendar::default(),
+            projected: "2015-04-01:2015-04-30".parse().unwrap(),
+            net: true,
+            rl: r#"
+                {"d":"2015-03-31","c":"aaa","a":2000}
+                {"d":"2015-03-31","c":"aaa","a":-1000}
+                {"d":"2015-04-01","c":"aaa","a":-2000}
+            "#
+            .parse()
+            .unwrap(),
+            term_width: 80,
+        };
+        let want = indoc!(
+            "
+            2015 Mar |++++++++++++++++++++++++++++++++++++++++++++++++++++++++++++++ 20.00
+                     |------------------------------- (10.00)
+                     |= 10.00
+            2015 Apr |0.00
+                     |~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~ (20.00)
+                     |= (20.00)
+        "
+        );
+        assert_eq!(config.to_barchart().to_string(), want)
+    }
 }
@@ -0,0 +1,19 @@
+use crate::base;
+
+/// Settings that determine how dates are grouped into periods longer than a
+/// day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Calendar {
+    /// First day of the week.
+    pub first_weekday: base::Weekday,
+    /// First month of the (possibly fiscal) year. Quarters and
+    /// [`base::Date::year`] remain calendar based regardless.
+    pub first_month: base::Month,
+}
+
+impl Calendar {
+    /// Returns whether years start in a month other than January.
+    pub fn has_fiscal_year(self) -> bool {
+        self.first_month != base::Month::January
+    }
+}
@@ -1,3 +1,6 @@
+//! Hierarchical category names.
+
+/// Slash-separated category name, such as `food/groceries`.
 #[derive(
     Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
 )]
@@ -5,13 +8,19 @@
 pub struct Category(String);
 
 impl Category {
+    /// Separates the levels of a category name.
     pub const SEP: &str = "/";
+    /// Name of the level above every category.
     pub const LEVEL0: &str = "All";
 
+    /// Returns the full name of the category.
     pub fn as_str(&self) -> &str {
         &self.0
     }
 
+    /// Returns the name truncated to its first `level` levels, or
+    /// [`Self::LEVEL0`] for level 0. Names with fewer levels are returned
+    /// whole.
     pub fn level(&self, level: usize) -> &str {
         if level == 0 {
             return Self::LEVEL0;
@@ -34,12 +43,16 @@ impl std::fmt::Display for Category {
     }
 }
 
+/// Error returned when a category name is invalid.
 #[derive(Debug, thiserror::Error)]
 pub enum ParseError {
+    /// Name is empty.
     #[error("input is empty")]
     Empty,
+    /// Name starts or ends with a separator.
     #[error("input starts or ends with '{}'", Category::SEP)]
     TerminalSeparator,
+    /// Name has an empty level between two separators.
     #[error("input contains consecutive occurrences of '{}'", Category::SEP)]
     ConsecutiveSeparators,
 }
@@ -27,6 +27,7 @@ use crate::base;
 pub struct Cents(pub i64);
 
 impl Cents {
+    /// Returns the absolute value.
     pub const fn abs(self) -> Self {
         Self(self.0.abs())
     }
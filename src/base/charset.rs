@@ -1,13 +1,27 @@
+/// Characters used to draw trees and charts, and whether to color them.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Charset {
+    /// Horizontal rule.
     pub dash: char,
+    /// Branch to a child that has siblings below it.
     pub tree_sideways_t: &'static str,
+    /// Branch to the last child.
     pub tree_corner: &'static str,
+    /// Indentation below a child that has siblings below it.
     pub tree_pipe_gap: &'static str,
+    /// Indentation below the last child.
     pub tree_space: &'static str,
+    /// Vertical axis between the labels and the bars of a chart.
     pub chart_axis: char,
+    /// Bar of incoming amounts.
     pub chart_bar_pos: char,
+    /// Bar of outgoing amounts.
     pub chart_bar_neg: char,
+    /// Bar of projected incoming amounts.
+    pub chart_bar_pos_projected: char,
+    /// Bar of projected outgoing amounts.
+    pub chart_bar_neg_projected: char,
+    /// Whether to use ANSI colors.
     pub color: bool,
 }
 
@@ -23,12 +37,15 @@ impl Default for Charset {
             chart_axis: '|',
             chart_bar_pos: '+',
             chart_bar_neg: '-',
+            chart_bar_pos_projected: '*',
+            chart_bar_neg_projected: '~',
             color: false,
         }
     }
 }
 
 impl Charset {
+    /// Replaces the drawing characters with box-drawing and block characters.
     pub fn with_unicode(self) -> Self {
         Self {
             dash: '\u{2500}',
@@ -39,10 +56,13 @@ impl Charset {
             chart_axis: '\u{2502}',
             chart_bar_pos: '\u{2588}',
             chart_bar_neg: '\u{2588}',
+            chart_bar_pos_projected: '\u{2592}',
+            chart_bar_neg_projected: '\u{2592}',
             ..self
         }
     }
 
+    /// Turns on colors.
     pub fn with_color(self) -> Self {
         Self {
             color: true,
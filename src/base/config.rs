@@ -1,22 +1,214 @@
+//! Contents of the config file.
+
 use crate::base;
 
 /// Application config.
 #[derive(Debug, Clone, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "camelCase", default, deny_unknown_fields)]
 pub struct Config {
+    /// Day that weeks start on.
+    pub first_day_of_week: base::Weekday,
+    /// Index of the first record in views, as shown to the user.
     pub first_index_in_date: usize,
+    /// First month of the fiscal year. Once set to a month other than January,
+    /// fiscal years replace calendar years everywhere, such as in dates,
+    /// intervals and 'plot -y', except in 'lim': contribution limits remain per
+    /// calendar year.
+    pub fiscal_year_start: base::Month,
+    /// Account type of the limits file when it doesn't define named accounts.
+    /// Either the name of a built-in type, or user-defined rules.
     pub lim_account_type: Option<base::Limitkind>,
+    /// Yearly maximums of the calculated RRSP deduction limit.
+    pub rrsp_deduction_maximums: base::Limits,
+    /// Whether RRSP contributions made in the first 60 days of a year count
+    /// towards the previous year.
+    pub rrsp_first_60_days: bool,
+    /// Wildcard patterns to match categories of earned income.
+    pub rrsp_income_categories: Vec<String>,
+    /// Whether amounts given without a sign are outgoing.
     pub unsigned_is_negative: bool,
+    /// Whether to use ANSI colors in output.
     pub use_colored_output: bool,
+    /// Whether to draw with Unicode symbols instead of ASCII.
     pub use_unicode_symbols: bool,
+    /// Named lists of entries to log together.
     pub templates: std::collections::BTreeMap<String, Vec<TemplateEntry>>,
+    /// Short names that stand for categories wherever a category or category
+    /// pattern is given.
+    pub aliases: std::collections::BTreeMap<String, base::Category>,
 }
 
+impl Config {
+    /// Returns the category that `s` is an alias of, or `s` itself if it is
+    /// not an alias.
+    pub fn expand_alias<'a>(&'a self, s: &'a str) -> &'a str {
+        self.aliases.get(s).map_or(s, base::Category::as_str)
+    }
+
+    /// Returns `category` with aliases expanded.
+    pub fn expand_category(&self, category: &base::Category) -> base::Category {
+        self.aliases
+            .get(category.as_str())
+            .unwrap_or(category)
+            .clone()
+    }
+}
+
+/// Entry of a template, logged as one record.
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct TemplateEntry {
+    /// Category of the record.
     pub category: base::Category,
-    pub amount: base::Cents,
+    /// Amount of the record.
+    pub amount: TemplateAmount,
+    /// Note of the record.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub note: String,
+    /// Number of days after the template's date to log the entry on. May be
+    /// negative.
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub days: i32,
+}
+
+fn is_zero(n: &i32) -> bool {
+    *n == 0
+}
+
+/// Amount of a template entry. Either a fixed amount in cents, such as `5000`,
+/// or a percentage of one of the amounts the template is logged with, such as
+/// `{"param": 1, "percent": -20}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(untagged)]
+pub enum TemplateAmount {
+    /// Amount in cents.
+    Fixed(base::Cents),
+    /// Percentage of a parameter.
+    Param(TemplateParam),
+}
+
+/// Reference to one of the amounts a template is logged with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateParam {
+    /// Position of the amount among those the template is logged with,
+    /// starting at 1.
+    pub param: std::num::NonZeroUsize,
+    /// Percentage of the amount to use. Defaults to 100.
+    #[serde(
+        default = "Percent::hundred",
+        skip_serializing_if = "Percent::is_hundred"
+    )]
+    pub percent: Percent,
+}
+
+/// Percentage with up to two decimal places, stored in hundredths of a
+/// percent. Written in JSON as a plain number, such as `5.95`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(try_from = "serde_json::Number", into = "serde_json::Number")]
+pub struct Percent(pub i64);
+
+impl Percent {
+    fn hundred() -> Self {
+        Self(10000)
+    }
+
+    fn is_hundred(&self) -> bool {
+        *self == Self::hundred()
+    }
+
+    /// Returns this percentage of `amount`, rounded to the nearest cent, with
+    /// halves rounded away from zero.
+    pub fn of(self, amount: base::Cents) -> base::Cents {
+        let n = amount.0 as i128 * self.0 as i128;
+        let rounded = (n + n.signum() * 5000) / 10000;
+        base::Cents(rounded as i64)
+    }
+}
+
+/// Error returned when a number is not a valid [`Percent`].
+#[derive(Debug, thiserror::Error)]
+#[error("invalid percentage '{0}'")]
+pub struct ParsePercentError(String);
+
+impl TemplateAmount {
+    /// Returns the amount given `params`, the amounts the template is logged
+    /// with, or `None` if it refers to a missing one.
+    pub fn resolve(self, params: &[base::Cents]) -> Option<base::Cents> {
+        match self {
+            TemplateAmount::Fixed(cents) => Some(cents),
+            TemplateAmount::Param(p) => params
+                .get(p.param.get() - 1)
+                .map(|&amount| p.percent.of(amount)),
+        }
+    }
+
+    /// Returns the position of the parameter the amount refers to, if any.
+    pub fn param(self) -> Option<usize> {
+        match self {
+            TemplateAmount::Fixed(_) => None,
+            TemplateAmount::Param(p) => Some(p.param.get()),
+        }
+    }
+}
+
+impl std::fmt::Display for TemplateAmount {
+    /// Formats fixed amounts as cents do, and parameters like `$1` or
+    /// `-20% of $1`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TemplateAmount::Fixed(cents) => write!(f, "{}", cents),
+            TemplateAmount::Param(p) if p.percent.is_hundred() => write!(f, "${}", p.param),
+            TemplateAmount::Param(p) => write!(f, "{}% of ${}", p.percent, p.param),
+        }
+    }
+}
+
+impl std::fmt::Display for Percent {
+    /// Formats without trailing zeros, such as `20` or `-5.9`.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let (whole, frac) = (self.0.abs() / 100, self.0.abs() % 100);
+        match frac {
+            0 => write!(f, "{}{}", sign, whole),
+            _ if frac % 10 == 0 => write!(f, "{}{}.{}", sign, whole, frac / 10),
+            _ => write!(f, "{}{}.{:02}", sign, whole, frac),
+        }
+    }
+}
+
+impl std::str::FromStr for Percent {
+    type Err = ParsePercentError;
+
+    /// Parses a percentage such as `20` or `-5.95`, without a `%` sign.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParsePercentError(s.to_string());
+        match s.split_once('.') {
+            Some((_, frac)) if frac.trim_end_matches('0').len() > 2 => Err(invalid()),
+            _ if s.contains(['e', 'E', ',']) => Err(invalid()),
+            _ => s
+                .parse::<base::Cents>()
+                .map(|c| Self(c.0))
+                .map_err(|_| invalid()),
+        }
+    }
+}
+
+impl TryFrom<serde_json::Number> for Percent {
+    type Error = ParsePercentError;
+
+    fn try_from(value: serde_json::Number) -> Result<Self, Self::Error> {
+        value.to_string().parse()
+    }
+}
+
+impl From<Percent> for serde_json::Number {
+    fn from(value: Percent) -> Self {
+        match value.0 % 100 {
+            0 => Self::from(value.0 / 100),
+            _ => Self::from_f64(value.0 as f64 / 100.0).expect("percentage should be finite"),
+        }
+    }
 }
 
 impl std::fmt::Display for Config {
@@ -42,3 +234,54 @@ impl TryFrom<&str> for Config {
         value.parse::<Self>()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("20", Some(Percent(2000)))]
+    #[case("-5.95", Some(Percent(-595)))]
+    #[case("0.5", Some(Percent(50)))]
+    #[case("1.230", Some(Percent(123)))]
+    #[case("1.234", None)]
+    #[case("1e2", Some(Percent(10000)))]
+    #[case("1e-5", None)]
+    fn test_percent_from_json(#[case] s: &str, #[case] want: Option<Percent>) {
+        let got = serde_json::from_str::<Percent>(s).ok();
+        assert_eq!(got, want);
+        if let Some(percent) = got {
+            let roundtrip = serde_json::to_string(&percent).unwrap();
+            assert_eq!(
+                serde_json::from_str::<Percent>(&roundtrip).unwrap(),
+                percent
+            );
+        }
+    }
+
+    #[rstest]
+    #[case(Percent(2000), base::Cents(320000), base::Cents(64000))]
+    #[case(Percent(-595), base::Cents(320000), base::Cents(-19040))]
+    #[case(Percent(5000), base::Cents(1), base::Cents(1))]
+    #[case(Percent(-5000), base::Cents(1), base::Cents(-1))]
+    #[case(Percent(3333), base::Cents(100), base::Cents(33))]
+    fn test_percent_of(
+        #[case] percent: Percent,
+        #[case] amount: base::Cents,
+        #[case] want: base::Cents,
+    ) {
+        assert_eq!(percent.of(amount), want);
+    }
+
+    #[rstest]
+    #[case(r#"{"category": "a", "amount": 100}"#, true)]
+    #[case(r#"{"category": "a", "amount": {"param": 1}}"#, true)]
+    #[case(r#"{"category": "a", "amount": {"param": 0}}"#, false)]
+    #[case(r#"{"category": "a", "amount": {"param": 1, "bogus": 1}}"#, false)]
+    #[case(r#"{"category": "a", "amount": 1, "note": "x", "days": -1}"#, true)]
+    fn test_template_entry_from_json(#[case] s: &str, #[case] ok: bool) {
+        assert_eq!(serde_json::from_str::<TemplateEntry>(s).is_ok(), ok);
+    }
+}
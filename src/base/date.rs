@@ -1,3 +1,5 @@
+//! Dates and the ways users may write them.
+
 use crate::base;
 
 /// A date type without time or timezone information. Values are guaranteed to
@@ -27,18 +29,26 @@ impl Date {
         time::Date::__from_ordinal_date_unchecked(9999, time::util::days_in_year(9999))
     });
 
+    /// Returns the calendar year.
     pub const fn year(self) -> u16 {
         self.0.year() as u16
     }
 
+    /// Returns the month, from 1 to 12.
     pub const fn month(self) -> u16 {
         self.0.month() as u16
     }
 
+    /// Returns the day of the month, starting from 1.
     pub const fn day(self) -> u16 {
         self.0.day() as u16
     }
 
+    /// Returns the day of the year, starting from 1.
+    pub const fn ordinal(self) -> u16 {
+        self.0.ordinal()
+    }
+
     fn new(inner: time::Date) -> Option<Self> {
         let dt = Self(inner);
         if dt >= Self::MIN && dt <= Self::MAX {
@@ -48,6 +58,8 @@ impl Date {
         }
     }
 
+    /// Returns the date with the given calendar year, month and day, or `None`
+    /// if it does not exist or is out of range.
     pub fn from_ymd(year: u16, month: u16, day: u16) -> Option<Self> {
         let year = i32::from(year);
         let month = time::Month::try_from(u8::try_from(month).ok()?).ok()?;
@@ -77,6 +89,7 @@ impl Date {
         Self::from_ymd_unchecked(2015, 3, 30)
     }
 
+    /// Formats the date with a `time` format description.
     pub fn format(
         self,
         fmt: &(impl time::formatting::Formattable + ?Sized),
@@ -84,35 +97,91 @@ impl Date {
         self.0.format(fmt)
     }
 
-    pub fn first_of(self, part: base::Datepart) -> Self {
+    /// Returns the day of the week.
+    pub fn weekday(self) -> base::Weekday {
+        self.0.weekday().into()
+    }
+
+    /// Returns the first day of the datepart containing this date. Weeks and
+    /// years that would begin before [`Self::MIN`] are clamped to it.
+    pub fn first_of(self, part: base::Datepart, cal: base::Calendar) -> Self {
         match part {
             base::Datepart::Day => self,
+            base::Datepart::Week => self
+                .shift(base::Datepart::Day, -self.days_into_week(cal))
+                .unwrap_or(Self::MIN),
             base::Datepart::Month => Self::from_ymd_unchecked(self.year(), self.month(), 1),
-            base::Datepart::Year => Self::from_ymd_unchecked(self.year(), 1, 1),
+            base::Datepart::Quarter => {
+                Self::from_ymd_unchecked(self.year(), (self.month() - 1) / 3 * 3 + 1, 1)
+            }
+            base::Datepart::Year => {
+                let first_month = cal.first_month.number();
+                let year = match self.month() >= first_month {
+                    true => Some(self.year()),
+                    false => self.year().checked_sub(1),
+                };
+                year.and_then(|y| Self::from_ymd(y, first_month, 1))
+                    .unwrap_or(Self::MIN)
+            }
         }
     }
 
-    pub fn last_of(self, part: base::Datepart) -> Self {
+    /// Returns the last day of the datepart containing this date. Weeks and
+    /// years that would end after [`Self::MAX`] are clamped to it.
+    pub fn last_of(self, part: base::Datepart, cal: base::Calendar) -> Self {
         match part {
             base::Datepart::Day => self,
+            base::Datepart::Week => self
+                .shift(base::Datepart::Day, 6 - self.days_into_week(cal))
+                .unwrap_or(Self::MAX),
             base::Datepart::Month => Self::from_ymd_unchecked(
                 self.year(),
                 self.month(),
                 self.0.month().length(self.0.year()).into(),
             ),
-            base::Datepart::Year => Self::from_ymd_unchecked(self.year(), 12, 31),
+            base::Datepart::Quarter => {
+                let month = (self.month() - 1) / 3 * 3 + 3;
+                Self::from_ymd_unchecked(self.year(), month, 1).last_of(base::Datepart::Month, cal)
+            }
+            base::Datepart::Year => {
+                let first_month = cal.first_month.number();
+                if first_month == 1 {
+                    return Self::from_ymd_unchecked(self.year(), 12, 31);
+                }
+                let year = match self.month() >= first_month {
+                    true => self.year() + 1,
+                    false => self.year(),
+                };
+                Self::from_ymd(year, first_month - 1, 1)
+                    .map(|dt| dt.last_of(base::Datepart::Month, cal))
+                    .unwrap_or(Self::MAX)
+            }
         }
     }
 
+    /// Returns the number of days between the start of this date's week and
+    /// this date. The result is in \[0, 6].
+    fn days_into_week(self, cal: base::Calendar) -> i32 {
+        let weekday = self.weekday().number_days_from_monday() as i32;
+        let first = cal.first_weekday.number_days_from_monday() as i32;
+        (weekday - first).rem_euclid(7)
+    }
+
     /// Offsets the given date by the given datepart, returning `None` if the
     /// resultant date is out of bounds.
     ///
-    /// When shifting by years or months, this function clamps the resultant
-    /// date's day to the resultant month's last-day-of-month. For example, if
-    /// the original date is a Feb 29, shifting by 1 year will yield the next
-    /// year's Feb 28.
+    /// When shifting by years, quarters or months, this function clamps the
+    /// resultant date's day to the resultant month's last-day-of-month. For
+    /// example, if the original date is a Feb 29, shifting by 1 year will
+    /// yield the next year's Feb 28.
     pub fn shift(self, part: base::Datepart, offset: i32) -> Option<Self> {
         let (y, m) = match part {
+            base::Datepart::Week => {
+                return self.shift(base::Datepart::Day, offset.checked_mul(7)?);
+            }
+            base::Datepart::Quarter => {
+                return self.shift(base::Datepart::Month, offset.checked_mul(3)?);
+            }
             base::Datepart::Day => {
                 return self
                     .0
@@ -151,62 +220,245 @@ impl std::fmt::Display for Date {
     }
 }
 
+/// Error returned when a date fails to parse.
 #[derive(Debug, thiserror::Error)]
 pub enum ParseError {
+    /// Input is empty.
     #[error("input is empty")]
     Empty,
+    /// Input looks like a literal date but isn't one.
     #[error(transparent)]
     BadFormat(#[from] time::error::Parse),
+    /// Date falls outside of [`Date::MIN`] and [`Date::MAX`].
     #[error("date is before {} or after {}", Date::MIN, Date::MAX)]
     OutOfRange,
-    #[error("first character is not one of {{y, Y, m, M, d, D}}")]
+    /// Month of a `yyyy-mm` literal is not between 1 and 12.
+    #[error("month is not between 01 and 12")]
+    InvalidMonth,
+    /// Relative date starts with an unknown datepart.
+    #[error("first character is not one of {{y, Y, q, Q, m, M, w, W, d, D}}")]
     InvalidFirstChar,
+    /// Offset of a relative date is not an integer.
     #[error(transparent)]
     InvalidOffset(#[from] std::num::ParseIntError),
+    /// Input is not the name of a day or of a weekday.
+    #[error("'{0}' is not a recognized date name")]
+    UnknownName(String),
 }
 
-impl std::str::FromStr for Date {
-    type Err = ParseError;
+/// A parsed date input that remembers the datepart it was written in terms
+/// of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Spec {
+    /// A single day, e.g. `2015-03-30`, `d-1` or `yesterday`.
+    Day(Date),
+    /// A specific day within a datepart, e.g. `m` (first day of this month) or
+    /// `Y` (last day of this year).
+    Within(Date, base::Datepart),
+    /// An entire datepart, e.g. `2015`, `2015-03` or `last-month`. The date is
+    /// the datepart's first day.
+    Whole(Date, base::Datepart),
+}
 
-    /// Parses a string to a date. Inputs must be in one of the following formats:
+impl Spec {
+    /// Parses a string in one of the following formats:
     /// - `yyyy-mm-dd`
-    /// - `xn` where `x` is one of `{y, Y, m, M, d, D}` and `n` is an integer
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+    /// - `yyyy-mm` or `yyyy`, meaning the whole month or year
+    /// - `xn` where `x` is one of `{y, Y, q, Q, m, M, w, W, d, D}` and `n` is
+    ///   an integer
+    /// - `today`, `yesterday` or `last-month`
+    /// - A weekday name such as `mon` or `monday`, meaning the most recent
+    ///   such day up to and including today
+    ///
+    /// Relative dates are resolved against today's date, with week and year
+    /// boundaries determined by `cal`.
+    pub fn parse(s: &str, cal: base::Calendar) -> Result<Self, ParseError> {
         if s.is_empty() {
-            return Err(Self::Err::Empty);
+            return Err(ParseError::Empty);
+        }
+        let bytes = s.as_bytes();
+        if bytes[0].is_ascii_digit() {
+            return Self::parse_literal(s, cal);
         }
-        if s.as_bytes()[0].is_ascii_digit() {
-            return time::Date::parse(s, &time::format_description::well_known::Iso8601::DEFAULT)
-                .map_err(Self::Err::BadFormat)
-                .and_then(|x| Self::new(x).ok_or(Self::Err::OutOfRange));
+        if bytes.len() > 1 && bytes[1].is_ascii_alphabetic() {
+            return Self::parse_name(s);
         }
 
-        let bytes = s.as_bytes();
         let offset: i32 = if bytes.len() == 1 {
             0
         } else {
             std::str::from_utf8(&bytes[1..])
-                .map_err(|_| Self::Err::InvalidFirstChar)?
+                .map_err(|_| ParseError::InvalidFirstChar)?
                 .parse::<i32>()?
         };
-        let today = Self::today();
-        match bytes[0] as char {
-            'd' | 'D' => today.shift(base::Datepart::Day, offset),
-            'y' => today
-                .first_of(base::Datepart::Year)
-                .shift(base::Datepart::Year, offset),
-            'Y' => today
-                .last_of(base::Datepart::Year)
-                .shift(base::Datepart::Year, offset),
-            'm' => today
-                .first_of(base::Datepart::Month)
-                .shift(base::Datepart::Month, offset),
-            'M' => today
-                .shift(base::Datepart::Month, offset)
-                .map(|dt| dt.last_of(base::Datepart::Month)),
-            _ => None,
+        Self::relative(bytes[0] as char, offset, Date::today(), cal)
+    }
+
+    /// Resolves the relative date `xn`, where `x` is `kind` and `n` is
+    /// `offset`, against `today`.
+    fn relative(
+        kind: char,
+        offset: i32,
+        today: Date,
+        cal: base::Calendar,
+    ) -> Result<Self, ParseError> {
+        let within = |dt: Option<Date>, part| dt.map(|dt| Self::Within(dt, part));
+        match kind {
+            'd' | 'D' => today.shift(base::Datepart::Day, offset).map(Self::Day),
+            'y' => within(
+                today
+                    .first_of(base::Datepart::Year, cal)
+                    .shift(base::Datepart::Year, offset),
+                base::Datepart::Year,
+            ),
+            'Y' => within(
+                today
+                    .shift(base::Datepart::Year, offset)
+                    .map(|dt| dt.last_of(base::Datepart::Year, cal)),
+                base::Datepart::Year,
+            ),
+            'q' => within(
+                today
+                    .first_of(base::Datepart::Quarter, cal)
+                    .shift(base::Datepart::Quarter, offset),
+                base::Datepart::Quarter,
+            ),
+            'Q' => within(
+                today
+                    .shift(base::Datepart::Quarter, offset)
+                    .map(|dt| dt.last_of(base::Datepart::Quarter, cal)),
+                base::Datepart::Quarter,
+            ),
+            'm' => within(
+                today
+                    .first_of(base::Datepart::Month, cal)
+                    .shift(base::Datepart::Month, offset),
+                base::Datepart::Month,
+            ),
+            'M' => within(
+                today
+                    .shift(base::Datepart::Month, offset)
+                    .map(|dt| dt.last_of(base::Datepart::Month, cal)),
+                base::Datepart::Month,
+            ),
+            'w' => within(
+                today
+                    .first_of(base::Datepart::Week, cal)
+                    .shift(base::Datepart::Week, offset),
+                base::Datepart::Week,
+            ),
+            'W' => within(
+                today
+                    .last_of(base::Datepart::Week, cal)
+                    .shift(base::Datepart::Week, offset),
+                base::Datepart::Week,
+            ),
+            _ => return Err(ParseError::InvalidFirstChar),
+        }
+        .ok_or(ParseError::OutOfRange)
+    }
+
+    /// Parses `yyyy`, `yyyy-mm` or `yyyy-mm-dd`. A year literal refers to the
+    /// year that starts in `cal.first_month` of `yyyy`.
+    fn parse_literal(s: &str, cal: base::Calendar) -> Result<Self, ParseError> {
+        let bytes = s.as_bytes();
+        let is_year = |b: &[u8]| b.len() == 4 && b.iter().all(u8::is_ascii_digit);
+        if is_year(bytes) {
+            let year = s.parse::<u16>().expect("year should be 4 digits");
+            return Ok(Self::Whole(
+                Date::from_ymd_unchecked(year, cal.first_month.number(), 1),
+                base::Datepart::Year,
+            ));
+        }
+        if bytes.len() == 7
+            && is_year(&bytes[..4])
+            && bytes[4] == b'-'
+            && bytes[5..].iter().all(u8::is_ascii_digit)
+        {
+            let year = s[..4].parse::<u16>().expect("year should be 4 digits");
+            let month = s[5..].parse::<u16>().expect("month should be 2 digits");
+            return Date::from_ymd(year, month, 1)
+                .map(|dt| Self::Whole(dt, base::Datepart::Month))
+                .ok_or(ParseError::InvalidMonth);
+        }
+        time::Date::parse(s, &time::format_description::well_known::Iso8601::DEFAULT)
+            .map_err(ParseError::BadFormat)
+            .and_then(|x| Date::new(x).ok_or(ParseError::OutOfRange))
+            .map(Self::Day)
+    }
+
+    /// Parses named dates such as `yesterday` or `fri`.
+    fn parse_name(s: &str) -> Result<Self, ParseError> {
+        let today = Date::today();
+        let name = s.to_ascii_lowercase();
+        let weekday = match name.as_str() {
+            "today" => return Ok(Self::Day(today)),
+            "yesterday" => {
+                return today
+                    .shift(base::Datepart::Day, -1)
+                    .map(Self::Day)
+                    .ok_or(ParseError::OutOfRange);
+            }
+            "last-month" => {
+                return today
+                    .first_of(base::Datepart::Month, base::Calendar::default())
+                    .shift(base::Datepart::Month, -1)
+                    .map(|dt| Self::Whole(dt, base::Datepart::Month))
+                    .ok_or(ParseError::OutOfRange);
+            }
+            "mon" => base::Weekday::Monday,
+            "tue" => base::Weekday::Tuesday,
+            "wed" => base::Weekday::Wednesday,
+            "thu" => base::Weekday::Thursday,
+            "fri" => base::Weekday::Friday,
+            "sat" => base::Weekday::Saturday,
+            "sun" => base::Weekday::Sunday,
+            _ => name
+                .parse::<base::Weekday>()
+                .map_err(|_| ParseError::UnknownName(s.to_string()))?,
+        };
+        let days_since = (today.weekday().number_days_from_monday() as i32
+            - weekday.number_days_from_monday() as i32)
+            .rem_euclid(7);
+        today
+            .shift(base::Datepart::Day, -days_since)
+            .map(Self::Day)
+            .ok_or(ParseError::OutOfRange)
+    }
+
+    /// Returns the date named by the input. Entire dateparts name their first
+    /// day.
+    pub fn date(self) -> Date {
+        match self {
+            Self::Day(dt) | Self::Within(dt, _) | Self::Whole(dt, _) => dt,
+        }
+    }
+
+    /// Returns the first and last day of the datepart the input was written in
+    /// terms of.
+    pub fn span(self, cal: base::Calendar) -> (Date, Date) {
+        match self {
+            Self::Day(dt) => (dt, dt),
+            Self::Within(dt, part) | Self::Whole(dt, part) => {
+                (dt.first_of(part, cal), dt.last_of(part, cal))
+            }
         }
-        .ok_or(Self::Err::InvalidFirstChar)
+    }
+}
+
+impl Date {
+    /// Parses a string to a date. See [`Spec::parse`] for accepted formats.
+    pub fn from_str_with(s: &str, cal: base::Calendar) -> Result<Self, ParseError> {
+        Spec::parse(s, cal).map(Spec::date)
+    }
+}
+
+impl std::str::FromStr for Date {
+    type Err = ParseError;
+
+    /// Same as [`Date::from_str_with`] using the default calendar.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_str_with(s, base::Calendar::default())
     }
 }
 
@@ -240,15 +492,66 @@ mod tests {
 
     #[rstest]
     #[case("2015-03-30", base::Datepart::Year, "2015-01-01")]
+    #[case("2015-03-30", base::Datepart::Quarter, "2015-01-01")]
+    #[case("2015-12-30", base::Datepart::Quarter, "2015-10-01")]
     #[case("2015-03-30", base::Datepart::Month, "2015-03-01")]
+    #[case("2015-03-30", base::Datepart::Week, "2015-03-30")]
+    #[case("2015-04-05", base::Datepart::Week, "2015-03-30")]
+    #[case("0000-01-01", base::Datepart::Week, "0000-01-01")]
     #[case("2015-03-30", base::Datepart::Day, "2015-03-30")]
     fn test_first_of(#[case] dt: Date, #[case] part: base::Datepart, #[case] want: Date) {
-        assert_eq!(dt.first_of(part), want)
+        assert_eq!(dt.first_of(part, base::Calendar::default()), want)
+    }
+
+    #[rstest]
+    #[case("2015-03-30", base::Month::January, "2015-01-01", "2015-12-31")]
+    #[case("2015-03-30", base::Month::April, "2014-04-01", "2015-03-31")]
+    #[case("2015-04-01", base::Month::April, "2015-04-01", "2016-03-31")]
+    #[case("2015-03-30", base::Month::March, "2015-03-01", "2016-02-29")]
+    #[case("2015-12-31", base::Month::December, "2015-12-01", "2016-11-30")]
+    #[case("0000-01-01", base::Month::April, "0000-01-01", "0000-03-31")]
+    #[case("9999-12-31", base::Month::April, "9999-04-01", "9999-12-31")]
+    fn test_year_bounds(
+        #[case] dt: Date,
+        #[case] first_month: base::Month,
+        #[case] want_first: Date,
+        #[case] want_last: Date,
+    ) {
+        let cal = base::Calendar {
+            first_month,
+            ..Default::default()
+        };
+        assert_eq!(dt.first_of(base::Datepart::Year, cal), want_first);
+        assert_eq!(dt.last_of(base::Datepart::Year, cal), want_last);
+    }
+
+    #[rstest]
+    #[case("2015-03-30", base::Weekday::Monday, "2015-03-30", "2015-04-05")]
+    #[case("2015-03-30", base::Weekday::Sunday, "2015-03-29", "2015-04-04")]
+    #[case("2015-04-01", base::Weekday::Thursday, "2015-03-26", "2015-04-01")]
+    fn test_week_bounds(
+        #[case] dt: Date,
+        #[case] first_weekday: base::Weekday,
+        #[case] want_first: Date,
+        #[case] want_last: Date,
+    ) {
+        let cal = base::Calendar {
+            first_weekday,
+            ..Default::default()
+        };
+        assert_eq!(dt.first_of(base::Datepart::Week, cal), want_first);
+        assert_eq!(dt.last_of(base::Datepart::Week, cal), want_last);
     }
 
     #[rstest]
     #[case("2015-03-30", base::Datepart::Year, "2015-12-31")]
+    #[case("2015-03-30", base::Datepart::Quarter, "2015-03-31")]
+    #[case("2015-04-30", base::Datepart::Quarter, "2015-06-30")]
+    #[case("2015-11-30", base::Datepart::Quarter, "2015-12-31")]
     #[case("2015-03-30", base::Datepart::Month, "2015-03-31")]
+    #[case("2015-03-30", base::Datepart::Week, "2015-04-05")]
+    #[case("2015-04-05", base::Datepart::Week, "2015-04-05")]
+    #[case("9999-12-30", base::Datepart::Week, "9999-12-31")]
     #[case("2015-03-30", base::Datepart::Day, "2015-03-30")]
     #[case("1700-02-15", base::Datepart::Month, "1700-02-28")]
     #[case("1704-02-15", base::Datepart::Month, "1704-02-29")]
@@ -266,7 +569,7 @@ mod tests {
     #[case("3000-11-15", base::Datepart::Month, "3000-11-30")]
     #[case("3000-12-15", base::Datepart::Month, "3000-12-31")]
     fn test_last_of(#[case] dt: Date, #[case] part: base::Datepart, #[case] want: Date) {
-        assert_eq!(dt.last_of(part), want)
+        assert_eq!(dt.last_of(part, base::Calendar::default()), want)
     }
 
     #[rstest]
@@ -275,11 +578,17 @@ mod tests {
     #[case("2015-03-30", base::Datepart::Year, -1, Date::from_ymd(2014, 3, 30))]
     #[case("2015-03-30", base::Datepart::Year, 30, Date::from_ymd(2045, 3, 30))]
     #[case("2015-03-30", base::Datepart::Year, i32::MAX, None)]
+    #[case("2015-03-30", base::Datepart::Quarter, 1, Date::from_ymd(2015, 6, 30))]
+    #[case("2015-03-30", base::Datepart::Quarter, -5, Date::from_ymd(2013, 12, 30))]
+    #[case("2015-03-30", base::Datepart::Quarter, i32::MAX, None)]
     #[case("2015-03-30", base::Datepart::Month, 0, Date::from_ymd(2015, 3, 30))]
     #[case("2015-03-30", base::Datepart::Month, 1, Date::from_ymd(2015, 4, 30))]
     #[case("2015-03-30", base::Datepart::Month, -1, Date::from_ymd(2015, 2, 28))]
     #[case("2015-03-30", base::Datepart::Month, 27, Date::from_ymd(2017, 6, 30))]
     #[case("2015-03-30", base::Datepart::Month, -27, Date::from_ymd(2012, 12, 30))]
+    #[case("2015-03-30", base::Datepart::Week, 1, Date::from_ymd(2015, 4, 6))]
+    #[case("2015-03-30", base::Datepart::Week, -2, Date::from_ymd(2015, 3, 16))]
+    #[case("2015-03-30", base::Datepart::Week, i32::MAX, None)]
     #[case("2015-03-30", base::Datepart::Day, 0, Date::from_ymd(2015, 3, 30))]
     #[case("2015-03-30", base::Datepart::Day, 1, Date::from_ymd(2015, 3, 31))]
     #[case("2015-03-30", base::Datepart::Day, -1, Date::from_ymd(2015, 3, 29))]
@@ -300,20 +609,38 @@ mod tests {
 
     #[rstest]
     #[case("2015-03-30", Date::from_ymd(2015, 3, 30))]
-    #[case("y", Date::today().first_of(base::Datepart::Year).into())]
-    #[case("Y", Date::today().last_of(base::Datepart::Year).into())]
-    #[case("y+0", Date::today().first_of(base::Datepart::Year).into())]
-    #[case("y-0", Date::today().first_of(base::Datepart::Year).into())]
-    #[case("y100", Date::today().shift(base::Datepart::Year, 100).map(|dt| dt.first_of(base::Datepart::Year)))]
-    #[case("Y-100", Date::today().shift(base::Datepart::Year, -100).map(|dt| dt.last_of(base::Datepart::Year)))]
-    #[case("m", Date::today().first_of(base::Datepart::Month).into())]
-    #[case("M", Date::today().last_of(base::Datepart::Month).into())]
-    #[case("m100", Date::today().shift(base::Datepart::Month, 100).map(|dt| dt.first_of(base::Datepart::Month)))]
-    #[case("M-100", Date::today().shift(base::Datepart::Month, -100).map(|dt| dt.last_of(base::Datepart::Month)))]
-    #[case("d", Date::today().first_of(base::Datepart::Day).into())]
-    #[case("D", Date::today().last_of(base::Datepart::Day).into())]
+    #[case("y", Date::today().first_of(base::Datepart::Year, base::Calendar::default()).into())]
+    #[case("Y", Date::today().last_of(base::Datepart::Year, base::Calendar::default()).into())]
+    #[case("y+0", Date::today().first_of(base::Datepart::Year, base::Calendar::default()).into())]
+    #[case("y-0", Date::today().first_of(base::Datepart::Year, base::Calendar::default()).into())]
+    #[case("y100", Date::today().shift(base::Datepart::Year, 100).map(|dt| dt.first_of(base::Datepart::Year, base::Calendar::default())))]
+    #[case("Y-100", Date::today().shift(base::Datepart::Year, -100).map(|dt| dt.last_of(base::Datepart::Year, base::Calendar::default())))]
+    #[case("m", Date::today().first_of(base::Datepart::Month, base::Calendar::default()).into())]
+    #[case("M", Date::today().last_of(base::Datepart::Month, base::Calendar::default()).into())]
+    #[case("m100", Date::today().shift(base::Datepart::Month, 100).map(|dt| dt.first_of(base::Datepart::Month, base::Calendar::default())))]
+    #[case("M-100", Date::today().shift(base::Datepart::Month, -100).map(|dt| dt.last_of(base::Datepart::Month, base::Calendar::default())))]
+    #[case("q", Date::from_ymd(2015, 1, 1))]
+    #[case("Q", Date::from_ymd(2015, 3, 31))]
+    #[case("q1", Date::from_ymd(2015, 4, 1))]
+    #[case("Q-1", Date::from_ymd(2014, 12, 31))]
+    #[case("w", Date::from_ymd(2015, 3, 30))]
+    #[case("W", Date::from_ymd(2015, 4, 5))]
+    #[case("w-1", Date::from_ymd(2015, 3, 23))]
+    #[case("W+1", Date::from_ymd(2015, 4, 12))]
+    #[case("d", Date::today().first_of(base::Datepart::Day, base::Calendar::default()).into())]
+    #[case("D", Date::today().last_of(base::Datepart::Day, base::Calendar::default()).into())]
     #[case("d100", Date::today().shift(base::Datepart::Day, 100))]
     #[case("D-100", Date::today().shift(base::Datepart::Day, -100))]
+    #[case("2015-02", Date::from_ymd(2015, 2, 1))]
+    #[case("0000", Date::from_ymd(0, 1, 1))]
+    #[case("2014", Date::from_ymd(2014, 1, 1))]
+    #[case("today", Date::from_ymd(2015, 3, 30))]
+    #[case("Yesterday", Date::from_ymd(2015, 3, 29))]
+    #[case("last-month", Date::from_ymd(2015, 2, 1))]
+    #[case("mon", Date::from_ymd(2015, 3, 30))]
+    #[case("tue", Date::from_ymd(2015, 3, 24))]
+    #[case("sun", Date::from_ymd(2015, 3, 29))]
+    #[case("saturday", Date::from_ymd(2015, 3, 28))]
     #[case("", None)]
     #[case("0000-00-01", None)]
     #[case("0000-00-01", None)]
@@ -326,7 +653,101 @@ mod tests {
     #[case("a", None)]
     #[case("a123", None)]
     #[case("\u{251c}123", None)]
+    #[case("2015-00", None)]
+    #[case("2015-13", None)]
+    #[case("2015-1", None)]
+    #[case("201", None)]
+    #[case("20150", None)]
+    #[case("mo", None)]
+    #[case("last-year", None)]
     fn test_from_str(#[case] s: &str, #[case] want: Option<Date>) {
         assert_eq!(s.parse::<Date>().ok(), want)
     }
+
+    #[rstest]
+    #[case("w", base::Weekday::Sunday, "2015-03-29")]
+    #[case("W", base::Weekday::Sunday, "2015-04-04")]
+    #[case("w", base::Weekday::Tuesday, "2015-03-24")]
+    #[case("W1", base::Weekday::Tuesday, "2015-04-06")]
+    #[case("m", base::Weekday::Sunday, "2015-03-01")]
+    fn test_from_str_with_weekday(
+        #[case] s: &str,
+        #[case] first_weekday: base::Weekday,
+        #[case] want: Date,
+    ) {
+        let cal = base::Calendar {
+            first_weekday,
+            ..Default::default()
+        };
+        assert_eq!(Date::from_str_with(s, cal).unwrap(), want)
+    }
+
+    #[rstest]
+    #[case("y", base::Month::April, "2014-04-01")]
+    #[case("Y", base::Month::April, "2015-03-31")]
+    #[case("y1", base::Month::April, "2015-04-01")]
+    #[case("Y-1", base::Month::March, "2015-02-28")]
+    #[case("2015", base::Month::April, "2015-04-01")]
+    #[case("2015-02", base::Month::April, "2015-02-01")]
+    fn test_from_str_with_month(
+        #[case] s: &str,
+        #[case] first_month: base::Month,
+        #[case] want: Date,
+    ) {
+        let cal = base::Calendar {
+            first_month,
+            ..Default::default()
+        };
+        assert_eq!(Date::from_str_with(s, cal).unwrap(), want)
+    }
+
+    #[rstest]
+    #[case('Y', 0, "2015-02-28")]
+    #[case('Y', 1, "2016-02-29")]
+    #[case('Y', 2, "2017-02-28")]
+    #[case('y', 2, "2016-03-01")]
+    fn test_relative_fiscal_leap_year(#[case] kind: char, #[case] offset: i32, #[case] want: Date) {
+        let cal = base::Calendar {
+            first_month: base::Month::March,
+            ..Default::default()
+        };
+        let today = Date::from_ymd_unchecked(2014, 6, 15);
+        let got = Spec::relative(kind, offset, today, cal).map(Spec::date);
+        assert_eq!(got.ok(), Some(want));
+    }
+
+    #[rstest]
+    #[case("", "Empty")]
+    #[case("2015-03-3", "BadFormat")]
+    #[case("2015-13", "InvalidMonth")]
+    #[case("y+9999", "OutOfRange")]
+    #[case("D-999999", "OutOfRange")]
+    #[case("a1", "InvalidFirstChar")]
+    #[case("d1.5", "InvalidOffset")]
+    #[case("someday", "UnknownName(\"someday\")")]
+    fn test_from_str_error(#[case] s: &str, #[case] want_variant: &str) {
+        let err = s.parse::<Date>().unwrap_err();
+        let got = format!("{:?}", err);
+        assert!(got.starts_with(want_variant), "{}", got)
+    }
+
+    #[rstest]
+    #[case("2015-03-30", Spec::Day(Date::from_ymd_unchecked(2015, 3, 30)))]
+    #[case("D", Spec::Day(Date::from_ymd_unchecked(2015, 3, 30)))]
+    #[case(
+        "M",
+        Spec::Within(Date::from_ymd_unchecked(2015, 3, 31), base::Datepart::Month)
+    )]
+    #[case(
+        "2015",
+        Spec::Whole(Date::from_ymd_unchecked(2015, 1, 1), base::Datepart::Year)
+    )]
+    #[case(
+        "last-month",
+        Spec::Whole(Date::from_ymd_unchecked(2015, 2, 1), base::Datepart::Month)
+    )]
+    #[case("wed", Spec::Day(Date::from_ymd_unchecked(2015, 3, 25)))]
+    fn test_spec_parse(#[case] s: &str, #[case] want: Spec) {
+        assert_eq!(Spec::parse(s, base::Calendar::default()).unwrap(), want)
+    }
 }
@@ -1,6 +1,14 @@
+/// Unit of time that dates are grouped by.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Datepart {
+    /// Calendar or fiscal year, depending on the calendar in use.
     Year,
+    /// Calendar quarter.
+    Quarter,
+    /// Calendar month.
     Month,
+    /// Week starting on the calendar's first weekday.
+    Week,
+    /// Single day.
     Day,
 }
@@ -0,0 +1,94 @@
+//! Projected records based on past ones.
+
+use crate::base;
+
+/// Note attached to every projected record.
+pub const NOTE: &str = "projected";
+
+/// Projects the monthly totals of each top-level category in `rl` onto every
+/// month starting within `projected`.
+///
+/// A category's projected monthly total is its average monthly total over
+/// `window`, where months without transactions count as zero. Each projection
+/// is dated the first of its month.
+pub fn project(
+    rl: &base::Recordlist,
+    window: base::Interval,
+    projected: base::Interval,
+) -> base::Recordlist {
+    let cal = base::Calendar::default();
+    let months = window
+        .iter(base::Datepart::Month, cal)
+        .map(|interval| {
+            rl.slice_spanning_interval(interval)
+                .iter()
+                .map(|r| (r.category().level(1), r.amount()))
+                .collect::<base::Aggregate<_, _>>()
+        })
+        .collect::<Vec<_>>();
+    let categories = months
+        .iter()
+        .flat_map(|agg| agg.iter().map(|(cat, _)| cat))
+        .collect::<std::collections::BTreeSet<_>>();
+    let averages = categories
+        .into_iter()
+        .filter_map(|cat| {
+            let monthly_totals = months
+                .iter()
+                .map(|agg| agg.get(cat).unwrap_or_default())
+                .collect::<Vec<_>>();
+            let average = base::Stats::new(&monthly_totals, 0)?.average;
+            (average != base::Cents(0)).then_some((cat, average))
+        })
+        .collect::<Vec<_>>();
+
+    let mut out = base::Recordlist::new();
+    for interval in projected.iter(base::Datepart::Month, cal) {
+        for &(cat, average) in &averages {
+            out.insert(base::Record::new(
+                interval.start,
+                cat.parse()
+                    .expect("top-level category should be a valid category"),
+                average,
+                NOTE.to_string(),
+            ));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("", "2015-01-01:2015-02-28", "2015-04-01:2015-05-31", "")]
+    #[case(
+        r#"
+            {"d":"2014-12-31","c":"food","a":-99999}
+            {"d":"2015-01-05","c":"food/groceries","a":-10000}
+            {"d":"2015-01-20","c":"food/restaurant","a":-5000}
+            {"d":"2015-02-10","c":"rent","a":-90000}
+            {"d":"2015-02-15","c":"salary","a":300000}
+            {"d":"2015-02-16","c":"salary","a":-300000}
+            {"d":"2015-04-10","c":"food","a":-99999}
+        "#,
+        "2015-01-01:2015-02-28",
+        "2015-04-01:2015-05-31",
+        r#"
+            {"d":"2015-04-01","c":"food","a":-7500,"n":"projected"}
+            {"d":"2015-04-01","c":"rent","a":-45000,"n":"projected"}
+            {"d":"2015-05-01","c":"food","a":-7500,"n":"projected"}
+            {"d":"2015-05-01","c":"rent","a":-45000,"n":"projected"}
+        "#
+    )]
+    fn test_project(
+        #[case] rl: base::Recordlist,
+        #[case] window: base::Interval,
+        #[case] projected: base::Interval,
+        #[case] want: base::Recordlist,
+    ) {
+        assert_eq!(project(&rl, window, projected), want)
+    }
+}
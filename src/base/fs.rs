@@ -1,3 +1,5 @@
+//! Reading and writing repository files.
+
 use crate::base;
 
 /// Application filesystem.
@@ -7,6 +9,7 @@ pub struct Fs {
 
 /// Marker for types that are serialized to or deserialized from the filesystem.
 pub trait Io: Default + ToString + std::str::FromStr {
+    /// Name of the file within the working directory.
     const FILENAME: &'static str;
 }
 impl Io for base::Config {
@@ -15,11 +18,12 @@ impl Io for base::Config {
 impl Io for base::Recordlist {
     const FILENAME: &'static str = "ledger.jsonl";
 }
-impl Io for base::Limits {
+impl Io for base::Limitaccounts {
     const FILENAME: &'static str = "limits.json";
 }
 
 impl Fs {
+    /// Returns a filesystem anchored at `dir`.
     pub fn new<P>(dir: P) -> Self
     where
         P: Into<std::path::PathBuf>,
@@ -32,6 +36,7 @@ impl Fs {
         &self.dir
     }
 
+    /// Returns whether the working directory has a config file.
     pub fn is_repo(&self) -> bool {
         self.path::<base::Config>().exists()
     }
@@ -63,6 +68,7 @@ impl Fs {
         }
     }
 
+    /// Serializes `obj` to disk, replacing `T`'s file.
     pub fn write<T>(&self, obj: &T) -> std::io::Result<()>
     where
         T: Io,
@@ -71,10 +77,13 @@ impl Fs {
     }
 }
 
+/// Error returned when a file fails to be read.
 #[derive(Debug, thiserror::Error)]
 pub enum ReadError {
+    /// File exists but could not be read.
     #[error(transparent)]
     Io(#[from] std::io::Error),
+    /// File was read but could not be deserialized.
     #[error(transparent)]
     Serde(#[from] Box<dyn std::error::Error + Send + Sync>),
     // This box can be removed once specialization stabilizes.
@@ -99,7 +108,7 @@ mod tests {
 
         let a = fs.path::<base::Config>();
         let b = fs.path::<base::Recordlist>();
-        let c = fs.path::<base::Limits>();
+        let c = fs.path::<base::Limitaccounts>();
         assert_ne!(a, b);
         assert_ne!(b, c);
         assert_ne!(a, c);
@@ -110,14 +119,14 @@ mod tests {
         let (fs, _td) = tempfs();
 
         // Read nonexistent config.
-        assert_eq!(fs.is_repo(), false);
+        assert!(!fs.is_repo());
         assert_eq!(fs.read::<base::Config>().unwrap(), base::Config::default());
 
         // Read config.
         let s = r#"{"unsignedIsNegative": true}"#;
         let config = s.parse::<base::Config>().unwrap();
         std::fs::write(fs.path::<base::Config>(), s).unwrap();
-        assert_eq!(fs.is_repo(), true);
+        assert!(fs.is_repo());
         assert_eq!(fs.read::<base::Config>().unwrap(), config);
 
         // Write config.
@@ -127,12 +136,18 @@ mod tests {
             indoc!(
                 r#"
                 {
+                  "firstDayOfWeek": "monday",
                   "firstIndexInDate": 0,
+                  "fiscalYearStart": "january",
                   "limAccountType": null,
+                  "rrspDeductionMaximums": {},
+                  "rrspFirst60Days": false,
+                  "rrspIncomeCategories": [],
                   "unsignedIsNegative": true,
                   "useColoredOutput": false,
                   "useUnicodeSymbols": false,
-                  "templates": {}
+                  "templates": {},
+                  "aliases": {}
                 }
                 "#
             )
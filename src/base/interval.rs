@@ -1,3 +1,5 @@
+//! Ranges of dates.
+
 use crate::base;
 
 /// Interval defined by the inclusive bound of two dates. If `start` is greater
@@ -5,7 +7,9 @@ use crate::base;
 /// equivalent.
 #[derive(Debug, Clone, Copy, Eq)]
 pub struct Interval {
+    /// First day of the interval.
     pub start: base::Date,
+    /// Last day of the interval.
     pub end: base::Date,
 }
 
@@ -30,15 +34,18 @@ impl Interval {
         end: base::Date::MAX,
     };
 
+    /// Canonical empty interval.
     pub const EMPTY: Self = Self {
         start: base::Date::MAX,
         end: base::Date::MIN,
     };
 
+    /// Returns whether the interval contains no date.
     pub fn is_empty(self) -> bool {
         self.start > self.end
     }
 
+    /// Returns the dates contained in both intervals.
     pub fn intersection(self, other: Self) -> Self {
         Self {
             start: self.start.max(other.start),
@@ -48,14 +55,15 @@ impl Interval {
 
     /// Returns an iterator over subintervals.
     ///
-    /// Subintervals try to span the beginning to the end of calendar
-    /// years/months. For example, iterating by year over \[2000-04-15,
-    /// 2003-08-10] will yield \[2000-04-15, 2000-12-31], \[2001-01-01,
-    /// 2001-12-31], etc.
-    pub fn iter(self, part: base::Datepart) -> impl Iterator<Item = Self> {
+    /// Subintervals try to span the beginning to the end of
+    /// years/quarters/months/weeks as defined by `cal`. For example, iterating
+    /// by calendar year over \[2000-04-15, 2003-08-10] will yield
+    /// \[2000-04-15, 2000-12-31], \[2001-01-01, 2001-12-31], etc.
+    pub fn iter(self, part: base::Datepart, cal: base::Calendar) -> impl Iterator<Item = Self> {
         struct Iter {
             bounds: Interval,
             part: base::Datepart,
+            cal: base::Calendar,
             next: Option<Interval>,
         }
 
@@ -66,8 +74,8 @@ impl Interval {
                 let ret = self.next;
                 if let Some(i) = self.next {
                     self.next = i.start.shift(self.part, 1).and_then(|dt| {
-                        let start = dt.first_of(self.part);
-                        let end = dt.last_of(self.part).min(self.bounds.end);
+                        let start = dt.first_of(self.part, self.cal);
+                        let end = dt.last_of(self.part, self.cal).min(self.bounds.end);
                         if start <= end {
                             Some(Interval { start, end })
                         } else {
@@ -82,11 +90,12 @@ impl Interval {
         Iter {
             bounds: self,
             part,
+            cal,
             next: match self.is_empty() {
                 true => None,
                 false => Some(Self {
                     start: self.start,
-                    end: self.start.last_of(part).min(self.end),
+                    end: self.start.last_of(part, cal).min(self.end),
                 }),
             },
         }
@@ -99,47 +108,58 @@ impl std::fmt::Display for Interval {
     }
 }
 
+/// Error returned when an interval fails to parse.
 #[derive(Debug, thiserror::Error)]
 pub enum ParseError {
+    /// Input without `:` is not a valid date.
     #[error(transparent)]
     Date(#[from] base::date::ParseError),
+    /// Date on the left side of `:` is invalid.
     #[error("invalid left side")]
     Left(#[source] base::date::ParseError),
+    /// Date on the right side of `:` is invalid.
     #[error("invalid right side")]
     Right(#[source] base::date::ParseError),
 }
 
-impl std::str::FromStr for Interval {
-    type Err = ParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
+impl Interval {
+    /// Parses a string to an interval, resolving relative dates with `cal`.
+    /// See [`base::date::Spec::parse`] for accepted date formats. A year or
+    /// month literal on the right side of `:` refers to its last day.
+    pub fn from_str_with(s: &str, cal: base::Calendar) -> Result<Self, ParseError> {
         let (start, end) = match s.split_once(':') {
             Some((left, right)) => (
                 if left.is_empty() {
                     base::Date::MIN
                 } else {
-                    left.parse::<base::Date>().map_err(Self::Err::Left)?
+                    base::date::Spec::parse(left, cal)
+                        .map_err(ParseError::Left)?
+                        .date()
                 },
                 if right.is_empty() {
                     base::Date::MAX
                 } else {
-                    right.parse::<base::Date>().map_err(Self::Err::Right)?
+                    match base::date::Spec::parse(right, cal).map_err(ParseError::Right)? {
+                        spec @ base::date::Spec::Whole(..) => spec.span(cal).1,
+                        spec => spec.date(),
+                    }
                 },
             ),
-            None => {
-                let dt = s.parse::<base::Date>()?;
-                let part = match s.as_bytes()[0] as char {
-                    'y' | 'Y' => base::Datepart::Year,
-                    'm' | 'M' => base::Datepart::Month,
-                    _ => base::Datepart::Day,
-                };
-                (dt.first_of(part), dt.last_of(part))
-            }
+            None => base::date::Spec::parse(s, cal)?.span(cal),
         };
         Ok(Self { start, end })
     }
 }
 
+impl std::str::FromStr for Interval {
+    type Err = ParseError;
+
+    /// Same as [`Interval::from_str_with`] using the default calendar.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_str_with(s, base::Calendar::default())
+    }
+}
+
 impl TryFrom<&str> for Interval {
     type Error = <Self as std::str::FromStr>::Err;
 
@@ -163,10 +183,45 @@ mod tests {
     #[case(":d4", "0000-01-01", "2015-04-03")]
     #[case(":", "0000-01-01", "9999-12-31")]
     #[case("D-10:", "2015-03-20", "9999-12-31")]
+    #[case("q", "2015-01-01", "2015-03-31")]
+    #[case("Q1", "2015-04-01", "2015-06-30")]
+    #[case("w", "2015-03-30", "2015-04-05")]
+    #[case("W-1", "2015-03-23", "2015-03-29")]
+    #[case("w-2:W", "2015-03-16", "2015-04-05")]
+    #[case("2015", "2015-01-01", "2015-12-31")]
+    #[case("2015-02", "2015-02-01", "2015-02-28")]
+    #[case("2014:2015", "2014-01-01", "2015-12-31")]
+    #[case("2015-01:2015-02", "2015-01-01", "2015-02-28")]
+    #[case("2015-02:m", "2015-02-01", "2015-03-01")]
+    #[case("last-month", "2015-02-01", "2015-02-28")]
+    #[case("last-month:", "2015-02-01", "9999-12-31")]
+    #[case("yesterday", "2015-03-29", "2015-03-29")]
+    #[case("fri:today", "2015-03-27", "2015-03-30")]
     fn test_from_str(#[case] s: &str, #[case] start: base::Date, #[case] end: base::Date) {
         assert_eq!(s.parse::<Interval>().unwrap(), Interval { start, end })
     }
 
+    #[rstest]
+    #[case("y", "2014-04-01", "2015-03-31")]
+    #[case("2015", "2015-04-01", "2016-03-31")]
+    #[case("2014:2015", "2014-04-01", "2016-03-31")]
+    #[case("y-1:Y-1", "2013-04-01", "2014-03-31")]
+    #[case("m", "2015-03-01", "2015-03-31")]
+    fn test_from_str_with_fiscal_year(
+        #[case] s: &str,
+        #[case] start: base::Date,
+        #[case] end: base::Date,
+    ) {
+        let cal = base::Calendar {
+            first_month: base::Month::April,
+            ..Default::default()
+        };
+        assert_eq!(
+            Interval::from_str_with(s, cal).unwrap(),
+            Interval { start, end }
+        )
+    }
+
     #[rstest]
     #[case("")]
     #[case(":a")]
@@ -175,6 +230,8 @@ mod tests {
     #[case("d10000000000000000000000000000000000000000000000000000000000000")]
     #[case("12345-01-01")]
     #[case("12345-01-01:")]
+    #[case("2015-13")]
+    #[case("2015:nextweek")]
     fn test_from_str_failing(#[case] s: &str) {
         assert!(s.parse::<Interval>().is_err())
     }
@@ -207,6 +264,17 @@ mod tests {
         "2015-04-01:2015-04-30".parse().unwrap(),
         "2015-05-01:2015-05-29".parse().unwrap(),
     ])]
+    #[case("2015-03-30:2015-11-15", base::Datepart::Quarter, &[
+        "2015-03-30:2015-03-31".parse().unwrap(),
+        "2015-04-01:2015-06-30".parse().unwrap(),
+        "2015-07-01:2015-09-30".parse().unwrap(),
+        "2015-10-01:2015-11-15".parse().unwrap(),
+    ])]
+    #[case("2015-03-28:2015-04-08", base::Datepart::Week, &[
+        "2015-03-28:2015-03-29".parse().unwrap(),
+        "2015-03-30:2015-04-05".parse().unwrap(),
+        "2015-04-06:2015-04-08".parse().unwrap(),
+    ])]
     #[case("2015-03-30", base::Datepart::Day, &[
         "2015-03-30:2015-03-30".parse().unwrap(),
     ])]
@@ -220,7 +288,9 @@ mod tests {
         #[case] part: base::Datepart,
         #[case] want: &[Interval],
     ) {
-        let got = bounds.iter(part).collect::<Vec<_>>();
+        let got = bounds
+            .iter(part, base::Calendar::default())
+            .collect::<Vec<_>>();
         assert_eq!(got.as_slice(), want)
     }
 }
@@ -0,0 +1,188 @@
+use crate::base;
+
+/// Contents of the limits file.
+///
+/// The file either holds the yearly limits of a single account, whose
+/// contributions and withdrawals are all transactions in the ledger, or several
+/// named accounts that each select their transactions by category.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+#[serde(untagged)]
+pub enum Limitaccounts {
+    /// Limits of the single account, whose type is set in the config.
+    Single(base::Limits),
+    /// Accounts by name.
+    Named(std::collections::BTreeMap<String, Limitaccount>),
+}
+
+/// Named account within the limits file.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Limitaccount {
+    /// Account type, which determines how its room is calculated.
+    pub kind: base::Limitkind,
+    /// Wildcard pattern to match categories of contributions and withdrawals.
+    pub category: String,
+    /// Yearly limits of the account.
+    #[serde(default)]
+    pub limits: base::Limits,
+}
+
+impl Limitaccount {
+    /// Returns whether transactions in `category` belong to this account.
+    pub fn matches(&self, category: &base::Category) -> bool {
+        wildmatch::WildMatch::new(&self.category).matches(category.as_str())
+    }
+
+    /// Returns transactions in `rl` that belong to this account.
+    pub fn filter(&self, rl: &base::Recordlist) -> base::Recordlist {
+        let pattern = wildmatch::WildMatch::new(&self.category);
+        rl.iter()
+            .filter(|r| pattern.matches(r.category().as_str()))
+            .collect()
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Limitaccounts {
+    /// An empty object is a single account without limits. Otherwise, the
+    /// object holds named accounts if all of its values are objects.
+    //
+    // `#[serde(untagged)]` cannot be derived here, as it loses the ability to
+    // deserialize the years of `base::Limits` from string keys.
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let map = serde_json::Map::<String, serde_json::Value>::deserialize(deserializer)?;
+        let is_named = !map.is_empty() && map.values().all(serde_json::Value::is_object);
+        let value = serde_json::Value::Object(map);
+        if is_named {
+            serde_json::from_value(value)
+                .map(Self::Named)
+                .map_err(serde::de::Error::custom)
+        } else {
+            serde_json::from_value(value)
+                .map(Self::Single)
+                .map_err(serde::de::Error::custom)
+        }
+    }
+}
+
+impl Default for Limitaccounts {
+    fn default() -> Self {
+        Self::Single(base::Limits::default())
+    }
+}
+
+impl From<base::Limits> for Limitaccounts {
+    fn from(value: base::Limits) -> Self {
+        Self::Single(value)
+    }
+}
+
+impl std::fmt::Display for Limitaccounts {
+    /// Writes a terminating newline.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = serde_json::to_string_pretty(self).map_err(|_| std::fmt::Error)?;
+        writeln!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for Limitaccounts {
+    type Err = serde_json::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        serde_json::from_str(s)
+    }
+}
+
+impl TryFrom<&str> for Limitaccounts {
+    type Error = <Self as std::str::FromStr>::Err;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse::<Self>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(indoc!("{}\n"), Limitaccounts::Single(base::Limits::new()))]
+    #[case(
+        indoc!(r#"
+        {
+          "2015": 5000
+        }
+        "#),
+        Limitaccounts::Single(r#"{"2015": 5000}"#.parse().unwrap()),
+    )]
+    #[case(
+        indoc!(r#"
+        {
+          "retirement": {
+            "kind": "rrsp",
+            "category": "invest/rrsp*",
+            "limits": {
+              "2015": 5000
+            }
+          },
+          "savings": {
+            "kind": "tfsa",
+            "category": "invest/tfsa",
+            "limits": {}
+          }
+        }
+        "#),
+        Limitaccounts::Named([
+            (
+                "retirement".to_string(),
+                Limitaccount {
+                    kind: base::Limitkind::Rrsp,
+                    category: "invest/rrsp*".to_string(),
+                    limits: r#"{"2015": 5000}"#.parse().unwrap(),
+                },
+            ),
+            (
+                "savings".to_string(),
+                Limitaccount {
+                    kind: base::Limitkind::Tfsa,
+                    category: "invest/tfsa".to_string(),
+                    limits: base::Limits::new(),
+                },
+            ),
+        ].into()),
+    )]
+    fn test_serde(#[case] s: &str, #[case] want: Limitaccounts) {
+        let got = s.parse::<Limitaccounts>().unwrap();
+        assert_eq!(got, want);
+        assert_eq!(got.to_string(), s);
+    }
+
+    #[test]
+    fn test_filter() {
+        let account = Limitaccount {
+            kind: base::Limitkind::Tfsa,
+            category: "invest/tfsa*".to_string(),
+            limits: base::Limits::new(),
+        };
+        let rl = r#"
+            {"d":"2015-01-01","c":"invest/rrsp","a":100}
+            {"d":"2015-01-01","c":"invest/tfsa","a":200}
+            {"d":"2015-01-02","c":"invest/tfsa/cash","a":-300}
+        "#
+        .parse()
+        .unwrap();
+        assert_eq!(
+            account.filter(&rl),
+            r#"
+                {"d":"2015-01-01","c":"invest/tfsa","a":200}
+                {"d":"2015-01-02","c":"invest/tfsa/cash","a":-300}
+            "#
+            .parse()
+            .unwrap()
+        );
+    }
+}
@@ -1,67 +1,428 @@
 use crate::base;
 
+/// Type of registered account, which determines how contribution room is
+/// calculated.
 #[derive(
-    Debug,
-    Clone,
-    Copy,
-    PartialEq,
-    Eq,
-    serde::Serialize,
-    serde::Deserialize,
-    strum::EnumString,
-    strum::Display,
-    strum::AsRefStr,
-    strum::IntoStaticStr,
-    strum::VariantNames,
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, strum::EnumString,
 )]
 #[serde(rename_all = "lowercase")]
 #[strum(ascii_case_insensitive, serialize_all = "lowercase")]
 pub enum Limitkind {
+    /// Registered retirement savings plan.
     Rrsp,
+    /// Tax-free savings account.
     Tfsa,
+    /// First home savings account.
+    Fhsa,
+    /// Registered education savings plan.
+    Resp,
+    /// User-defined rules, given as an object instead of a name.
+    #[serde(untagged)]
+    #[strum(disabled)]
+    Custom(base::Limitrule),
 }
 
+impl std::fmt::Display for Limitkind {
+    /// Writes the name of the account type, or `custom` for user-defined rules.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name().unwrap_or("custom"))
+    }
+}
+
+/// Published annual TFSA dollar limits.
+const TFSA_OFFICIAL_LIMITS: &[(u16, base::Cents)] = &[
+    (2009, base::Cents(500000)),
+    (2010, base::Cents(500000)),
+    (2011, base::Cents(500000)),
+    (2012, base::Cents(500000)),
+    (2013, base::Cents(550000)),
+    (2014, base::Cents(550000)),
+    (2015, base::Cents(1000000)),
+    (2016, base::Cents(550000)),
+    (2017, base::Cents(550000)),
+    (2018, base::Cents(550000)),
+    (2019, base::Cents(600000)),
+    (2020, base::Cents(600000)),
+    (2021, base::Cents(600000)),
+    (2022, base::Cents(600000)),
+    (2023, base::Cents(650000)),
+    (2024, base::Cents(700000)),
+    (2025, base::Cents(700000)),
+    (2026, base::Cents(700000)),
+];
+/// Published annual RRSP dollar limits, i.e. the maximum deduction limit
+/// accrued in a year.
+const RRSP_OFFICIAL_LIMITS: &[(u16, base::Cents)] = &[
+    (2003, base::Cents(1450000)),
+    (2004, base::Cents(1550000)),
+    (2005, base::Cents(1650000)),
+    (2006, base::Cents(1800000)),
+    (2007, base::Cents(1900000)),
+    (2008, base::Cents(2000000)),
+    (2009, base::Cents(2100000)),
+    (2010, base::Cents(2200000)),
+    (2011, base::Cents(2245000)),
+    (2012, base::Cents(2297000)),
+    (2013, base::Cents(2382000)),
+    (2014, base::Cents(2427000)),
+    (2015, base::Cents(2493000)),
+    (2016, base::Cents(2537000)),
+    (2017, base::Cents(2601000)),
+    (2018, base::Cents(2623000)),
+    (2019, base::Cents(2650000)),
+    (2020, base::Cents(2723000)),
+    (2021, base::Cents(2783000)),
+    (2022, base::Cents(2921000)),
+    (2023, base::Cents(3078000)),
+    (2024, base::Cents(3156000)),
+    (2025, base::Cents(3249000)),
+    (2026, base::Cents(3381000)),
+];
+/// Penalty per month on the highest excess TFSA contribution in that month.
+const TFSA_PENALTY_PERCENT_PER_MONTH: i64 = 1;
+/// Most unused FHSA room that carries forward into the next year.
+const FHSA_CARRY_FORWARD_MAX: base::Cents = base::Cents(800000);
+/// Most FHSA contributions over the account's lifetime.
+const FHSA_LIFETIME_MAX: base::Cents = base::Cents(4000000);
+/// Most RESP contributions over the beneficiary's lifetime.
+const RESP_LIFETIME_MAX: base::Cents = base::Cents(5000000);
+/// Grant-eligible RESP contribution room accrued each year.
+const RESP_GRANT_ROOM_PER_YEAR: base::Cents = base::Cents(250000);
+/// Most RESP contributions eligible for the grant in a single year.
+const RESP_GRANT_ELIGIBLE_PER_YEAR_MAX: base::Cents = base::Cents(500000);
+/// Most RESP contributions eligible for the grant over the beneficiary's
+/// lifetime.
+const RESP_GRANT_ELIGIBLE_LIFETIME_MAX: base::Cents = base::Cents(3600000);
+
+/// Number of days at the start of a year in which RRSP contributions may be
+/// attributed to the previous year.
+const RRSP_FIRST_DAYS: u16 = 60;
+
 impl Limitkind {
-    pub fn remaining(self, limits: &base::Limits, rl: &base::Recordlist, year: u16) -> base::Cents {
+    /// Account types with built-in rules, which are the ones that have names.
+    pub const BUILTIN: [Self; 4] = [Self::Rrsp, Self::Tfsa, Self::Fhsa, Self::Resp];
+
+    /// Returns the name that parses to this kind of account, or `None` for
+    /// user-defined rules.
+    pub fn name(self) -> Option<&'static str> {
         match self {
-            Limitkind::Rrsp => Self::remaining_rrsp(limits, rl, year),
-            Limitkind::Tfsa => Self::remaining_tfsa(limits, rl, year),
+            Limitkind::Rrsp => Some("rrsp"),
+            Limitkind::Tfsa => Some("tfsa"),
+            Limitkind::Fhsa => Some("fhsa"),
+            Limitkind::Resp => Some("resp"),
+            Limitkind::Custom(_) => None,
         }
     }
 
-    fn remaining_rrsp(limits: &base::Limits, rl: &base::Recordlist, year: u16) -> base::Cents {
-        let contributions = rl
-            .iter()
-            .map(|r| match r.date().year() <= year && r.amount().0 > 0 {
-                true => r.amount(),
-                false => base::Cents(0),
-            })
-            .sum();
-        limits.inception_to_year(year) - contributions
+    /// If `rrsp_first_60_days` is set, RRSP contributions made in the first 60
+    /// days of a year count towards the previous year.
+    pub fn remaining(
+        self,
+        limits: &base::Limits,
+        rl: &base::Recordlist,
+        year: u16,
+        rrsp_first_60_days: bool,
+    ) -> base::Cents {
+        match self {
+            Limitkind::Fhsa => Self::fhsa(limits, rl, year).remaining,
+            Limitkind::Resp => Self::resp(limits, rl, year).remaining,
+            _ => self
+                .rule(rrsp_first_60_days)
+                .expect("account type should have rules")
+                .remaining(limits, rl, year),
+        }
     }
 
-    fn remaining_tfsa(limits: &base::Limits, rl: &base::Recordlist, year: u16) -> base::Cents {
-        let contributions = rl
-            .iter()
-            .map(|r| match r.date().year() <= year && r.amount().0 > 0 {
-                true => r.amount(),
-                false => base::Cents(0),
-            })
-            .sum();
-        let withdrawals_before_year = rl
-            .iter()
-            .map(|r| match r.date().year() < year && r.amount().0 < 0 {
-                true => -r.amount(),
-                false => base::Cents(0),
+    /// Returns the rules of this kind of account, unless they can't be
+    /// expressed declaratively. See [`Self::remaining`] for
+    /// `rrsp_first_60_days`.
+    pub fn rule(self, rrsp_first_60_days: bool) -> Option<base::Limitrule> {
+        match self {
+            Limitkind::Rrsp if rrsp_first_60_days => Some(base::Limitrule {
+                prior_year_days: RRSP_FIRST_DAYS,
+                ..base::Limitrule::RRSP
+            }),
+            Limitkind::Rrsp => Some(base::Limitrule::RRSP),
+            Limitkind::Tfsa => Some(base::Limitrule::TFSA),
+            Limitkind::Fhsa | Limitkind::Resp => None,
+            Limitkind::Custom(rule) => Some(rule),
+        }
+    }
+
+    /// Returns the published yearly limit for `year`, if this kind of account
+    /// has one and it is known. For RRSPs, this is the dollar limit that caps
+    /// the room accrued from earned income.
+    pub fn official_limit(self, year: u16) -> Option<base::Cents> {
+        let table = match self {
+            Limitkind::Rrsp => RRSP_OFFICIAL_LIMITS,
+            Limitkind::Tfsa => TFSA_OFFICIAL_LIMITS,
+            Limitkind::Fhsa | Limitkind::Resp | Limitkind::Custom(_) => return None,
+        };
+        table
+            .binary_search_by_key(&year, |&(y, _)| y)
+            .ok()
+            .map(|i| table[i].1)
+    }
+
+    /// Returns labeled amounts, besides the total and remaining limits, that
+    /// are specific to this kind of account. If `rrsp_first_60_days` is set,
+    /// the RRSP contributions that count towards `year` are split into those
+    /// made in the rest of `year` and those made in the first 60 days of the
+    /// next year.
+    pub fn details(
+        self,
+        limits: &base::Limits,
+        rl: &base::Recordlist,
+        year: u16,
+        rrsp_first_60_days: bool,
+    ) -> Vec<(String, base::Cents)> {
+        match self {
+            Limitkind::Rrsp if !rrsp_first_60_days => vec![],
+            Limitkind::Rrsp => {
+                let rule = self
+                    .rule(rrsp_first_60_days)
+                    .expect("RRSPs should have rules");
+                let (rest, next_year) = rl
+                    .iter()
+                    .filter(|r| r.amount().0 > 0 && rule.attributed_year(r.date()) == year)
+                    .partition::<Vec<_>, _>(|r| r.date().year() == year);
+                vec![
+                    (
+                        format!("Rest of {:0>4}", year),
+                        rest.iter().map(|r| r.amount()).sum(),
+                    ),
+                    (
+                        format!("First 60 days of {:0>4}", year + 1),
+                        next_year.iter().map(|r| r.amount()).sum(),
+                    ),
+                ]
+            }
+            Limitkind::Tfsa => {
+                let end = base::Date::today()
+                    .min(base::Date::from_ymd(year, 12, 31).expect("year should be in range"));
+                let excesses = Self::tfsa_excesses(limits, rl, end)
+                    .into_iter()
+                    .filter(|(month, _)| month.year() == year)
+                    .collect::<Vec<_>>();
+                if excesses.is_empty() {
+                    return vec![];
+                }
+                let penalty = excesses
+                    .iter()
+                    .map(|&(_, excess)| tfsa_penalty(excess))
+                    .sum();
+                excesses
+                    .into_iter()
+                    .map(|(month, excess)| (format!("Excess in {}", month_label(month)), excess))
+                    .chain(std::iter::once(("Penalty estimate".into(), penalty)))
+                    .collect()
+            }
+            Limitkind::Fhsa => {
+                let fhsa = Self::fhsa(limits, rl, year);
+                vec![
+                    ("Carry-forward".into(), fhsa.carry_forward),
+                    ("Lifetime used".into(), fhsa.lifetime_used),
+                ]
+            }
+            Limitkind::Resp => {
+                let resp = Self::resp(limits, rl, year);
+                vec![
+                    ("Lifetime used".into(), resp.lifetime_used),
+                    ("Grant-eligible".into(), resp.grant_eligible),
+                ]
+            }
+            Limitkind::Custom(_) => vec![],
+        }
+    }
+
+    /// Returns a breakdown of every year from the first year with a limit or a
+    /// transaction up to and including `year`. See [`Self::remaining`] for
+    /// `rrsp_first_60_days`.
+    pub fn history(
+        self,
+        limits: &base::Limits,
+        rl: &base::Recordlist,
+        year: u16,
+        rrsp_first_60_days: bool,
+    ) -> Vec<Yearhistory> {
+        let rule = self.rule(rrsp_first_60_days);
+        (first_year(limits, rl, year)..=year)
+            .map(|y| Yearhistory {
+                year: y,
+                new_room: limits.get(y).unwrap_or_default(),
+                contributions: rule.map_or_else(
+                    || contributions_in_year(rl, y),
+                    |rule| rule.contributions_in_year(rl, y),
+                ),
+                withdrawals: withdrawals_in_year(rl, y),
+                restored: rule.map_or(base::Cents(0), |rule| rule.restored_in_year(rl, y)),
+                room: self.remaining(limits, rl, y, rrsp_first_60_days),
             })
-            .sum();
-        limits.inception_to_year(year) - contributions + withdrawals_before_year
+            .collect()
+    }
+
+    /// Walks `rl` month by month up to and including the month of `end`, and
+    /// returns the first day and highest excess contribution of every month
+    /// in which TFSA room was exceeded.
+    ///
+    /// Withdrawals first reduce any excess. The rest of a withdrawal is only
+    /// restored as room at the start of the following year.
+    pub fn tfsa_excesses(
+        limits: &base::Limits,
+        rl: &base::Recordlist,
+        end: base::Date,
+    ) -> Vec<(base::Date, base::Cents)> {
+        let cal = base::Calendar::default();
+        let first_year = first_year(limits, rl, end.year());
+        let Some(start) = base::Date::from_ymd(first_year, 1, 1) else {
+            return vec![];
+        };
+        let mut room = base::Cents(0);
+        let mut restorable = base::Cents(0);
+        let mut excesses = Vec::new();
+        for month in (base::Interval { start, end }).iter(base::Datepart::Month, cal) {
+            if month.start.month() == 1 {
+                let year = month.start.year();
+                room += limits.get(year).unwrap_or_default() + restorable;
+                restorable = base::Cents(0);
+            }
+            let mut highest = -room;
+            for r in rl.slice_spanning_interval(month) {
+                let amount = r.amount();
+                if amount.0 > 0 {
+                    room -= amount;
+                    highest = highest.max(-room);
+                } else {
+                    let reduction = (-amount).min((-room).max(base::Cents(0)));
+                    room += reduction;
+                    restorable += -amount - reduction;
+                }
+            }
+            if highest.0 > 0 {
+                excesses.push((month.start, highest));
+            }
+        }
+        excesses
+    }
+
+    /// Yearly limits are the annual FHSA room for each year the account is
+    /// open. Unused room carries forward, up to a maximum, and contributions
+    /// are capped over the account's lifetime. Withdrawals never restore room.
+    fn fhsa(limits: &base::Limits, rl: &base::Recordlist, year: u16) -> Fhsa {
+        let mut fhsa = Fhsa::default();
+        for y in first_year(limits, rl, year)..=year {
+            let room = limits.get(y).unwrap_or_default() + fhsa.carry_forward;
+            let contributions = contributions_in_year(rl, y);
+            fhsa.lifetime_used += contributions;
+            if y == year {
+                fhsa.remaining = (room - contributions).min(FHSA_LIFETIME_MAX - fhsa.lifetime_used);
+            } else {
+                fhsa.carry_forward =
+                    (room - contributions).clamp(base::Cents(0), FHSA_CARRY_FORWARD_MAX);
+            }
+        }
+        fhsa
+    }
+
+    /// Yearly limits are not used. Contributions are capped over the
+    /// beneficiary's lifetime, and a portion of each year's contributions is
+    /// eligible for the education savings grant. Unused grant-eligible room
+    /// accrues every year starting from the first year with a limit or a
+    /// transaction.
+    fn resp(limits: &base::Limits, rl: &base::Recordlist, year: u16) -> Resp {
+        let mut resp = Resp::default();
+        let mut grant_room = base::Cents(0);
+        let mut grant_eligible_used = base::Cents(0);
+        for y in first_year(limits, rl, year)..=year {
+            grant_room += RESP_GRANT_ROOM_PER_YEAR;
+            let grant_eligible_max = grant_room
+                .min(RESP_GRANT_ELIGIBLE_PER_YEAR_MAX)
+                .min(RESP_GRANT_ELIGIBLE_LIFETIME_MAX - grant_eligible_used);
+            let contributions = contributions_in_year(rl, y);
+            let grant_eligible = contributions.min(grant_eligible_max);
+            grant_room -= grant_eligible;
+            grant_eligible_used += grant_eligible;
+            resp.lifetime_used += contributions;
+            if y == year {
+                resp.grant_eligible = grant_eligible_max - grant_eligible;
+            }
+        }
+        resp.remaining = RESP_LIFETIME_MAX - resp.lifetime_used;
+        resp
     }
 }
 
+/// Room and transactions of a single year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Yearhistory {
+    pub year: u16,
+    /// Yearly limit.
+    pub new_room: base::Cents,
+    pub contributions: base::Cents,
+    /// Withdrawals, as a positive amount.
+    pub withdrawals: base::Cents,
+    /// Withdrawals in the previous year that are restored as room this year.
+    pub restored: base::Cents,
+    /// Remaining room at the end of the year.
+    pub room: base::Cents,
+}
+
+#[derive(Default)]
+struct Fhsa {
+    /// Unused room carried forward into the year of interest.
+    carry_forward: base::Cents,
+    lifetime_used: base::Cents,
+    remaining: base::Cents,
+}
+
+#[derive(Default)]
+struct Resp {
+    lifetime_used: base::Cents,
+    /// Contributions that may still be made in the year of interest and be
+    /// eligible for the grant.
+    grant_eligible: base::Cents,
+    remaining: base::Cents,
+}
+
+/// Penalty on the highest excess TFSA contribution in a month.
+fn tfsa_penalty(excess: base::Cents) -> base::Cents {
+    base::Cents((excess.0 * TFSA_PENALTY_PERCENT_PER_MONTH + 50).div_euclid(100))
+}
+
+/// Formats the month of `dt`, e.g. `2015 Mar`.
+fn month_label(dt: base::Date) -> String {
+    dt.format(time::macros::format_description!(
+        "[year] [month repr:short]"
+    ))
+    .expect("formatting should succeed")
+}
+
+/// Returns the earliest year with a limit or a transaction, but no later than
+/// `year`.
+pub(super) fn first_year(limits: &base::Limits, rl: &base::Recordlist, year: u16) -> u16 {
+    let first_limit_year = limits.range(..).next().map_or(year, |(y, _)| y);
+    let first_record_year = rl.iter().next().map_or(year, |r| r.date().year());
+    year.min(first_limit_year).min(first_record_year)
+}
+
+fn contributions_in_year(rl: &base::Recordlist, year: u16) -> base::Cents {
+    rl.iter()
+        .filter(|r| r.date().year() == year && r.amount().0 > 0)
+        .map(|r| r.amount())
+        .sum()
+}
+
+pub(super) fn withdrawals_in_year(rl: &base::Recordlist, year: u16) -> base::Cents {
+    rl.iter()
+        .filter(|r| r.date().year() == year && r.amount().0 < 0)
+        .map(|r| -r.amount())
+        .sum()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rstest::fixture;
     use rstest::rstest;
 
     #[rstest]
@@ -170,12 +531,327 @@ mod tests {
         #[case] want_tfsa: base::Cents,
     ) {
         assert_eq!(
-            Limitkind::Rrsp.remaining(&limits, &rl, current_year),
+            Limitkind::Rrsp.remaining(&limits, &rl, current_year, false),
             want_rrsp
         );
         assert_eq!(
-            Limitkind::Tfsa.remaining(&limits, &rl, current_year),
+            Limitkind::Tfsa.remaining(&limits, &rl, current_year, false),
             want_tfsa
         );
     }
+
+    #[rstest]
+    #[case::no_limits("{}", "", 2023, [0, 0, 0])]
+    #[case::first_year(
+        r#"{"2023": 800000}"#,
+        r#"{"d":"2023-05-01","c":"aaa","a":300000}"#,
+        2023,
+        [0, 300000, 500000],
+    )]
+    #[case::carry_forward_is_capped(
+        r#"{"2023": 800000, "2024": 800000, "2025": 800000}"#,
+        r#"{"d":"2023-05-01","c":"aaa","a":-100000}"#,
+        2025,
+        [800000, 0, 1600000],
+    )]
+    #[case::unused_room_carries_forward(
+        r#"{"2023": 800000, "2024": 800000}"#,
+        r#"
+            {"d":"2023-05-01","c":"aaa","a":300000}
+            {"d":"2024-05-01","c":"aaa","a":100000}
+        "#,
+        2024,
+        [500000, 400000, 1200000],
+    )]
+    #[case::lifetime_cap(
+        r#"{"2023": 4000000, "2024": 800000}"#,
+        r#"{"d":"2023-05-01","c":"aaa","a":3500000}"#,
+        2024,
+        [500000, 3500000, 500000],
+    )]
+    fn test_fhsa(
+        #[case] limits: base::Limits,
+        #[case] rl: base::Recordlist,
+        #[case] year: u16,
+        #[case] want: [i64; 3],
+    ) {
+        let [carry_forward, lifetime_used, remaining] = want;
+        assert_eq!(
+            Limitkind::Fhsa.details(&limits, &rl, year, false),
+            vec![
+                ("Carry-forward".to_string(), base::Cents(carry_forward)),
+                ("Lifetime used".to_string(), base::Cents(lifetime_used)),
+            ]
+        );
+        assert_eq!(
+            Limitkind::Fhsa.remaining(&limits, &rl, year, false),
+            base::Cents(remaining)
+        );
+    }
+
+    #[rstest]
+    #[case::no_contributions("", 2015, [0, 250000, 5000000])]
+    #[case::partially_eligible(
+        r#"{"d":"2015-05-01","c":"aaa","a":100000}"#,
+        2015,
+        [100000, 150000, 4900000],
+    )]
+    #[case::unused_room_carries_forward(
+        r#"
+            {"d":"2013-05-01","c":"aaa","a":0}
+            {"d":"2015-05-01","c":"aaa","a":100000}
+        "#,
+        2015,
+        [100000, 400000, 4900000],
+    )]
+    #[case::yearly_eligible_max(
+        r#"
+            {"d":"2010-05-01","c":"aaa","a":0}
+            {"d":"2015-05-01","c":"aaa","a":200000}
+        "#,
+        2015,
+        [200000, 300000, 4800000],
+    )]
+    #[case::withdrawals_do_not_restore(
+        r#"
+            {"d":"2014-05-01","c":"aaa","a":5500000}
+            {"d":"2015-05-01","c":"aaa","a":-1000000}
+        "#,
+        2015,
+        [5500000, 250000, -500000],
+    )]
+    fn test_resp(#[case] rl: base::Recordlist, #[case] year: u16, #[case] want: [i64; 3]) {
+        let [lifetime_used, grant_eligible, remaining] = want;
+        let limits = base::Limits::new();
+        assert_eq!(
+            Limitkind::Resp.details(&limits, &rl, year, false),
+            vec![
+                ("Lifetime used".to_string(), base::Cents(lifetime_used)),
+                ("Grant-eligible".to_string(), base::Cents(grant_eligible)),
+            ]
+        );
+        assert_eq!(
+            Limitkind::Resp.remaining(&limits, &rl, year, false),
+            base::Cents(remaining)
+        );
+    }
+
+    #[fixture]
+    fn rl_around_first_60_days() -> base::Recordlist {
+        r#"
+            {"d":"2014-01-01","c":"aaa","a":1000}
+            {"d":"2015-01-01","c":"aaa","a":2000}
+            {"d":"2015-03-01","c":"aaa","a":4000}
+            {"d":"2015-03-02","c":"aaa","a":8000}
+            {"d":"2015-03-02","c":"aaa","a":-16000}
+        "#
+        .parse()
+        .unwrap()
+    }
+
+    #[rstest]
+    #[case::calendar_year(2014, false, 100000 - 1000)]
+    #[case::first_60_days_of_next_year(2014, true, 100000 - 1000 - 2000 - 4000)]
+    #[case::rest_of_year(2015, true, 200000 - 1000 - 2000 - 4000 - 8000)]
+    fn test_remaining_rrsp_first_60_days(
+        rl_around_first_60_days: base::Recordlist,
+        #[case] year: u16,
+        #[case] first_60_days: bool,
+        #[case] want: i64,
+    ) {
+        let limits = r#"{"2014": 100000, "2015": 100000}"#.parse().unwrap();
+        assert_eq!(
+            Limitkind::Rrsp.remaining(&limits, &rl_around_first_60_days, year, first_60_days),
+            base::Cents(want)
+        );
+    }
+
+    #[rstest]
+    fn test_details_rrsp(rl_around_first_60_days: base::Recordlist) {
+        let limits = base::Limits::new();
+        assert_eq!(
+            Limitkind::Rrsp.details(&limits, &rl_around_first_60_days, 2015, false),
+            vec![]
+        );
+        assert_eq!(
+            Limitkind::Rrsp.details(&limits, &rl_around_first_60_days, 2014, true),
+            vec![
+                ("Rest of 2014".to_string(), base::Cents(0)),
+                (
+                    "First 60 days of 2015".to_string(),
+                    base::Cents(2000 + 4000)
+                ),
+            ]
+        );
+        assert_eq!(
+            Limitkind::Rrsp.details(&limits, &rl_around_first_60_days, 2015, true),
+            vec![
+                ("Rest of 2015".to_string(), base::Cents(8000)),
+                ("First 60 days of 2016".to_string(), base::Cents(0)),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case::within_room(
+        r#"{"d":"2015-02-01","c":"aaa","a":100000}"#,
+        "2015-12-31",
+        vec![],
+    )]
+    #[case::excess_persists(
+        r#"{"d":"2015-02-01","c":"aaa","a":120000}"#,
+        "2015-04-30",
+        vec![("2015-02-01", 20000), ("2015-03-01", 20000), ("2015-04-01", 20000)],
+    )]
+    #[case::new_year_room_eliminates_excess(
+        r#"{"d":"2015-11-01","c":"aaa","a":120000}"#,
+        "2016-02-29",
+        vec![("2015-11-01", 20000), ("2015-12-01", 20000)],
+    )]
+    #[case::withdrawal_reduces_excess(
+        r#"
+            {"d":"2015-02-01","c":"aaa","a":150000}
+            {"d":"2015-02-20","c":"aaa","a":-30000}
+            {"d":"2015-03-10","c":"aaa","a":-30000}
+        "#,
+        "2015-04-30",
+        vec![("2015-02-01", 50000), ("2015-03-01", 20000)],
+    )]
+    #[case::withdrawal_is_not_restored_within_year(
+        r#"
+            {"d":"2015-02-01","c":"aaa","a":100000}
+            {"d":"2015-03-01","c":"aaa","a":-50000}
+            {"d":"2015-04-01","c":"aaa","a":10000}
+        "#,
+        "2015-04-30",
+        vec![("2015-04-01", 10000)],
+    )]
+    fn test_tfsa_excesses(
+        #[case] rl: base::Recordlist,
+        #[case] end: base::Date,
+        #[case] want: Vec<(&str, i64)>,
+    ) {
+        let limits = r#"{"2015": 100000, "2016": 100000}"#.parse().unwrap();
+        let want = want
+            .into_iter()
+            .map(|(dt, excess)| (dt.parse().unwrap(), base::Cents(excess)))
+            .collect::<Vec<_>>();
+        assert_eq!(Limitkind::tfsa_excesses(&limits, &rl, end), want);
+    }
+
+    #[test]
+    fn test_details_tfsa() {
+        let limits = r#"{"2014": 100000}"#.parse().unwrap();
+        let rl = r#"
+            {"d":"2014-11-15","c":"aaa","a":150000}
+            {"d":"2014-12-15","c":"aaa","a":-20000}
+        "#
+        .parse()
+        .unwrap();
+        assert_eq!(
+            Limitkind::Tfsa.details(&limits, &rl, 2014, false),
+            vec![
+                ("Excess in 2014 Nov".to_string(), base::Cents(50000)),
+                ("Excess in 2014 Dec".to_string(), base::Cents(50000)),
+                ("Penalty estimate".to_string(), base::Cents(1000)),
+            ]
+        );
+        // The excess persists until today if it's never eliminated.
+        assert_eq!(
+            Limitkind::Tfsa.details(&limits, &rl, 2015, false),
+            vec![
+                ("Excess in 2015 Jan".to_string(), base::Cents(30000)),
+                ("Excess in 2015 Feb".to_string(), base::Cents(30000)),
+                ("Excess in 2015 Mar".to_string(), base::Cents(30000)),
+                ("Penalty estimate".to_string(), base::Cents(900)),
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case::tfsa(Limitkind::Tfsa, false, [[100000, 50000, 20000, 0, 50000], [200000, 30000, 0, 20000, 240000]])]
+    #[case::rrsp(Limitkind::Rrsp, false, [[100000, 50000, 20000, 0, 50000], [200000, 30000, 0, 0, 220000]])]
+    #[case::rrsp_first_60_days(
+        Limitkind::Rrsp,
+        true,
+        [[100000, 80000, 20000, 0, 20000], [200000, 0, 0, 0, 220000]],
+    )]
+    fn test_history(
+        #[case] kind: Limitkind,
+        #[case] rrsp_first_60_days: bool,
+        #[case] want: [[i64; 5]; 2],
+    ) {
+        let limits = r#"{"2014": 100000, "2015": 200000}"#.parse().unwrap();
+        let rl = r#"
+            {"d":"2014-03-15","c":"aaa","a":50000}
+            {"d":"2014-06-15","c":"aaa","a":-20000}
+            {"d":"2015-01-15","c":"aaa","a":30000}
+        "#
+        .parse()
+        .unwrap();
+        let want = [2014, 2015]
+            .into_iter()
+            .zip(want)
+            .map(
+                |(year, [new_room, contributions, withdrawals, restored, room])| Yearhistory {
+                    year,
+                    new_room: base::Cents(new_room),
+                    contributions: base::Cents(contributions),
+                    withdrawals: base::Cents(withdrawals),
+                    restored: base::Cents(restored),
+                    room: base::Cents(room),
+                },
+            )
+            .collect::<Vec<_>>();
+        assert_eq!(kind.history(&limits, &rl, 2015, rrsp_first_60_days), want);
+    }
+
+    #[rstest]
+    #[case(r#""tfsa""#, Limitkind::Tfsa)]
+    #[case(
+        r#"{"withdrawals": "immediately", "carryForwardMax": 100}"#,
+        Limitkind::Custom(base::Limitrule {
+            withdrawals: base::limitrule::Restoration::Immediately,
+            carry_forward_max: Some(base::Cents(100)),
+            ..base::Limitrule::RRSP
+        }),
+    )]
+    fn test_deserialize(#[case] s: &str, #[case] want: Limitkind) {
+        assert_eq!(serde_json::from_str::<Limitkind>(s).unwrap(), want)
+    }
+
+    #[test]
+    fn test_names() {
+        for kind in Limitkind::BUILTIN {
+            let name = kind.name().unwrap();
+            assert_eq!(kind.to_string(), name);
+            assert_eq!(name.parse::<Limitkind>().unwrap(), kind);
+        }
+        let custom = Limitkind::Custom(base::Limitrule::TFSA);
+        assert_eq!(custom.name(), None);
+        assert_eq!(custom.to_string(), "custom");
+        assert!("custom".parse::<Limitkind>().is_err());
+    }
+
+    #[rstest]
+    #[case(Limitkind::Tfsa, 2008, None)]
+    #[case(Limitkind::Tfsa, 2009, Some(base::Cents(500000)))]
+    #[case(Limitkind::Tfsa, 2015, Some(base::Cents(1000000)))]
+    #[case(Limitkind::Rrsp, 2015, Some(base::Cents(2493000)))]
+    #[case(Limitkind::Rrsp, 9999, None)]
+    #[case(Limitkind::Fhsa, 2024, None)]
+    fn test_official_limit(
+        #[case] kind: Limitkind,
+        #[case] year: u16,
+        #[case] want: Option<base::Cents>,
+    ) {
+        assert_eq!(kind.official_limit(year), want)
+    }
+
+    #[test]
+    fn test_official_limits_are_sorted() {
+        for table in [TFSA_OFFICIAL_LIMITS, RRSP_OFFICIAL_LIMITS] {
+            assert!(table.windows(2).all(|w| w[0].0 + 1 == w[1].0))
+        }
+    }
 }
@@ -1,80 +1,353 @@
+//! Tables of registered account limits.
+
 use crate::base;
 
+/// Table of limits followed by a summary, ready to be displayed.
 pub struct Limitprinter<'a> {
     charset: &'a base::Charset,
-    /// Sorted yearly limits.
-    limits: Vec<(String, base::Cents)>,
-    /// Total limit and remaining limit, in that order.
-    summary: [(String, base::Cents); 2],
+    /// Column headings of `limits`, starting with the heading of the labels.
+    /// Empty if `limits` has no headings.
+    header: Vec<String>,
+    /// Sorted yearly limits, the steps of a calculation, or a per-year
+    /// breakdown with one value per column.
+    limits: Vec<(String, Vec<base::Cents>)>,
+    /// Total limit, details specific to the kind of account, and remaining
+    /// limit, in that order.
+    summary: Vec<(String, base::Cents)>,
+    /// Where every column of `limits` but the last one ends.
+    column_ends: Vec<usize>,
     alignment_charlen: usize,
 }
 
+/// Limits and remaining room of a single account.
 #[derive(Debug, PartialEq, Eq)]
 pub struct Config {
+    /// Characters to draw the table with.
     pub charset: base::Charset,
+    /// Last year to take into account.
     pub year: u16,
+    /// Type of the account.
     pub kind: base::Limitkind,
+    /// See [`base::Limitkind::remaining`].
+    pub rrsp_first_60_days: bool,
+    /// Whether to break down every year instead of listing yearly limits.
+    pub history: bool,
+    /// Yearly limits of the account.
     pub limits: base::Limits,
+    /// Contributions and withdrawals of the account.
     pub rl: base::Recordlist,
 }
 
 impl Config {
+    /// Computes the total and remaining room, and lays out the table.
     pub fn to_limitprinter(&'_ self) -> Limitprinter<'_> {
+        let total = self.limits.inception_to_year(self.year);
+        let remaining =
+            self.kind
+                .remaining(&self.limits, &self.rl, self.year, self.rrsp_first_60_days);
+        let summary = std::iter::once(("Total".into(), total))
+            .chain(
+                self.kind
+                    .details(&self.limits, &self.rl, self.year, self.rrsp_first_60_days),
+            )
+            .chain(std::iter::once(("Remaining".into(), remaining)))
+            .collect::<Vec<_>>();
+
+        if self.history {
+            return self.to_history_limitprinter(summary);
+        }
         let limits = self
             .limits
             .range(..=self.year)
             .map(|(year, limit)| (format!("{:0>4}", year), limit))
-            .collect::<Vec<_>>();
+            .collect();
+        Limitprinter::new(&self.charset, limits, summary)
+    }
 
-        let total = limits.iter().map(|&(_, limit)| limit).sum::<base::Cents>();
-        let remaining = self.kind.remaining(&self.limits, &self.rl, self.year);
-        let summary = [("Total".into(), total), ("Remaining".into(), remaining)];
+    fn to_history_limitprinter(&'_ self, summary: Vec<(String, base::Cents)>) -> Limitprinter<'_> {
+        let restores = self
+            .kind
+            .rule(self.rrsp_first_60_days)
+            .is_some_and(|rule| rule.withdrawals != base::limitrule::Restoration::Never);
+        let mut header = vec!["Year", "New room", "Contributions", "Withdrawals"];
+        if restores {
+            header.push("Restored");
+        }
+        header.push("Room");
+        let rows = self
+            .kind
+            .history(&self.limits, &self.rl, self.year, self.rrsp_first_60_days)
+            .into_iter()
+            .map(|h| {
+                let mut values = vec![h.new_room, h.contributions, h.withdrawals];
+                if restores {
+                    values.push(h.restored);
+                }
+                values.push(h.room);
+                (format!("{:0>4}", h.year), values)
+            })
+            .collect();
+        Limitprinter::with_header(
+            &self.charset,
+            header.into_iter().map(String::from).collect(),
+            rows,
+            summary,
+        )
+    }
+}
 
-        fn char_count((label, value): &(String, base::Cents)) -> usize {
-            label.len()
-                + base::util::BOUNDING_SPACES_COUNT
-                + base::util::MIN_DASHES_COUNT
-                + value.charlen_for_alignment()
+/// Remaining limits of several named accounts.
+#[derive(Debug, PartialEq, Eq)]
+pub struct SummaryConfig {
+    /// Characters to draw the table with.
+    pub charset: base::Charset,
+    /// Year to compute the remaining room at the end of.
+    pub year: u16,
+    /// See [`base::Limitkind::remaining`].
+    pub rrsp_first_60_days: bool,
+    /// Accounts by name.
+    pub accounts: std::collections::BTreeMap<String, base::Limitaccount>,
+    /// Every record in the ledger. Each account only sees those matching its
+    /// category.
+    pub rl: base::Recordlist,
+}
+
+impl SummaryConfig {
+    /// Computes the remaining room of each account and lays out the table.
+    pub fn to_limitprinter(&'_ self) -> Limitprinter<'_> {
+        let summary = self
+            .accounts
+            .iter()
+            .map(|(name, account)| {
+                let remaining = account.kind.remaining(
+                    &account.limits,
+                    &account.filter(&self.rl),
+                    self.year,
+                    self.rrsp_first_60_days,
+                );
+                (name.clone(), remaining)
+            })
+            .collect();
+        Limitprinter::new(&self.charset, vec![], summary)
+    }
+}
+
+/// Derivation of a year's RRSP deduction limit from earned income in the
+/// previous year.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DeductionConfig {
+    /// Characters to draw the table with.
+    pub charset: base::Charset,
+    /// Year that the limit applies to.
+    pub year: u16,
+    /// Earned income in the previous year.
+    pub earned_income: base::Cents,
+    /// Maximum deduction limit for `year`, if one is known.
+    pub maximum: Option<base::Cents>,
+    /// Pension adjustment reported for the previous year.
+    pub pension_adjustment: base::Cents,
+}
+
+impl DeductionConfig {
+    /// Percentage of earned income that becomes deduction room.
+    const EARNED_INCOME_PERCENT: i64 = 18;
+
+    fn earned_income_portion(&self) -> base::Cents {
+        base::Cents((self.earned_income.0 * Self::EARNED_INCOME_PERCENT + 50).div_euclid(100))
+    }
+
+    /// Returns the deduction limit, which is never negative even if the
+    /// pension adjustment exceeds the room from earned income.
+    pub fn limit(&self) -> base::Cents {
+        let portion = self.earned_income_portion();
+        let capped = match self.maximum {
+            Some(maximum) => portion.min(maximum),
+            None => portion,
+        };
+        (capped - self.pension_adjustment).max(base::Cents(0))
+    }
+
+    /// Lays out each step of the calculation.
+    pub fn to_limitprinter(&'_ self) -> Limitprinter<'_> {
+        let prev_year = self.year.saturating_sub(1);
+        let mut rows = vec![
+            (
+                format!("Earned income in {:0>4}", prev_year),
+                self.earned_income,
+            ),
+            (
+                format!("{}% of earned income", Self::EARNED_INCOME_PERCENT),
+                self.earned_income_portion(),
+            ),
+        ];
+        if let Some(maximum) = self.maximum {
+            rows.push((format!("Maximum for {:0>4}", self.year), maximum));
+        }
+        rows.push(("Pension adjustment".into(), -self.pension_adjustment));
+        let summary = vec![(format!("{:0>4} limit", self.year), self.limit())];
+        Limitprinter::new(&self.charset, rows, summary)
+    }
+}
+
+/// Yearly limits filled in from published limits.
+#[derive(Debug, PartialEq, Eq)]
+pub struct FillConfig {
+    /// Characters to draw the table with.
+    pub charset: base::Charset,
+    /// Years with published limits, along with their limits and whether they
+    /// are filled in. Years that are not filled in keep their existing limits.
+    pub years: Vec<(u16, base::Cents, bool)>,
+}
+
+impl FillConfig {
+    /// Returns the number of years that are filled in.
+    pub fn filled_count(&self) -> usize {
+        self.years.iter().filter(|&&(_, _, filled)| filled).count()
+    }
+
+    /// Lists each year, marking those that are filled in with `+`.
+    pub fn to_limitprinter(&'_ self) -> Limitprinter<'_> {
+        let rows = self
+            .years
+            .iter()
+            .map(|&(year, limit, filled)| {
+                let marker = if filled { '+' } else { ' ' };
+                (format!("{} {:0>4}", marker, year), limit)
+            })
+            .collect();
+        Limitprinter::new(&self.charset, vec![], rows)
+    }
+}
+
+impl<'a> Limitprinter<'a> {
+    fn new(
+        charset: &'a base::Charset,
+        limits: Vec<(String, base::Cents)>,
+        summary: Vec<(String, base::Cents)>,
+    ) -> Self {
+        let limits = limits
+            .into_iter()
+            .map(|(label, value)| (label, vec![value]))
+            .collect();
+        Self::with_header(charset, vec![], limits, summary)
+    }
+
+    fn with_header(
+        charset: &'a base::Charset,
+        header: Vec<String>,
+        limits: Vec<(String, Vec<base::Cents>)>,
+        summary: Vec<(String, base::Cents)>,
+    ) -> Self {
+        const PADDING: usize = base::util::BOUNDING_SPACES_COUNT + base::util::MIN_DASHES_COUNT;
+
+        let rows = Self::rows(&header, &limits);
+        let column_count = rows.iter().map(|(_, cells)| cells.len()).max();
+        let mut column_ends = Vec::new();
+        if let Some(column_count) = column_count.filter(|&count| count > 1) {
+            let mut end = rows
+                .iter()
+                .map(|(label, _)| label.chars().count())
+                .max()
+                .unwrap_or_default();
+            for i in 0..column_count - 1 {
+                end += PADDING
+                    + rows
+                        .iter()
+                        .filter_map(|(_, cells)| cells.get(i))
+                        .map(|&(_, charlen)| charlen)
+                        .max()
+                        .unwrap_or_default();
+                column_ends.push(end);
+            }
         }
+
+        let row_charlen = |(label, cells): &(&str, Vec<(String, usize)>)| {
+            let start = column_ends
+                .last()
+                .copied()
+                .unwrap_or_else(|| label.chars().count());
+            start + PADDING + cells.last().map_or(0, |&(_, charlen)| charlen)
+        };
+        let summary_charlen = |(label, value): &(String, base::Cents)| {
+            label.chars().count() + PADDING + value.charlen_for_alignment()
+        };
         let alignment_charlen = usize::max(
-            limits.iter().map(char_count).max().unwrap_or_default(),
-            summary.iter().map(char_count).max().unwrap_or_default(),
+            rows.iter().map(row_charlen).max().unwrap_or_default(),
+            summary
+                .iter()
+                .map(summary_charlen)
+                .max()
+                .unwrap_or_default(),
         );
 
-        Limitprinter {
-            charset: &self.charset,
+        Self {
+            charset,
+            header,
             limits,
             summary,
+            column_ends,
             alignment_charlen,
         }
     }
-}
 
-impl Limitprinter<'_> {
+    /// Returns the heading row, if any, followed by every row of `limits`, with
+    /// every cell paired with its length for alignment.
+    fn rows<'b>(
+        header: &'b [String],
+        limits: &'b [(String, Vec<base::Cents>)],
+    ) -> Vec<(&'b str, Vec<(String, usize)>)> {
+        let heading = header.split_first().map(|(label, headings)| {
+            let cells = headings
+                .iter()
+                .map(|heading| (heading.clone(), heading.chars().count() + 1))
+                .collect();
+            (label.as_str(), cells)
+        });
+        let values = limits.iter().map(|(label, values)| {
+            let cells = values
+                .iter()
+                .map(|value| (value.to_string(), value.charlen_for_alignment()))
+                .collect();
+            (label.as_str(), cells)
+        });
+        heading.into_iter().chain(values).collect()
+    }
+
+    /// Draws `label` followed by `cells`, each preceded by dashes so that it
+    /// ends where its column does.
     fn draw(
         &self,
         w: &mut impl std::fmt::Write,
-        (label, value): &(String, base::Cents),
+        label: &str,
+        cells: &[(String, usize)],
     ) -> std::fmt::Result {
-        let dash_count = self.alignment_charlen
-            - label.len()
-            - base::util::BOUNDING_SPACES_COUNT
-            - value.charlen_for_alignment();
         w.write_str(label)?;
-        w.write_char(' ')?;
-        for _ in 0..dash_count {
-            w.write_char(self.charset.dash)?;
+        let mut charlen = label.chars().count();
+        for (i, (text, text_charlen)) in cells.iter().enumerate() {
+            let last = i + 1 == cells.len();
+            let end = match last {
+                true => self.alignment_charlen,
+                false => self.column_ends[i],
+            };
+            let dash_count = end - charlen - base::util::BOUNDING_SPACES_COUNT - text_charlen;
+            w.write_char(' ')?;
+            for _ in 0..dash_count {
+                w.write_char(self.charset.dash)?;
+            }
+            w.write_char(' ')?;
+            w.write_str(text)?;
+            charlen = end - text_charlen + text.chars().count();
         }
-        w.write_char(' ')?;
-        writeln!(w, "{}", value)?;
-        Ok(())
+        writeln!(w)
     }
 }
 
 impl std::fmt::Display for Limitprinter<'_> {
     /// Writes a terminating newline.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.limits.iter().try_for_each(|row| self.draw(f, row))?;
+        Self::rows(&self.header, &self.limits)
+            .iter()
+            .try_for_each(|(label, cells)| self.draw(f, label, cells))?;
         if !self.limits.is_empty() {
             use std::fmt::Write;
             for _ in 1..self.alignment_charlen {
@@ -82,7 +355,10 @@ impl std::fmt::Display for Limitprinter<'_> {
             }
             f.write_char('\n')?;
         }
-        self.summary.iter().try_for_each(|row| self.draw(f, row))?;
+        self.summary.iter().try_for_each(|(label, value)| {
+            let cell = (value.to_string(), value.charlen_for_alignment());
+            self.draw(f, label, &[cell])
+        })?;
         Ok(())
     }
 }
@@ -142,6 +418,36 @@ mod tests {
             Remaining --- (5.00)
         ")
     )]
+    #[case(
+        2024,
+        base::Limitkind::Fhsa,
+        r#"{
+            "2023": 800000,
+            "2024": 800000
+        }"#,
+        r#"{"d":"2023-05-01","c":"aaa","a":300000}"#,
+        indoc!("
+            2023 ----------- 8,000.00
+            2024 ----------- 8,000.00
+            =========================
+            Total --------- 16,000.00
+            Carry-forward -- 5,000.00
+            Lifetime used -- 3,000.00
+            Remaining ----- 13,000.00
+        ")
+    )]
+    #[case(
+        2015,
+        base::Limitkind::Resp,
+        "{}",
+        r#"{"d":"2015-05-01","c":"aaa","a":100000}"#,
+        indoc!("
+            Total --------------- 0.00
+            Lifetime used --- 1,000.00
+            Grant-eligible -- 1,500.00
+            Remaining ------ 49,000.00
+        ")
+    )]
     fn test_to_string(
         #[case] year: u16,
         #[case] kind: base::Limitkind,
@@ -152,6 +458,8 @@ mod tests {
         let config = Config {
             charset: base::Charset::default(),
             year,
+            rrsp_first_60_days: false,
+            history: false,
             kind,
             limits,
             rl,
@@ -159,4 +467,179 @@ mod tests {
         let printer = config.to_limitprinter();
         assert_eq!(printer.to_string(), want)
     }
+
+    #[test]
+    fn test_to_string_rrsp_first_60_days() {
+        let config = Config {
+            charset: base::Charset::default(),
+            year: 2015,
+            kind: base::Limitkind::Rrsp,
+            rrsp_first_60_days: true,
+            history: false,
+            limits: r#"{"2015": 500000}"#.parse().unwrap(),
+            rl: r#"
+                {"d":"2015-02-01","c":"aaa","a":100000}
+                {"d":"2015-06-01","c":"aaa","a":200000}
+                {"d":"2016-02-01","c":"aaa","a":50000}
+            "#
+            .parse()
+            .unwrap(),
+        };
+        let want = indoc!(
+            "
+            2015 ----------------- 5,000.00
+            ===============================
+            Total ---------------- 5,000.00
+            Rest of 2015 --------- 2,000.00
+            First 60 days of 2016 -- 500.00
+            Remaining ------------ 1,500.00
+        "
+        );
+        assert_eq!(config.to_limitprinter().to_string(), want)
+    }
+
+    #[test]
+    fn test_history_to_string() {
+        let config = Config {
+            charset: base::Charset::default(),
+            year: 2015,
+            kind: base::Limitkind::Tfsa,
+            rrsp_first_60_days: false,
+            history: true,
+            limits: r#"{"2014": 550000, "2015": 1000000}"#.parse().unwrap(),
+            rl: r#"
+                {"d":"2014-02-01","c":"aaa","a":500000}
+                {"d":"2014-06-01","c":"aaa","a":-200000}
+                {"d":"2015-02-01","c":"aaa","a":1500000}
+            "#
+            .parse()
+            .unwrap(),
+        };
+        let want = indoc!(
+            "
+            Year --- New room --- Contributions --- Withdrawals --- Restored -------- Room
+            2014 --- 5,500.00 -------- 5,000.00 ------ 2,000.00 ------- 0.00 ------ 500.00
+            2015 -- 10,000.00 ------- 15,000.00 ---------- 0.00 --- 2,000.00 --- (2,500.00)
+            ==============================================================================
+            Total -------------------------------------------------------------- 15,500.00
+            Excess in 2015 Feb -------------------------------------------------- 2,500.00
+            Excess in 2015 Mar -------------------------------------------------- 2,500.00
+            Penalty estimate ------------------------------------------------------- 50.00
+            Remaining ---------------------------------------------------------- (2,500.00)
+        "
+        );
+        assert_eq!(config.to_limitprinter().to_string(), want)
+    }
+
+    #[test]
+    fn test_summary_to_string() {
+        let config = SummaryConfig {
+            charset: base::Charset::default(),
+            year: 2015,
+            rrsp_first_60_days: false,
+            accounts: [
+                (
+                    "retirement".to_string(),
+                    base::Limitaccount {
+                        kind: base::Limitkind::Rrsp,
+                        category: "rrsp".to_string(),
+                        limits: r#"{"2015": 100000}"#.parse().unwrap(),
+                    },
+                ),
+                (
+                    "savings".to_string(),
+                    base::Limitaccount {
+                        kind: base::Limitkind::Tfsa,
+                        category: "tfsa".to_string(),
+                        limits: r#"{"2015": 50000}"#.parse().unwrap(),
+                    },
+                ),
+            ]
+            .into(),
+            rl: r#"
+                {"d":"2015-01-01","c":"rrsp","a":20000}
+                {"d":"2015-01-01","c":"tfsa","a":60000}
+            "#
+            .parse()
+            .unwrap(),
+        };
+        let want = indoc!(
+            "
+            retirement -- 800.00
+            savings ---- (100.00)
+        "
+        );
+        assert_eq!(config.to_limitprinter().to_string(), want)
+    }
+
+    #[rstest]
+    #[case::uncapped(
+        None,
+        base::Cents(0),
+        indoc!("
+            Earned income in 2014 -- 50,000.00
+            18% of earned income ---- 9,000.00
+            Pension adjustment ---------- 0.00
+            ==================================
+            2015 limit -------------- 9,000.00
+        "),
+    )]
+    #[case::capped(
+        Some(base::Cents(800000)),
+        base::Cents(100000),
+        indoc!("
+            Earned income in 2014 -- 50,000.00
+            18% of earned income ---- 9,000.00
+            Maximum for 2015 -------- 8,000.00
+            Pension adjustment ----- (1,000.00)
+            ==================================
+            2015 limit -------------- 7,000.00
+        "),
+    )]
+    #[case::pension_adjustment_exceeds_room(
+        None,
+        base::Cents(1000000),
+        indoc!("
+            Earned income in 2014 -- 50,000.00
+            18% of earned income ---- 9,000.00
+            Pension adjustment ---- (10,000.00)
+            ==================================
+            2015 limit ------------------ 0.00
+        "),
+    )]
+    fn test_deduction_to_string(
+        #[case] maximum: Option<base::Cents>,
+        #[case] pension_adjustment: base::Cents,
+        #[case] want: &str,
+    ) {
+        let config = DeductionConfig {
+            charset: base::Charset::default(),
+            year: 2015,
+            earned_income: base::Cents(5000000),
+            maximum,
+            pension_adjustment,
+        };
+        assert_eq!(config.to_limitprinter().to_string(), want)
+    }
+
+    #[test]
+    fn test_fill_to_string() {
+        let config = FillConfig {
+            charset: base::Charset::default(),
+            years: vec![
+                (2014, base::Cents(550000), true),
+                (2015, base::Cents(100), false),
+                (2016, base::Cents(550000), true),
+            ],
+        };
+        let want = indoc!(
+            "
+            + 2014 -- 5,500.00
+              2015 ------ 1.00
+            + 2016 -- 5,500.00
+        "
+        );
+        assert_eq!(config.to_limitprinter().to_string(), want);
+        assert_eq!(config.filled_count(), 2);
+    }
 }
@@ -0,0 +1,171 @@
+use crate::base;
+
+/// Declarative rules of how contribution room accrues and is used up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase", default, deny_unknown_fields)]
+pub struct Limitrule {
+    /// When withdrawals are restored as room.
+    pub withdrawals: Restoration,
+    /// Most unused room that carries forward into the next year, if capped.
+    pub carry_forward_max: Option<base::Cents>,
+    /// Most contributions over the account's lifetime, if capped.
+    pub lifetime_max: Option<base::Cents>,
+    /// Whether yearly limits before the year of the first contribution count
+    /// towards room.
+    pub accrues_before_first_contribution: bool,
+    /// Number of days at the start of a year in which contributions count
+    /// towards the previous year.
+    pub prior_year_days: u16,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Restoration {
+    Immediately,
+    NextYear,
+    #[default]
+    Never,
+}
+
+impl Default for Limitrule {
+    fn default() -> Self {
+        Self::RRSP
+    }
+}
+
+impl Limitrule {
+    /// Rules of an RRSP, apart from the first 60 days of each year.
+    pub const RRSP: Self = Self {
+        withdrawals: Restoration::Never,
+        carry_forward_max: None,
+        lifetime_max: None,
+        accrues_before_first_contribution: true,
+        prior_year_days: 0,
+    };
+    /// Rules of a TFSA.
+    pub const TFSA: Self = Self {
+        withdrawals: Restoration::NextYear,
+        carry_forward_max: None,
+        lifetime_max: None,
+        accrues_before_first_contribution: true,
+        prior_year_days: 0,
+    };
+
+    /// Returns the room remaining at the end of `year`.
+    pub fn remaining(
+        &self,
+        limits: &base::Limits,
+        rl: &base::Recordlist,
+        year: u16,
+    ) -> base::Cents {
+        let first_contribution_year = rl
+            .iter()
+            .find(|r| r.amount().0 > 0)
+            .map(|r| self.attributed_year(r.date()));
+        let mut room = base::Cents(0);
+        let mut lifetime_used = base::Cents(0);
+        let first_year = base::limitkind::first_year(limits, rl, year)
+            .min(first_contribution_year.unwrap_or(year));
+        for y in first_year..=year {
+            if self.accrues_before_first_contribution
+                || first_contribution_year.is_some_and(|first| first <= y)
+            {
+                room += limits.get(y).unwrap_or_default();
+            }
+            let contributions = self.contributions_in_year(rl, y);
+            room += self.restored_in_year(rl, y) - contributions;
+            lifetime_used += contributions;
+            if let Some(max) = self.carry_forward_max
+                && y < year
+            {
+                room = room.min(max);
+            }
+        }
+        match self.lifetime_max {
+            Some(max) => room.min(max - lifetime_used),
+            None => room,
+        }
+    }
+
+    /// Returns contributions that count towards `year`.
+    pub fn contributions_in_year(&self, rl: &base::Recordlist, year: u16) -> base::Cents {
+        rl.iter()
+            .filter(|r| r.amount().0 > 0 && self.attributed_year(r.date()) == year)
+            .map(|r| r.amount())
+            .sum()
+    }
+
+    /// Returns withdrawals that are restored as room in `year`.
+    pub fn restored_in_year(&self, rl: &base::Recordlist, year: u16) -> base::Cents {
+        match self.withdrawals {
+            Restoration::Immediately => base::limitkind::withdrawals_in_year(rl, year),
+            Restoration::NextYear => year.checked_sub(1).map_or(base::Cents(0), |prev| {
+                base::limitkind::withdrawals_in_year(rl, prev)
+            }),
+            Restoration::Never => base::Cents(0),
+        }
+    }
+
+    /// Returns the year that a contribution made on `dt` counts towards.
+    pub fn attributed_year(&self, dt: base::Date) -> u16 {
+        match dt.ordinal() <= self.prior_year_days {
+            true => dt.year().saturating_sub(1),
+            false => dt.year(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::rrsp(Limitrule::RRSP, [200000, 100000, 170000])]
+    #[case::tfsa(Limitrule::TFSA, [200000, 100000, 190000])]
+    #[case::restored_immediately(
+        Limitrule { withdrawals: Restoration::Immediately, ..Limitrule::RRSP },
+        [200000, 120000, 190000],
+    )]
+    #[case::carry_forward_max(
+        Limitrule { carry_forward_max: Some(base::Cents(50000)), ..Limitrule::RRSP },
+        [200000, -50000, 20000],
+    )]
+    #[case::lifetime_max(
+        Limitrule { lifetime_max: Some(base::Cents(200000)), ..Limitrule::TFSA },
+        [200000, 50000, 50000],
+    )]
+    #[case::accrues_from_first_contribution(
+        Limitrule { accrues_before_first_contribution: false, ..Limitrule::RRSP },
+        [0, -100000, -30000],
+    )]
+    #[case::prior_year_days(
+        Limitrule { prior_year_days: 60, ..Limitrule::RRSP },
+        [150000, 100000, 170000],
+    )]
+    fn test_remaining(#[case] rule: Limitrule, #[case] want: [i64; 3]) {
+        let limits = r#"{"2013": 200000, "2014": 50000, "2015": 70000}"#.parse().unwrap();
+        let rl = r#"
+            {"d":"2014-01-15","c":"aaa","a":50000}
+            {"d":"2014-03-15","c":"aaa","a":100000}
+            {"d":"2014-06-15","c":"aaa","a":-20000}
+        "#
+        .parse()
+        .unwrap();
+        let got = [2013, 2014, 2015].map(|year| rule.remaining(&limits, &rl, year).0);
+        assert_eq!(got, want)
+    }
+
+    #[test]
+    fn test_deserialize() {
+        let rule = r#"{"withdrawals": "nextYear", "lifetimeMax": 100}"#;
+        assert_eq!(
+            serde_json::from_str::<Limitrule>(rule).unwrap(),
+            Limitrule {
+                lifetime_max: Some(base::Cents(100)),
+                ..Limitrule::TFSA
+            }
+        );
+        assert!(serde_json::from_str::<Limitrule>(r#"{"foo": 1}"#).is_err());
+    }
+}
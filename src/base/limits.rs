@@ -1,25 +1,36 @@
 use crate::base;
 
+/// Contribution limits by year.
 #[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Limits(std::collections::BTreeMap<u16, base::Cents>);
 
 impl Limits {
+    /// Returns an empty set of limits.
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Returns whether no year has a limit.
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
 
+    /// Returns the number of years with a limit.
     pub fn len(&self) -> usize {
         self.0.len()
     }
 
+    /// Returns the limit of `year`, if it has one.
+    pub fn get(&self, year: u16) -> Option<base::Cents> {
+        self.0.get(&year).copied()
+    }
+
+    /// Sets the limit of `year`, replacing any existing one.
     pub fn set(&mut self, year: u16, limit: base::Cents) {
         self.0.insert(year, limit);
     }
 
+    /// Removes the limit of `year`, returning it if it had one.
     pub fn remove(&mut self, year: u16) -> Option<base::Cents> {
         self.0.remove(&year)
     }
@@ -100,6 +111,8 @@ mod tests {
             limits,
             Limits([(2015, base::Cents(1000)), (2016, base::Cents(0))].into())
         );
+        assert_eq!(limits.get(2015), Some(base::Cents(1000)));
+        assert_eq!(limits.get(2017), None);
         assert_eq!(limits.inception_to_year(2014), base::Cents(0));
         assert_eq!(limits.inception_to_year(2015), base::Cents(1000));
         assert_eq!(limits.inception_to_year(2016), base::Cents(1000));
@@ -1,32 +1,52 @@
+//! Repository files, the types stored in them, and the reports built from
+//! them.
+
 mod aggregate;
 pub mod barchart;
-mod category;
+mod calendar;
+pub mod category;
 mod cents;
 mod charset;
-mod config;
-mod date;
+pub mod config;
+pub mod date;
 mod datepart;
+mod filter;
+pub mod forecast;
 pub mod fs;
-mod interval;
+pub mod interval;
+mod limitaccounts;
 mod limitkind;
 pub mod limitprinter;
+mod limitrule;
 mod limits;
+mod month;
 mod record;
-mod recordlist;
+pub mod recordlist;
+mod stats;
 pub mod tree;
 mod util;
+mod weekday;
 
 pub use aggregate::Aggregate;
+pub use calendar::Calendar;
 pub use category::Category;
 pub use cents::Cents;
 pub use charset::Charset;
 pub use config::Config;
 pub use date::Date;
 pub use datepart::Datepart;
+pub use filter::filter_rl;
+pub use filter::preprocess_categories;
 pub use fs::Fs;
 pub use interval::Interval;
+pub use limitaccounts::Limitaccount;
+pub use limitaccounts::Limitaccounts;
 pub use limitkind::Limitkind;
+pub use limitrule::Limitrule;
 pub use limits::Limits;
+pub use month::Month;
 pub use record::Record;
 pub use recordlist::Recordlist;
+pub use stats::Stats;
 pub use tree::Tree;
+pub use weekday::Weekday;
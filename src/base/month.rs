@@ -0,0 +1,39 @@
+/// Month of the year.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Serialize,
+    serde::Deserialize,
+    strum::EnumString,
+    strum::Display,
+    strum::VariantNames,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(ascii_case_insensitive, serialize_all = "lowercase")]
+#[allow(missing_docs)] // Variants are named after what they stand for.
+pub enum Month {
+    #[default]
+    January,
+    February,
+    March,
+    April,
+    May,
+    June,
+    July,
+    August,
+    September,
+    October,
+    November,
+    December,
+}
+
+impl Month {
+    /// Returns the month's one-based number, i.e. 1 for January.
+    pub const fn number(self) -> u16 {
+        self as u16 + 1
+    }
+}
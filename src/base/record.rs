@@ -1,5 +1,7 @@
 use crate::base;
 
+/// A single cash flow: an amount of money moving in (positive) or out
+/// (negative) of a category on a date.
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct Record {
@@ -11,25 +13,53 @@ pub struct Record {
     amount: base::Cents,
     #[serde(rename = "n", skip_serializing_if = "String::is_empty", default)]
     note: String,
+    /// Identifies the records that a single transaction is split into.
+    #[serde(rename = "g", skip_serializing_if = "Option::is_none", default)]
+    group: Option<u32>,
+    /// Short identifier that stays the same as other records come and go.
+    /// Assigned when the record is added to a [`base::Recordlist`].
+    #[serde(rename = "i", skip_serializing_if = "Option::is_none", default)]
+    id: Option<String>,
 }
 
 impl Record {
+    /// Returns the date of the transaction.
     pub fn date(&self) -> base::Date {
         self.date
     }
 
+    /// Returns the category that the money moved in or out of.
     pub fn category(&self) -> &base::Category {
         &self.category
     }
 
+    /// Returns the amount, positive if money moved in.
     pub fn amount(&self) -> base::Cents {
         self.amount
     }
 
+    /// Returns the note, which is empty if there is none.
     pub fn note(&self) -> &str {
         &self.note
     }
 
+    /// Returns the group shared with the other records of the same transaction,
+    /// if the transaction was split.
+    pub fn group(&self) -> Option<u32> {
+        self.group
+    }
+
+    /// Returns the ID, if one was assigned.
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    /// Replaces the ID.
+    pub fn set_id(&mut self, id: String) {
+        self.id = Some(id);
+    }
+
+    /// Returns a record without a group or an ID.
     pub fn new(
         date: base::Date,
         category: base::Category,
@@ -41,8 +71,17 @@ impl Record {
             category,
             amount,
             note,
+            group: None,
+            id: None,
         }
     }
+
+    /// Marks the record as one of the records that a single transaction is
+    /// split into.
+    pub fn with_group(mut self, group: u32) -> Self {
+        self.group = Some(group);
+        self
+    }
 }
 
 impl std::fmt::Display for Record {
@@ -81,6 +120,8 @@ mod tests {
             category: "category".parse().unwrap(),
             amount:base:: Cents(123456),
             note: String::new(),
+            group: None,
+            id: None,
         },
     )]
     #[case(
@@ -90,6 +131,30 @@ mod tests {
             category: "category".parse().unwrap(),
             amount: base::Cents(-0),
             note: String::from("some note\nmore note"),
+            group: None,
+            id: None,
+        },
+    )]
+    #[case(
+        r#"{"d":"2015-03-30","c":"category","a":100,"g":7}"#,
+        Record {
+            date: "2015-03-30".parse().unwrap(),
+            category: "category".parse().unwrap(),
+            amount: base::Cents(100),
+            note: String::new(),
+            group: Some(7),
+            id: None,
+        },
+    )]
+    #[case(
+        r#"{"d":"2015-03-30","c":"category","a":100,"i":"a1b2c3"}"#,
+        Record {
+            date: "2015-03-30".parse().unwrap(),
+            category: "category".parse().unwrap(),
+            amount: base::Cents(100),
+            note: String::new(),
+            group: None,
+            id: Some("a1b2c3".to_string()),
         },
     )]
     fn test_serde(#[case] s: &str, #[case] r: Record) {
@@ -1,26 +1,44 @@
+//! The ledger file's list of records.
+
 use crate::base;
 
+/// Records sorted by date. Parsed from and written as one JSON record per
+/// line.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Recordlist(Vec<base::Record>);
 
 impl Recordlist {
+    /// Returns an empty list.
     pub fn new() -> Self {
         Self::default()
     }
 
     fn from_vec(mut inner: Vec<base::Record>) -> Self {
         inner.sort_by_key(base::Record::date);
+        let mut ids = inner
+            .iter()
+            .filter_map(|r| r.id().map(String::from))
+            .collect::<std::collections::HashSet<_>>();
+        for r in inner.iter_mut().filter(|r| r.id().is_none()) {
+            let id = generate_id(r, &[], |id| ids.contains(id));
+            ids.insert(id.clone());
+            r.set_id(id);
+        }
         Self(inner)
     }
 
+    /// Returns whether the list has no records.
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
     }
 
+    /// Returns the number of records.
     pub fn len(&self) -> usize {
         self.0.len()
     }
 
+    /// Returns the interval from the earliest to the latest date, or
+    /// [`base::Interval::EMPTY`] if there are no records.
     pub fn spanned_interval(&self) -> base::Interval {
         let start = match self.0.first() {
             Some(r) => r.date(),
@@ -33,6 +51,7 @@ impl Recordlist {
         base::Interval { start, end }
     }
 
+    /// Returns the records dated within `interval`.
     pub fn slice_spanning_interval(&self, interval: base::Interval) -> &[base::Record] {
         if interval.is_empty() {
             return &[];
@@ -42,7 +61,18 @@ impl Recordlist {
         &self.0[i..j]
     }
 
+    /// Assigns `r` a new ID if it doesn't already have one, or if its ID is
+    /// already taken by another record.
     pub fn insert(&mut self, r: base::Record) {
+        self.insert_with_seed(r, &new_id_seed());
+    }
+
+    fn insert_with_seed(&mut self, mut r: base::Record, seed: &[u8]) {
+        let is_used = |id: &str| self.iter().any(|x| x.id() == Some(id));
+        if r.id().is_none_or(is_used) {
+            let id = generate_id(&r, seed, is_used);
+            r.set_id(id);
+        }
         let i = self.0.partition_point(|x| x.date() <= r.date());
         self.0.insert(i, r);
     }
@@ -64,6 +94,14 @@ impl Recordlist {
         Some(&self.0[i])
     }
 
+    /// Returns the date and index-in-date of the record with the given ID, or
+    /// `None` if there is no such record.
+    pub fn find(&self, id: &str) -> Option<(base::Date, usize)> {
+        self.iter_with_iid()
+            .find(|(_, r)| r.id() == Some(id))
+            .map(|(iid, r)| (r.date(), iid))
+    }
+
     /// Removes and returns the record at the given date and index-in-date. If
     /// input is out of bounds, returns `None` and leaves record list
     /// unmodified.
@@ -72,10 +110,21 @@ impl Recordlist {
         Some(self.0.remove(i))
     }
 
+    /// Returns a group that no record belongs to.
+    pub fn next_group(&self) -> u32 {
+        self.iter()
+            .filter_map(base::Record::group)
+            .max()
+            .map_or(0, |group| group + 1)
+    }
+
+    /// Iterates over the records in order.
     pub fn iter(&self) -> impl Iterator<Item = &base::Record> {
         self.0.iter()
     }
 
+    /// Iterates over the records in order, along with each record's index among
+    /// those on the same date.
     pub fn iter_with_iid(&self) -> impl Iterator<Item = (usize, &base::Record)> {
         let mut iid = 0;
         self.iter().enumerate().map(move |(i, r)| {
@@ -89,6 +138,52 @@ impl Recordlist {
     }
 }
 
+/// Number of hexadecimal digits in a record ID.
+const ID_LEN: usize = 6;
+
+/// Derives an ID from the contents of `r` and `seed` for which `is_used`
+/// returns false. With an empty seed, records read without IDs are assigned the
+/// same IDs every time they are read, until they are written back with their
+/// IDs.
+fn generate_id(r: &base::Record, seed: &[u8], is_used: impl Fn(&str) -> bool) -> String {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const FNV_PRIME: u64 = 0x100000001b3;
+
+    let contents = r.to_string();
+    (0u32..)
+        .map(|salt| {
+            let hash = contents
+                .bytes()
+                .chain(seed.iter().copied())
+                .chain(salt.to_le_bytes())
+                .fold(FNV_OFFSET_BASIS, |hash, byte| {
+                    (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+                });
+            format!("{:0width$x}", hash >> (64 - 4 * ID_LEN), width = ID_LEN)
+        })
+        .find(|id| !is_used(id))
+        .expect("an unused ID should exist")
+}
+
+/// Returns a seed for the ID of a new record that is never returned again, so
+/// that removing a record and then adding an identical one does not reissue
+/// the removed record's ID.
+#[cfg(not(test))]
+fn new_id_seed() -> Vec<u8> {
+    static COUNT: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos());
+    let count = COUNT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    [&nanos.to_le_bytes()[..], &count.to_le_bytes()].concat()
+}
+
+/// Returns an empty seed, so that new records get the same IDs as when read.
+#[cfg(test)]
+fn new_id_seed() -> Vec<u8> {
+    Vec::new()
+}
+
 impl IntoIterator for Recordlist {
     type Item = base::Record;
     type IntoIter = std::vec::IntoIter<base::Record>;
@@ -120,26 +215,50 @@ impl std::fmt::Display for Recordlist {
     }
 }
 
+/// Error returned when a record list fails to parse.
 #[derive(Debug, thiserror::Error)]
-#[error("invalid record at line {line}")]
-pub struct ParseError {
-    line: usize,
-    source: serde_json::Error,
+pub enum ParseError {
+    /// A line is not a valid record.
+    #[error("invalid record at line {line}")]
+    Record {
+        /// Line number, starting at 1.
+        line: usize,
+        /// Why the record is invalid.
+        source: serde_json::Error,
+    },
+    /// A record has the same ID as one on an earlier line.
+    #[error("duplicate ID '{id}' at line {line}")]
+    DuplicateId {
+        /// Line number, starting at 1.
+        line: usize,
+        /// The ID.
+        id: String,
+    },
 }
 
 impl std::str::FromStr for Recordlist {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ids = std::collections::HashSet::new();
         s.lines()
             .map(str::trim)
             .enumerate()
             .filter(|(_, x)| !x.is_empty())
             .map(|(i, x)| {
-                x.parse::<base::Record>().map_err(|e| ParseError {
+                let r = x.parse::<base::Record>().map_err(|e| ParseError::Record {
                     line: i + 1,
                     source: e,
-                })
+                })?;
+                if let Some(id) = r.id()
+                    && !ids.insert(id.to_string())
+                {
+                    return Err(ParseError::DuplicateId {
+                        line: i + 1,
+                        id: id.to_string(),
+                    });
+                }
+                Ok(r)
             })
             .collect::<Result<Self, _>>()
     }
@@ -161,20 +280,90 @@ mod tests {
     #[test]
     fn test_sort_on_construction() {
         let rl = Recordlist::from_vec(vec![
-            r#"{"d":"2015-03-30","c":"aaa","a":999}"#.parse::<base::Record>().unwrap(),
-            r#"{"d":"2014-03-30","c":"bbb","a":888}"#.parse::<base::Record>().unwrap(),
-            r#"{"d":"2016-03-30","c":"ccc","a":777}"#.parse::<base::Record>().unwrap(),
-            r#"{"d":"2013-03-30","c":"ddd","a":666}"#.parse::<base::Record>().unwrap(),
+            r#"{"d":"2015-03-30","c":"aaa","a":999,"i":"a"}"#.parse::<base::Record>().unwrap(),
+            r#"{"d":"2014-03-30","c":"bbb","a":888,"i":"b"}"#.parse::<base::Record>().unwrap(),
+            r#"{"d":"2016-03-30","c":"ccc","a":777,"i":"c"}"#.parse::<base::Record>().unwrap(),
+            r#"{"d":"2013-03-30","c":"ddd","a":666,"i":"d"}"#.parse::<base::Record>().unwrap(),
         ]);
         let want_inner = vec![
-            r#"{"d":"2013-03-30","c":"ddd","a":666}"#.parse::<base::Record>().unwrap(),
-            r#"{"d":"2014-03-30","c":"bbb","a":888}"#.parse::<base::Record>().unwrap(),
-            r#"{"d":"2015-03-30","c":"aaa","a":999}"#.parse::<base::Record>().unwrap(),
-            r#"{"d":"2016-03-30","c":"ccc","a":777}"#.parse::<base::Record>().unwrap(),
+            r#"{"d":"2013-03-30","c":"ddd","a":666,"i":"d"}"#.parse::<base::Record>().unwrap(),
+            r#"{"d":"2014-03-30","c":"bbb","a":888,"i":"b"}"#.parse::<base::Record>().unwrap(),
+            r#"{"d":"2015-03-30","c":"aaa","a":999,"i":"a"}"#.parse::<base::Record>().unwrap(),
+            r#"{"d":"2016-03-30","c":"ccc","a":777,"i":"c"}"#.parse::<base::Record>().unwrap(),
         ];
         assert_eq!(rl.0, want_inner)
     }
 
+    #[test]
+    fn test_assign_ids() {
+        let s = r#"
+            {"d":"2015-03-30","c":"aaa","a":999}
+            {"d":"2015-03-30","c":"aaa","a":999}
+            {"d":"2015-03-31","c":"bbb","a":888,"i":"abc"}
+        "#;
+        let rl = s.parse::<Recordlist>().unwrap();
+        let ids = rl.iter().map(|r| r.id().unwrap()).collect::<Vec<_>>();
+        assert_eq!(ids.len(), 3);
+        assert_ne!(ids[0], ids[1]);
+        assert!(ids[..2].iter().all(|id| id.len() == ID_LEN));
+        assert_eq!(ids[2], "abc");
+        // IDs are assigned the same way every time.
+        assert_eq!(s.parse::<Recordlist>().unwrap(), rl);
+
+        let mut inserted = Recordlist::new();
+        for r in s.parse::<Recordlist>().unwrap() {
+            inserted.insert(r);
+        }
+        assert_eq!(inserted, rl);
+        let mut r = base::Record::new(
+            "2015-03-30".parse().unwrap(),
+            "aaa".parse().unwrap(),
+            base::Cents(999),
+            String::new(),
+        );
+        inserted.insert(r.clone());
+        assert!(
+            ids.iter()
+                .all(|&id| Some(id) != inserted.get(r.date(), 2).unwrap().id())
+        );
+        r.set_id("xyz".to_string());
+        inserted.insert(r);
+        assert_eq!(
+            inserted.find("xyz"),
+            Some(("2015-03-30".parse().unwrap(), 3))
+        );
+        assert_eq!(inserted.find("nonexistent"), None);
+    }
+
+    #[test]
+    fn test_insert_reassigns_ids() {
+        let mut rl = r#"
+            {"d":"2015-03-30","c":"aaa","a":999}
+            {"d":"2015-03-30","c":"ccc","a":777,"i":"abc"}
+        "#
+        .parse::<Recordlist>()
+        .unwrap();
+
+        // An ID that is already taken is replaced.
+        let r =
+            r#"{"d":"2015-03-31","c":"bbb","a":888,"i":"abc"}"#.parse::<base::Record>().unwrap();
+        rl.insert(r);
+        let id = rl.get("2015-03-31".parse().unwrap(), 0).unwrap().id();
+        assert!(id.is_some_and(|id| id != "abc"), "{:?}", id);
+
+        // A removed record's ID is not reissued to an identical record, unless
+        // the seed is reused.
+        let r = rl.remove("2015-03-30".parse().unwrap(), 0).unwrap();
+        let identical =
+            || base::Record::new(r.date(), r.category().clone(), r.amount(), String::new());
+        let mut reissued = rl.clone();
+        reissued.insert_with_seed(identical(), &[]);
+        rl.insert_with_seed(identical(), b"seed");
+        let old_id = r.id().unwrap();
+        assert!(reissued.find(old_id).is_some());
+        assert_eq!(rl.find(old_id), None);
+    }
+
     #[rstest]
     #[case("[]", "invalid record at line 1")]
     #[case(
@@ -191,6 +380,14 @@ mod tests {
         "#,
         "invalid record at line 3"
     )]
+    #[case(
+        r#"
+            {"d":"2015-03-30","c":"aaa","a":111,"i":"abc"}
+            {"d":"2015-03-30","c":"bbb","a":111}
+            {"d":"2015-03-31","c":"aaa","a":111,"i":"abc"}
+        "#,
+        "duplicate ID 'abc' at line 4"
+    )]
     fn test_fromstr_errormsg(#[case] s: &str, #[case] want: &str) {
         assert_eq!(s.parse::<Recordlist>().unwrap_err().to_string(), want)
     }
@@ -391,6 +588,21 @@ mod tests {
         assert_eq!(rl, want);
     }
 
+    #[rstest]
+    #[case("", 0)]
+    #[case(r#"{"d":"2015-03-01","c":"abc","a":111}"#, 0)]
+    #[case(
+        r#"
+            {"d":"2015-03-01","c":"abc","a":111,"g":3}
+            {"d":"2015-03-30","c":"abc","a":111}
+            {"d":"2015-04-01","c":"abc","a":111,"g":1}
+        "#,
+        4
+    )]
+    fn test_next_group(#[case] rl: Recordlist, #[case] want: u32) {
+        assert_eq!(rl.next_group(), want)
+    }
+
     #[rstest]
     #[case("", &[])]
     #[case(
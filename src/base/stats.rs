@@ -0,0 +1,167 @@
+use crate::base;
+
+/// Summary statistics over a category's monthly totals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    /// Number of months considered.
+    pub months: usize,
+    /// Mean of the monthly totals.
+    pub average: base::Cents,
+    /// Median of the monthly totals.
+    pub median: base::Cents,
+    /// Smallest monthly total.
+    pub min: base::Cents,
+    /// Largest monthly total.
+    pub max: base::Cents,
+    /// Population standard deviation.
+    pub std_dev: base::Cents,
+    /// Number of records within the months considered.
+    pub transactions: usize,
+    /// Mean amount of those records.
+    pub average_transaction: base::Cents,
+    /// Slope of the least squares line through the monthly totals, i.e. the
+    /// average change from one month to the next.
+    pub trend: base::Cents,
+}
+
+impl Stats {
+    /// Computes statistics from per-month totals, which must include zeros for
+    /// months without transactions. Returns `None` if `monthly_totals` is
+    /// empty.
+    pub fn new(monthly_totals: &[base::Cents], transactions: usize) -> Option<Self> {
+        if monthly_totals.is_empty() {
+            return None;
+        }
+        let n = monthly_totals.len() as f64;
+        let total = monthly_totals.iter().copied().sum::<base::Cents>();
+        let mean = total.0 as f64 / n;
+
+        let mut sorted = monthly_totals.to_vec();
+        sorted.sort_unstable();
+        let mid = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[mid - 1].0 as f64 + sorted[mid].0 as f64) / 2.0
+        } else {
+            sorted[mid].0 as f64
+        };
+
+        let variance = monthly_totals
+            .iter()
+            .map(|x| (x.0 as f64 - mean).powi(2))
+            .sum::<f64>()
+            / n;
+
+        // Least squares slope with months numbered 0, 1, 2, ...
+        let x_mean = (n - 1.0) / 2.0;
+        let (sxy, sxx) =
+            monthly_totals
+                .iter()
+                .enumerate()
+                .fold((0.0, 0.0), |(sxy, sxx), (i, y)| {
+                    let dx = i as f64 - x_mean;
+                    (sxy + dx * (y.0 as f64 - mean), sxx + dx * dx)
+                });
+        let trend = if sxx == 0.0 { 0.0 } else { sxy / sxx };
+
+        let average_transaction = match transactions {
+            0 => 0.0,
+            _ => total.0 as f64 / transactions as f64,
+        };
+
+        Some(Self {
+            months: monthly_totals.len(),
+            average: to_cents(mean),
+            median: to_cents(median),
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            std_dev: to_cents(variance.sqrt()),
+            transactions,
+            average_transaction: to_cents(average_transaction),
+            trend: to_cents(trend),
+        })
+    }
+}
+
+fn to_cents(x: f64) -> base::Cents {
+    base::Cents(x.round() as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(Stats::new(&[], 0), None);
+    }
+
+    #[rstest]
+    #[case(
+        &[base::Cents(-100)],
+        1,
+        Stats {
+            months: 1,
+            average: base::Cents(-100),
+            median: base::Cents(-100),
+            min: base::Cents(-100),
+            max: base::Cents(-100),
+            std_dev: base::Cents(0),
+            transactions: 1,
+            average_transaction: base::Cents(-100),
+            trend: base::Cents(0),
+        },
+    )]
+    #[case(
+        &[base::Cents(-300), base::Cents(0), base::Cents(-100), base::Cents(-200)],
+        3,
+        Stats {
+            months: 4,
+            average: base::Cents(-150),
+            median: base::Cents(-150),
+            min: base::Cents(-300),
+            max: base::Cents(0),
+            std_dev: base::Cents(112),
+            transactions: 3,
+            average_transaction: base::Cents(-200),
+            trend: base::Cents(20),
+        },
+    )]
+    #[case(
+        &[base::Cents(100), base::Cents(200), base::Cents(300)],
+        6,
+        Stats {
+            months: 3,
+            average: base::Cents(200),
+            median: base::Cents(200),
+            min: base::Cents(100),
+            max: base::Cents(300),
+            std_dev: base::Cents(82),
+            transactions: 6,
+            average_transaction: base::Cents(100),
+            trend: base::Cents(100),
+        },
+    )]
+    #[case(
+        &[base::Cents(0), base::Cents(0)],
+        0,
+        Stats {
+            months: 2,
+            average: base::Cents(0),
+            median: base::Cents(0),
+            min: base::Cents(0),
+            max: base::Cents(0),
+            std_dev: base::Cents(0),
+            transactions: 0,
+            average_transaction: base::Cents(0),
+            trend: base::Cents(0),
+        },
+    )]
+    fn test_new(
+        #[case] monthly_totals: &[base::Cents],
+        #[case] transactions: usize,
+        #[case] want: Stats,
+    ) {
+        assert_eq!(Stats::new(monthly_totals, transactions).unwrap(), want);
+    }
+}
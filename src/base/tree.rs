@@ -1,9 +1,13 @@
+//! Reports laid out as trees.
+
 pub mod forlogt;
+pub mod forstats;
 pub mod forsum;
 pub mod forview;
 
 use crate::base;
 
+/// Tree of labels, ready to be displayed with branches drawn between them.
 pub struct Tree {
     charset: base::Charset,
     root: Node,
@@ -1,18 +1,24 @@
+//! Tree of the config's templates.
+
 use crate::base;
 
+/// Templates of the config, each listed with its entries.
 #[derive(Debug, PartialEq, Eq)]
 pub struct Config {
+    /// Characters to draw the tree with.
     pub charset: base::Charset,
+    /// Templates by name.
     pub templates: std::collections::BTreeMap<String, Vec<base::config::TemplateEntry>>,
 }
 
 impl Config {
+    /// Builds a tree with a node per template and a leaf per entry.
     pub fn to_tree(&self) -> base::Tree {
         fn char_count(entry: &base::config::TemplateEntry) -> usize {
             entry.category.as_str().chars().count()
                 + base::util::BOUNDING_SPACES_COUNT
                 + base::util::MIN_DASHES_COUNT
-                + entry.amount.charlen_for_alignment()
+                + amount_charlen(entry.amount)
         }
         let alignment_charlen = self
             .templates
@@ -42,7 +48,7 @@ impl Config {
         let dash_count = alignment_charlen
             - entry.category.as_str().chars().count()
             - base::util::BOUNDING_SPACES_COUNT
-            - entry.amount.charlen_for_alignment();
+            - amount_charlen(entry.amount);
         let mut s = String::with_capacity(alignment_charlen);
         s.push_str(entry.category.as_str());
         s.push(' ');
@@ -51,10 +57,26 @@ impl Config {
         }
         s.push(' ');
         s.push_str(&entry.amount.to_string());
+        if entry.days != 0 {
+            s.push_str(&format!(" ({:+} days)", entry.days));
+        }
+        if !entry.note.is_empty() {
+            s.push_str(": ");
+            s.push_str(&entry.note);
+        }
         s
     }
 }
 
+/// Returns the character count of `amount` for aligning on the decimal point
+/// of fixed amounts. Parameters are aligned like non-negative fixed amounts.
+fn amount_charlen(amount: base::config::TemplateAmount) -> usize {
+    match amount {
+        base::config::TemplateAmount::Fixed(cents) => cents.charlen_for_alignment(),
+        base::config::TemplateAmount::Param(_) => amount.to_string().chars().count() + 1,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -70,11 +92,15 @@ mod tests {
                 vec![
                     base::config::TemplateEntry {
                         category: base::Category::try_from("category1").unwrap(),
-                        amount: base::Cents(123),
+                        amount: base::config::TemplateAmount::Fixed(base::Cents(123)),
+                        note: String::new(),
+                        days: 0,
                     },
                     base::config::TemplateEntry {
                         category: base::Category::try_from("Category2").unwrap(),
-                        amount: base::Cents(-123),
+                        amount: base::config::TemplateAmount::Fixed(base::Cents(-123)),
+                        note: String::new(),
+                        days: 0,
                     },
                 ],
             ),
@@ -90,6 +116,24 @@ mod tests {
             `-- Category2 -- (1.23)
         "),
     )]
+    #[case(
+        [(
+            String::from("paycheck"),
+            serde_json::from_str(r#"[
+                {"category": "income", "amount": {"param": 1}},
+                {"category": "tax", "amount": {"param": 1, "percent": -20}},
+                {"category": "cpp", "amount": {"param": 1, "percent": -5.95}, "note": "pension"},
+                {"category": "rrsp", "amount": 10000, "days": 2}
+            ]"#).unwrap(),
+        )].into(),
+        indoc!("
+            paycheck
+            |-- income --------- $1
+            |-- tax ---- -20% of $1
+            |-- cpp -- -5.95% of $1: pension
+            `-- rrsp ------- 100.00 (+2 days)
+        "),
+    )]
     fn test_to_tree(
         #[case] templates: std::collections::BTreeMap<String, Vec<base::config::TemplateEntry>>,
         #[case] want: &str,
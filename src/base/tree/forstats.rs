@@ -0,0 +1,201 @@
+//! Tree of statistics by category.
+
+use crate::base;
+
+/// Statistics of each category's monthly totals.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Config {
+    /// Characters to draw the tree with.
+    pub charset: base::Charset,
+    /// Months outside of these bounds are not considered. Months within these
+    /// bounds that have no transactions count as zero.
+    pub bounds: base::Interval,
+    /// Number of category levels to group by. Categories with more levels count
+    /// towards their ancestor at this level.
+    pub level: usize,
+    /// Records to compute statistics from.
+    pub rl: base::Recordlist,
+}
+
+/// A leaf's label and value, along with the value's character count as used
+/// for alignment.
+type Leaf = (&'static str, String, usize);
+
+impl Config {
+    /// Computes statistics for each category at `level`, sorted by descending
+    /// absolute total and then by name.
+    pub fn stats(&self) -> Vec<(&str, base::Stats)> {
+        let months = self
+            .bounds
+            .iter(base::Datepart::Month, base::Calendar::default())
+            .map(|interval| {
+                self.rl
+                    .slice_spanning_interval(interval)
+                    .iter()
+                    .map(|r| (r.category().level(self.level), r.amount()))
+                    .collect::<base::Aggregate<_, _>>()
+            })
+            .collect::<Vec<_>>();
+        let counts = self
+            .rl
+            .slice_spanning_interval(self.bounds)
+            .iter()
+            .map(|r| (r.category().level(self.level), 1))
+            .collect::<base::Aggregate<_, usize>>();
+
+        let mut totals = counts
+            .iter()
+            .map(|(cat, _)| {
+                let total = months
+                    .iter()
+                    .map(|agg| agg.get(cat).unwrap_or_default())
+                    .sum::<base::Cents>();
+                (cat, total)
+            })
+            .collect::<Vec<_>>();
+        totals.sort_unstable_by(|&(s1, a1), &(s2, a2)| {
+            if a1.abs() == a2.abs() {
+                s1.cmp(s2)
+            } else {
+                a2.abs().cmp(&a1.abs())
+            }
+        });
+        totals
+            .into_iter()
+            .filter_map(|(cat, _)| {
+                let monthly_totals = months
+                    .iter()
+                    .map(|agg| agg.get(cat).unwrap_or_default())
+                    .collect::<Vec<_>>();
+                let count = counts.get(cat).unwrap_or_default();
+                base::Stats::new(&monthly_totals, count).map(|stats| (cat, stats))
+            })
+            .collect()
+    }
+
+    /// Builds a tree with a node per category and a leaf per statistic.
+    pub fn to_tree(&self) -> base::Tree {
+        let stats = self.stats();
+        let leaves = stats
+            .iter()
+            .map(|(_, stats)| Self::leaves(stats))
+            .collect::<Vec<_>>();
+        let alignment_charlen = leaves
+            .iter()
+            .flatten()
+            .map(|(label, _, value_charlen)| {
+                label.chars().count()
+                    + base::util::BOUNDING_SPACES_COUNT
+                    + base::util::MIN_DASHES_COUNT
+                    + value_charlen
+            })
+            .max()
+            .unwrap_or_default();
+
+        let mut root = base::tree::Node::default();
+        for ((cat, _), leaves) in stats.iter().zip(leaves) {
+            let mut node = base::tree::Node::new(cat.to_string().into());
+            for leaf in leaves {
+                node.children.push(base::tree::Node::new(
+                    self.leaf_data(leaf, alignment_charlen).into(),
+                ));
+            }
+            root.children.push(node);
+        }
+        base::Tree {
+            charset: self.charset.clone(),
+            root,
+        }
+    }
+
+    fn leaves(stats: &base::Stats) -> [Leaf; 8] {
+        fn cents(label: &'static str, value: base::Cents) -> Leaf {
+            (label, value.to_string(), value.charlen_for_alignment())
+        }
+        let transactions = stats.transactions.to_string();
+        // Counts are aligned as if they were non-negative amounts.
+        let transactions_charlen = transactions.len() + 1;
+        [
+            cents("Monthly average", stats.average),
+            cents("Monthly median", stats.median),
+            cents("Monthly min", stats.min),
+            cents("Monthly max", stats.max),
+            cents("Monthly std dev", stats.std_dev),
+            ("Transactions", transactions, transactions_charlen),
+            cents("Average transaction", stats.average_transaction),
+            cents("Trend per month", stats.trend),
+        ]
+    }
+
+    fn leaf_data(&self, (label, value, value_charlen): Leaf, alignment_charlen: usize) -> String {
+        let dash_count = alignment_charlen
+            - label.chars().count()
+            - base::util::BOUNDING_SPACES_COUNT
+            - value_charlen;
+        let mut s = String::with_capacity(alignment_charlen);
+        s.push_str(label);
+        s.push(' ');
+        for _ in 0..dash_count {
+            s.push(self.charset.dash)
+        }
+        s.push(' ');
+        s.push_str(&value);
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indoc::indoc;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(0, "2015-01-01:2015-03-31", "", "")]
+    #[case(
+        1,
+        "2015-01-01:2015-04-30",
+        r#"
+            {"d":"2014-12-31","c":"food/groceries","a":-99999}
+            {"d":"2015-01-05","c":"food/groceries","a":-10000}
+            {"d":"2015-01-20","c":"food/restaurant","a":-5000}
+            {"d":"2015-03-10","c":"food/groceries","a":-15000}
+            {"d":"2015-04-10","c":"food/groceries","a":-10000}
+            {"d":"2015-02-01","c":"salary","a":300000}
+        "#,
+        indoc!("
+            salary
+            |-- Monthly average -------- 750.00
+            |-- Monthly median ----------- 0.00
+            |-- Monthly min -------------- 0.00
+            |-- Monthly max ---------- 3,000.00
+            |-- Monthly std dev ------ 1,299.04
+            |-- Transactions ---------------- 1
+            |-- Average transaction -- 3,000.00
+            `-- Trend per month ------- (300.00)
+            food
+            |-- Monthly average ------- (100.00)
+            |-- Monthly median -------- (125.00)
+            |-- Monthly min ----------- (150.00)
+            |-- Monthly max -------------- 0.00
+            |-- Monthly std dev --------- 61.24
+            |-- Transactions ---------------- 4
+            |-- Average transaction --- (100.00)
+            `-- Trend per month ---------- 0.00
+        "),
+    )]
+    fn test_to_tree(
+        #[case] level: usize,
+        #[case] bounds: base::Interval,
+        #[case] rl: base::Recordlist,
+        #[case] want: &str,
+    ) {
+        let config = Config {
+            charset: base::Charset::default(),
+            bounds,
+            level,
+            rl,
+        };
+        assert_eq!(config.to_tree().to_string(), want);
+    }
+}
@@ -1,9 +1,16 @@
+//! Tree of totals by category.
+
 use crate::base;
 
+/// Totals of incoming, outgoing and net amounts by category.
 #[derive(Debug, PartialEq, Eq)]
 pub struct Config {
+    /// Characters to draw the tree with.
     pub charset: base::Charset,
+    /// Number of category levels to group by. Categories with more levels count
+    /// towards their ancestor at this level.
     pub level: usize,
+    /// Records to sum.
     pub rl: base::Recordlist,
 }
 
@@ -13,9 +20,16 @@ impl Config {
     const NET: &str = "Net";
     const TOTAL: &str = "Total";
 
-    pub fn to_tree<'a>(&'a self) -> base::Tree {
-        let mut pos = base::Aggregate::<&str, base::Cents>::default();
-        let mut neg = base::Aggregate::<&str, base::Cents>::default();
+    /// Returns the totals of incoming and of outgoing amounts, by category
+    /// truncated to `level`.
+    pub fn totals(
+        &self,
+    ) -> (
+        base::Aggregate<&str, base::Cents>,
+        base::Aggregate<&str, base::Cents>,
+    ) {
+        let mut pos = base::Aggregate::default();
+        let mut neg = base::Aggregate::default();
         for r in self.rl.iter() {
             match r.amount().cmp(&base::Cents(0)) {
                 std::cmp::Ordering::Greater => pos.add(r.category().level(self.level), r.amount()),
@@ -23,6 +37,12 @@ impl Config {
                 _ => {}
             }
         }
+        (pos, neg)
+    }
+
+    /// Builds a tree with the totals under `In`, `Out` and `Net`.
+    pub fn to_tree<'a>(&'a self) -> base::Tree {
+        let (pos, neg) = self.totals();
 
         let sort_agg = |agg: &base::Aggregate<&'a str, base::Cents>| {
             let mut v = agg.iter().collect::<Vec<_>>();
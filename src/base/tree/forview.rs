@@ -1,8 +1,14 @@
+//! Tree of records by date.
+
 use crate::base;
 
+/// Records listed by date.
 pub struct Config {
+    /// Characters to draw the tree with.
     pub charset: base::Charset,
+    /// Index shown for the first record of each date.
     pub first_iid: usize,
+    /// Records to list.
     pub rl: base::Recordlist,
     /// Additional transformations to apply to a record's as-a-node string
     /// representation (records are leaf nodes). If not `None`, this is called
@@ -57,11 +63,30 @@ impl std::fmt::Debug for Config {
 }
 
 impl Config {
+    /// Builds a tree with a node per date and a leaf per record.
     pub fn to_tree(&self) -> base::Tree {
-        let alignment_charlen = self.get_alignment_charlen();
+        let records = self.rl.iter_with_iid().collect::<Vec<_>>();
+        let alignment_charlen = self.get_alignment_charlen(&records);
+        let indent_charlen = self.charset.tree_space.chars().count();
         let mut root = base::tree::Node::default();
-        for (iid0, r) in self.rl.iter_with_iid() {
-            self.make_year_node(&mut root, r, iid0, alignment_charlen);
+        for run in records.chunk_by(is_same_split) {
+            let day = self.make_year_node(&mut root, run[0].1.date());
+            if let [(first_iid0, _), .., (last_iid0, _)] = run {
+                let total = run.iter().map(|(_, r)| r.amount()).sum();
+                let label = self.split_label(*first_iid0, *last_iid0);
+                let data = self.node_data(&label, total, alignment_charlen, "split");
+                day.children.push(base::tree::Node::new(data.into()));
+                let split = last_child(day);
+                for &(iid0, r) in run {
+                    let data = self.leaf_data(r, iid0, alignment_charlen - indent_charlen);
+                    split.children.push(base::tree::Node::new(data.into()));
+                }
+            } else {
+                for &(iid0, r) in run {
+                    let data = self.leaf_data(r, iid0, alignment_charlen);
+                    day.children.push(base::tree::Node::new(data.into()));
+                }
+            }
         }
         base::Tree {
             charset: self.charset.clone(),
@@ -69,43 +94,90 @@ impl Config {
         }
     }
 
-    fn get_alignment_charlen(&self) -> usize {
-        let char_count = |(iid0, r): (usize, &base::Record)| -> usize {
-            base::util::count_digits((iid0 + self.first_iid) as u64)
+    fn get_alignment_charlen(&self, records: &[(usize, &base::Record)]) -> usize {
+        let indent_charlen = self.charset.tree_space.chars().count();
+        let char_count = |label_charlen: usize, amount: base::Cents| -> usize {
+            label_charlen
                 + base::util::BOUNDING_SPACES_COUNT
                 + base::util::MIN_DASHES_COUNT
-                + r.amount().charlen_for_alignment()
+                + amount.charlen_for_alignment()
         };
-        self.rl
-            .iter_with_iid()
-            .map(char_count)
+        records
+            .chunk_by(is_same_split)
+            .flat_map(|run| {
+                let iid_charlen =
+                    |iid0: usize| base::util::count_digits((iid0 + self.first_iid) as u64);
+                match run {
+                    [(first_iid0, _), .., (last_iid0, _)] => {
+                        let total = run.iter().map(|(_, r)| r.amount()).sum();
+                        let label = self.split_label(*first_iid0, *last_iid0);
+                        std::iter::once(char_count(label.chars().count(), total))
+                            .chain(run.iter().map(|&(iid0, r)| {
+                                indent_charlen + char_count(iid_charlen(iid0), r.amount())
+                            }))
+                            .collect::<Vec<_>>()
+                    }
+                    _ => run
+                        .iter()
+                        .map(|&(iid0, r)| char_count(iid_charlen(iid0), r.amount()))
+                        .collect(),
+                }
+            })
             .max()
             .unwrap_or_default()
     }
 
-    /// Constructs the string payload for the leaf node representing the given
-    /// record.
-    fn leaf_data(&self, r: &base::Record, iid0: usize, alignment_charlen: usize) -> String {
-        let iid = iid0 + self.first_iid;
+    /// Labels the node of a split transaction with the range of its records'
+    /// indices-in-date.
+    fn split_label(&self, first_iid0: usize, last_iid0: usize) -> String {
+        format!(
+            "{}-{}",
+            first_iid0 + self.first_iid,
+            last_iid0 + self.first_iid
+        )
+    }
+
+    /// Formats `label`, then `amount` right-aligned to `alignment_charlen`, and
+    /// then `description`.
+    fn node_data(
+        &self,
+        label: &str,
+        amount: base::Cents,
+        alignment_charlen: usize,
+        description: &str,
+    ) -> String {
         let dash_count = alignment_charlen
-            - base::util::count_digits(iid as u64)
+            - label.chars().count()
             - base::util::BOUNDING_SPACES_COUNT
-            - r.amount().charlen_for_alignment();
+            - amount.charlen_for_alignment();
         let mut s = String::new();
-        s.push_str(&iid.to_string());
+        s.push_str(label);
         s.push(' ');
         for _ in 0..dash_count {
             s.push(self.charset.dash)
         }
         s.push(' ');
-        s.push_str(&r.amount().to_string());
+        s.push_str(&amount.to_string());
         // Give non-negative amounts a trailing space in order to vertically
-        // align the beginning of `category`.
-        if r.amount().0 >= 0 {
+        // align the beginning of `description`.
+        if amount.0 >= 0 {
             s.push(' ');
         }
         s.push(' ');
-        s.push_str(r.category().as_str());
+        s.push_str(description);
+        s
+    }
+
+    /// Constructs the string payload for the leaf node representing the given
+    /// record.
+    fn leaf_data(&self, r: &base::Record, iid0: usize, alignment_charlen: usize) -> String {
+        let iid = iid0 + self.first_iid;
+        let mut s = self.node_data(
+            &iid.to_string(),
+            r.amount(),
+            alignment_charlen,
+            r.category().as_str(),
+        );
         if !r.note().is_empty() {
             s.push_str(": ");
             s.push_str(r.note());
@@ -116,24 +188,12 @@ impl Config {
         }
     }
 
-    fn make_leaf_node(
-        &self,
-        day: &mut base::tree::Node,
-        r: &base::Record,
-        iid0: usize,
-        alignment_charlen: usize,
-    ) {
-        let data = self.leaf_data(r, iid0, alignment_charlen);
-        day.children.push(base::tree::Node::new(data.into()));
-    }
-
-    fn make_day_node(
+    /// Returns the node of the day of `date`, creating it if needed.
+    fn make_day_node<'a>(
         &self,
-        month: &mut base::tree::Node,
-        r: &base::Record,
-        iid0: usize,
-        alignment_charlen: usize,
-    ) {
+        month: &'a mut base::tree::Node,
+        date: base::Date,
+    ) -> &'a mut base::tree::Node {
         #[rustfmt::skip]
         let strs = &[
             "",
@@ -142,47 +202,42 @@ impl Config {
             "21st", "22nd", "23rd", "24th", "25th", "26th", "27th", "28th", "29th", "30th",
             "31st",
         ];
-        let s = strs[r.date().day() as usize];
+        let s = strs[date.day() as usize];
         if !last_child_exists_and_has_expected_data(month, s) {
             month.children.push(base::tree::Node::new(s.into()))
         }
-        let day = last_child(month);
-        self.make_leaf_node(day, r, iid0, alignment_charlen);
+        last_child(month)
     }
 
-    fn make_month_node(
+    fn make_month_node<'a>(
         &self,
-        year: &mut base::tree::Node,
-        r: &base::Record,
-        iid0: usize,
-        alignment_charlen: usize,
-    ) {
+        year: &'a mut base::tree::Node,
+        date: base::Date,
+    ) -> &'a mut base::tree::Node {
         #[rustfmt::skip]
         let strs = &[
             "",
             "Jan", "Feb", "Mar", "Apr", "May", "Jun",
             "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
         ];
-        let s = strs[r.date().month() as usize];
+        let s = strs[date.month() as usize];
         if !last_child_exists_and_has_expected_data(year, s) {
             year.children.push(base::tree::Node::new(s.into()))
         }
         let month = last_child(year);
-        self.make_day_node(month, r, iid0, alignment_charlen);
+        self.make_day_node(month, date)
     }
 
-    fn make_year_node(
+    fn make_year_node<'a>(
         &self,
-        root: &mut base::tree::Node,
-        r: &base::Record,
-        iid0: usize,
-        alignment_charlen: usize,
-    ) {
+        root: &'a mut base::tree::Node,
+        date: base::Date,
+    ) -> &'a mut base::tree::Node {
         let buf = [
-            (r.date().year() / 1000) as u8 + b'0',
-            (r.date().year() / 100 % 10) as u8 + b'0',
-            (r.date().year() / 10 % 10) as u8 + b'0',
-            (r.date().year() % 10) as u8 + b'0',
+            (date.year() / 1000) as u8 + b'0',
+            (date.year() / 100 % 10) as u8 + b'0',
+            (date.year() / 10 % 10) as u8 + b'0',
+            (date.year() % 10) as u8 + b'0',
         ];
         let s = std::str::from_utf8(&buf).expect("all chars should be ascii");
         if !last_child_exists_and_has_expected_data(root, s) {
@@ -190,10 +245,15 @@ impl Config {
                 .push(base::tree::Node::new(s.to_string().into()))
         }
         let year = last_child(root);
-        self.make_month_node(year, r, iid0, alignment_charlen);
+        self.make_month_node(year, date)
     }
 }
 
+/// Whether two consecutive records belong to the same split transaction.
+fn is_same_split(&(_, a): &(usize, &base::Record), &(_, b): &(usize, &base::Record)) -> bool {
+    a.group().is_some() && a.group() == b.group() && a.date() == b.date()
+}
+
 fn last_child_exists_and_has_expected_data(node: &base::tree::Node, expected_data: &str) -> bool {
     match node.children.last() {
         Some(child) => child.data == expected_data,
@@ -321,6 +381,40 @@ mod tests {
         assert_eq!(tr.to_string(), want);
     }
 
+    #[test]
+    fn test_to_tree_split() {
+        let rl = r#"
+            {"d":"2015-03-30","c":"aaa","a":100}
+            {"d":"2015-03-30","c":"groceries","a":-10000,"n":"costco","g":0}
+            {"d":"2015-03-30","c":"household","a":-3000,"g":0}
+            {"d":"2015-03-30","c":"pharmacy","a":-2000,"g":0}
+            {"d":"2015-03-31","c":"bbb","a":-100,"g":1}
+        "#
+        .parse::<base::Recordlist>()
+        .unwrap();
+        let want = indoc!(
+            "
+            2015
+            `-- Mar
+                |-- 30th
+                |   |-- 1 --------- 1.00  aaa
+                |   `-- 2-4 ---- (150.00) split
+                |       |-- 2 -- (100.00) groceries: costco
+                |       |-- 3 --- (30.00) household
+                |       `-- 4 --- (20.00) pharmacy
+                `-- 31st
+                    `-- 1 -------- (1.00) bbb
+            "
+        );
+        let config = Config {
+            charset: base::Charset::default(),
+            first_iid: 1,
+            leaf_string_postprocessor: None,
+            rl,
+        };
+        assert_eq!(config.to_tree().to_string(), want);
+    }
+
     #[test]
     fn test_leaf_string_postprocessor() {
         let rl = r#"
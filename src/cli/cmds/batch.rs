@@ -0,0 +1,196 @@
+use anyhow::Context;
+
+use crate::cli;
+
+/// Run commands from a script, writing changes only if all of them succeed
+///
+/// Each line of the script is a 'log', 'logt', 'rm --confirm', or 'lim --set'
+/// command, without the leading 'ledger', and quotes words the way a POSIX
+/// shell does. Blank lines and lines starting with '#' are ignored. Every
+/// command sees the changes made by the ones before it, but nothing is written
+/// unless all of them succeed.
+#[derive(clap::Parser)]
+pub struct Batch {
+    /// Path of the script, or '-' to read it from stdin
+    #[arg(default_value = "-")]
+    script: std::path::PathBuf,
+}
+
+impl Batch {
+    pub fn run(&self, fs: &cli::Fs) -> anyhow::Result<cli::Output> {
+        let script = if self.script.as_os_str() == "-" {
+            std::io::read_to_string(std::io::stdin()).context("failed to read stdin")?
+        } else {
+            std::fs::read_to_string(&self.script)
+                .with_context(|| format!("failed to read '{}'", self.script.display()))?
+        };
+        let (count, warnings) = execute(&script, fs)?;
+        let mut s = warnings
+            .iter()
+            .map(|w| format!("{}\n", w))
+            .collect::<String>();
+        s.push_str(&match count {
+            1 => "1 command run.".to_string(),
+            n => format!("{} commands run.", n),
+        });
+        Ok(cli::Output::Str(s))
+    }
+}
+
+/// Runs every command in `script`, then writes their changes to `fs`. Returns
+/// the number of commands run, and the warnings they raised, each prefixed
+/// with its line number.
+fn execute(script: &str, fs: &cli::Fs) -> anyhow::Result<(usize, Vec<String>)> {
+    fs.defer();
+    let res = execute_lines(script, fs);
+    match res {
+        Ok(_) => fs.flush().context("failed to write changes")?,
+        Err(_) => fs.discard(),
+    }
+    res
+}
+
+fn execute_lines(script: &str, fs: &cli::Fs) -> anyhow::Result<(usize, Vec<String>)> {
+    let mut count = 0;
+    let mut warnings = Vec::new();
+    for (i, line) in script.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line_warnings = execute_line(line, fs).with_context(|| format!("line {}", i + 1))?;
+        warnings.extend(
+            line_warnings
+                .into_iter()
+                .map(|w| format!("line {}: {}", i + 1, w)),
+        );
+        count += 1;
+    }
+    Ok((count, warnings))
+}
+
+/// Runs the command on `line`. Returns the warnings it raised.
+fn execute_line(line: &str, fs: &cli::Fs) -> anyhow::Result<Vec<String>> {
+    let words = cli::util::split_words(line)?;
+    let args = std::iter::once("ledger".to_string()).chain(words);
+    let root = match <cli::Root as clap::Parser>::try_parse_from(args) {
+        Ok(root) => root,
+        Err(e) => anyhow::bail!("{}", cli::util::clap_error_message(&e)),
+    };
+    if !root.is_batchable() {
+        anyhow::bail!("only 'log', 'logt', 'rm --confirm' and 'lim --set' are allowed")
+    }
+    Ok(match root.run(fs)? {
+        cli::Output::Logged(_, warnings) => warnings,
+        _ => Vec::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::base;
+    use indoc::indoc;
+    use rstest::rstest;
+
+    use super::*;
+
+    const CONFIG: &str = r#"{"templates": {"t": [{"category": "bbb", "amount": 200}]}}"#;
+    const RL: &str = r#"{"d":"2015-03-30","c":"aaa","a":100,"i":"000001"}"#;
+
+    #[test]
+    fn test_execute() {
+        let (fs, _td) = cli::testing::tempfs();
+        cli::testing::StrState::new()
+            .with_config(CONFIG)
+            .with_rl(RL)
+            .to_fs(&fs);
+        let script = indoc!(
+            r#"
+            # Comment.
+            log aaa 3 2015-03-29 -n "x y"
+
+            logt t 2015-03-29
+            rm 2015-03-30 0 --confirm
+            lim --set 5000 2015
+            "#
+        );
+        assert_eq!(execute(script, &fs).unwrap(), (4, vec![]));
+        let rl = fs.read::<base::Recordlist>().unwrap();
+        let got = rl
+            .iter()
+            .map(|r| {
+                (
+                    r.date().to_string(),
+                    r.category().as_str(),
+                    r.amount().0,
+                    r.note(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            got,
+            [
+                ("2015-03-29".to_string(), "aaa", 300, "x y"),
+                ("2015-03-29".to_string(), "bbb", 200, ""),
+            ]
+        );
+        assert_eq!(
+            fs.read::<base::Limitaccounts>().unwrap(),
+            r#"{"2015": 500000}"#.parse().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_execute_warnings() {
+        let (fs, _td) = cli::testing::tempfs();
+        cli::testing::StrState::new()
+            .with_config(r#"{"limAccountType": "tfsa"}"#)
+            .with_rl(RL)
+            .with_limits(r#"{"2015": 300}"#)
+            .to_fs(&fs);
+        let script = "log aaa 3\nlog aaa -1\nlog aaa 4";
+        assert_eq!(
+            execute(script, &fs).unwrap(),
+            (
+                3,
+                vec![
+                    "line 1: TFSA ROOM EXCEEDED BY 1.00".to_string(),
+                    "line 3: TFSA ROOM EXCEEDED BY 4.00".to_string(),
+                ]
+            )
+        );
+    }
+
+    #[rstest]
+    #[case("log ccc 1", "line 1: nonexistent category")]
+    #[case("log aaa 1\n\nlog 'aaa 1", "line 3: unterminated quote")]
+    #[case(
+        "log aaa 1\nview",
+        "line 2: only 'log', 'logt', 'rm --confirm' and 'lim --set' are allowed"
+    )]
+    #[case(
+        "rm 2015-03-30 0",
+        "line 1: only 'log', 'logt', 'rm --confirm' and 'lim --set' are allowed"
+    )]
+    #[case(
+        "lim",
+        "line 1: only 'log', 'logt', 'rm --confirm' and 'lim --set' are allowed"
+    )]
+    #[case("log aaa 1\nbogus", "line 2: unrecognized subcommand 'bogus'")]
+    #[case("log aaa 1\nlogt u", "line 2: unknown template")]
+    fn test_execute_err(#[case] script: &str, #[case] want: &str) {
+        let (fs, _td) = cli::testing::tempfs();
+        let initial_state = cli::testing::StrState::new()
+            .with_config(CONFIG)
+            .with_rl(RL);
+        initial_state.to_fs(&fs);
+        let e = execute(script, &fs).unwrap_err();
+        let got = e
+            .chain()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join(": ");
+        assert_eq!(got, want);
+        assert_eq!(cli::testing::State::from_fs(&fs), initial_state.to_state());
+    }
+}
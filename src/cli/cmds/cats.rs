@@ -13,12 +13,24 @@ pub struct Cats {
     /// pattern.
     #[arg(default_value = "*")]
     pub category: Vec<String>,
+
+    /// Display category aliases instead, with the categories they stand for
+    #[arg(long, conflicts_with_all = ["fullmatch", "category"])]
+    pub aliases: bool,
 }
 
 impl Cats {
-    pub fn run(&self, rl: base::Recordlist) -> anyhow::Result<cli::Output> {
-        let categories = cli::util::preprocess_categories(&self.category, self.fullmatch);
-        let rl = cli::util::filter_rl::<_, &str>(&rl, base::Interval::MAX, &categories, &[]);
+    pub fn run(&self, rl: base::Recordlist, config: &base::Config) -> anyhow::Result<cli::Output> {
+        if self.aliases {
+            return Ok(cli::Output::Str(aliases(config)));
+        }
+        let categories = self
+            .category
+            .iter()
+            .map(|s| config.expand_alias(s).to_string())
+            .collect::<Vec<_>>();
+        let categories = base::preprocess_categories(&categories, self.fullmatch);
+        let rl = base::filter_rl::<_, &str>(&rl, base::Interval::MAX, &categories, &[]);
         let mut cats = rl.iter().map(|r| r.category().as_str()).collect::<Vec<_>>();
         cats.sort();
         cats.dedup();
@@ -30,6 +42,26 @@ impl Cats {
     }
 }
 
+/// Returns one line per alias, with the categories aliases stand for aligned
+/// in a column.
+fn aliases(config: &base::Config) -> String {
+    if config.aliases.is_empty() {
+        return "No aliases.".to_string();
+    }
+    let width = config
+        .aliases
+        .keys()
+        .map(|alias| alias.chars().count())
+        .max()
+        .unwrap_or_default();
+    config
+        .aliases
+        .iter()
+        .map(|(alias, category)| format!("{:width$}  {}", alias, category))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -109,5 +141,38 @@ mod tests {
                 ),
             }
         ),
+        (
+            alias,
+            cli::testing::Case {
+                invocations: &[
+                    cli::testing::Invocation {
+                        args: &["", "cats", "--fullmatch", "a"],
+                        res: cli::testing::ResultMatcher::OkStrGlob("aaa"),
+                    },
+                    cli::testing::Invocation {
+                        args: &["", "cats", "--aliases"],
+                        res: cli::testing::ResultMatcher::OkStrGlob("a     aaa\nbbbb  bbb"),
+                    },
+                ],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(r#"{"aliases": {"a": "aaa", "bbbb": "bbb"}}"#)
+                    .with_rl(
+                        r#"
+                            {"d":"2015-01-01","c":"bbb","a":100}
+                            {"d":"2016-01-01","c":"aaa","a":100}
+                        "#
+                    ),
+            }
+        ),
+        (
+            no_aliases,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "cats", "--aliases"],
+                    res: cli::testing::ResultMatcher::OkStrGlob("no aliases."),
+                }],
+                initial_state: cli::testing::StrState::new().with_config("{}"),
+            }
+        ),
     ];
 }
@@ -0,0 +1,322 @@
+use crate::base;
+use crate::cli;
+
+/// Print a shell completion script
+///
+/// Besides subcommands and options, the script completes categories and
+/// category aliases for 'log', '--categories' and '--not-categories', template
+/// names for 'logt', and account types for 'lim --view', using the repository
+/// in the current directory. To enable completions, add the line for your shell
+/// to its startup file:
+///   bash: eval "$(ledger completions bash)"
+///   zsh:  eval "$(ledger completions zsh)"
+///   fish: ledger completions fish | source
+#[derive(clap::Parser)]
+#[command(verbatim_doc_comment)]
+pub struct Completions {
+    /// Shell to print the script for
+    shell: Shell,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Completes the values of arguments from the current repository. Called by
+/// completion scripts with the words of the command line up to and including
+/// the one being completed, and prints one candidate per line.
+#[derive(clap::Parser)]
+pub struct Complete {
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    words: Vec<String>,
+}
+
+/// Name of the hidden subcommand that runs [`Complete`].
+pub const COMPLETE_NAME: &str = "__complete";
+
+impl Completions {
+    pub fn run(&self) -> anyhow::Result<cli::Output> {
+        let mut cmd = <cli::Root as clap::CommandFactory>::command();
+        let name = cmd.get_name().to_string();
+        let mut buf = Vec::new();
+        let (shell, values) = match self.shell {
+            Shell::Bash => (clap_complete::Shell::Bash, BASH_VALUES),
+            Shell::Zsh => (clap_complete::Shell::Zsh, ZSH_VALUES),
+            Shell::Fish => (clap_complete::Shell::Fish, FISH_VALUES),
+        };
+        clap_complete::generate(shell, &mut cmd, &name, &mut buf);
+        let script = String::from_utf8(buf).expect("script should be UTF-8");
+        let values = values
+            .replace("{name}", &name)
+            .replace("{complete}", COMPLETE_NAME);
+        Ok(cli::Output::Str(format!("{}\n{}", script, values)))
+    }
+}
+
+// Each of these wraps the generated completion function, offering the values
+// from 'ledger __complete' instead whenever there are any.
+
+// Bash splits '--categories=aaa' into three words, so they are joined back
+// together, and the replies then only replace what follows the '='.
+const BASH_VALUES: &str = r#"_{name}_values() {
+    local words=() word values=() value i
+    for ((i = 0; i <= COMP_CWORD; i++)); do
+        word=${COMP_WORDS[i]}
+        if [[ ${#words[@]} -gt 0 && ${words[-1]} == --* && ${words[-1]} != *=* && $word == = ]] ||
+            [[ ${#words[@]} -gt 0 && ${words[-1]} == --*= ]]; then
+            words[-1]+=$word
+        else
+            words+=("$word")
+        fi
+    done
+    while IFS= read -r value; do
+        [[ -n $value ]] && values+=("$value")
+    done < <({name} {complete} -- "${words[@]}" 2>/dev/null)
+    if [[ ${words[-1]} == --*=* ]]; then
+        values=("${values[@]#*=}")
+    fi
+    if [[ ${#values[@]} -gt 0 ]]; then
+        COMPREPLY=("${values[@]}")
+    else
+        _{name} "$@"
+    fi
+}
+
+complete -F _{name}_values -o bashdefault -o default {name}
+"#;
+
+const ZSH_VALUES: &str = r#"_{name}_values() {
+    local -a values
+    values=(${(f)"$({name} {complete} -- "${(@)words[1,CURRENT]}" 2>/dev/null)"})
+    if (( ${#values} )); then
+        compadd -a values
+    else
+        _{name} "$@"
+    fi
+}
+
+compdef _{name}_values {name}
+"#;
+
+const FISH_VALUES: &str = r#"function __fish_{name}_values
+    set -l tokens (commandline -opc)
+    set -l current (commandline -ct)
+    {name} {complete} -- $tokens "$current" 2>/dev/null
+end
+
+complete -c {name} -f -n '__fish_{name}_values | string length -q' -a '(__fish_{name}_values)'
+"#;
+
+/// Kinds of values completed from the repository.
+#[derive(Debug, PartialEq, Eq)]
+enum Kind {
+    Categories,
+    Templates,
+    AccountTypes,
+}
+
+impl Complete {
+    pub fn run(&self, fs: &cli::Fs) -> anyhow::Result<cli::Output> {
+        // Completion must never get in the way, so failures are not reported.
+        let candidates = match self.words.split_last() {
+            Some((current, before)) if fs.is_repo() => match kind(before, current) {
+                Some((kind, separated, prefix)) => {
+                    candidates(fs, kind, &current[prefix.len()..], separated)
+                        .into_iter()
+                        .map(|c| format!("{}{}", prefix, c))
+                        .collect()
+                }
+                None => Vec::new(),
+            },
+            _ => Vec::new(),
+        };
+        Ok(cli::Output::Str(candidates.join("\n")))
+    }
+}
+
+/// Returns the kind of value expected in `current`, the word being completed,
+/// given `before`, the words preceding it, starting with the program name. Also
+/// returns whether the value is a comma-separated list, and the part of
+/// `current` that precedes the value, such as `--categories=` or `-c`.
+fn kind<'a>(before: &[String], current: &'a str) -> Option<(Kind, bool, &'a str)> {
+    let root = <cli::Root as clap::CommandFactory>::command();
+    let sub = root.find_subcommand(before.get(1)?)?;
+    // Returns the option in `word`, and where its value starts if the value is
+    // attached, as in '--categories=aaa' or '-caaa'.
+    let find_option = |word: &str| {
+        if let Some(long) = word.strip_prefix("--") {
+            let (name, value_start) = match long.split_once('=') {
+                Some((name, _)) => (name, Some(2 + name.len() + 1)),
+                None => (long, None),
+            };
+            let arg = sub.get_arguments().find(|a| a.get_long() == Some(name))?;
+            return Some((arg, value_start));
+        }
+        let mut chars = word.chars();
+        if chars.next() != Some('-') {
+            return None;
+        }
+        let short = chars.next()?;
+        let arg = sub.get_arguments().find(|a| a.get_short() == Some(short))?;
+        let value_start = match chars.next() {
+            Some(_) if arg.get_action().takes_values() => Some(1 + short.len_utf8()),
+            Some(_) => return None,
+            None => None,
+        };
+        Some((arg, value_start))
+    };
+    let option_kind = |arg: &clap::Arg| match (sub.get_name(), arg.get_id().as_str()) {
+        (_, "categories" | "not_categories") => Some((Kind::Categories, true)),
+        ("lim", "view") => Some((Kind::AccountTypes, false)),
+        _ => None,
+    };
+
+    // Walk the arguments, counting positionals and noting whether the last word
+    // is an option expecting a value.
+    let mut positionals = 0;
+    let mut expecting = None;
+    let mut only_positionals = false;
+    for word in &before[2..] {
+        if expecting.take().is_some() {
+            continue;
+        }
+        if only_positionals || !word.starts_with('-') || word == "-" {
+            positionals += 1;
+        } else if word == "--" {
+            only_positionals = true;
+        } else if let Some((arg, None)) = find_option(word)
+            && arg.get_action().takes_values()
+        {
+            expecting = Some(arg);
+        }
+    }
+
+    if let Some(arg) = expecting {
+        let (kind, separated) = option_kind(arg)?;
+        return Some((kind, separated, ""));
+    }
+    if !only_positionals && current.starts_with('-') {
+        let (arg, value_start) = find_option(current)?;
+        let (kind, separated) = option_kind(arg)?;
+        return Some((kind, separated, &current[..value_start?]));
+    }
+    let arg = sub.get_positionals().nth(positionals)?;
+    match (sub.get_name(), arg.get_id().as_str()) {
+        ("log", "category") => Some((Kind::Categories, false, "")),
+        ("logt", "template") => Some((Kind::Templates, false, "")),
+        _ => None,
+    }
+}
+
+/// Returns the values of `kind` that start with `current`. If `separated`, only
+/// the part of `current` after its last comma is completed.
+fn candidates(fs: &cli::Fs, kind: Kind, current: &str, separated: bool) -> Vec<String> {
+    let (head, current) = match current.rfind(',') {
+        Some(i) if separated => current.split_at(i + 1),
+        _ => ("", current),
+    };
+    let values = match kind {
+        Kind::Categories => {
+            let mut values = std::collections::BTreeSet::new();
+            if let Ok(rl) = fs.read::<base::Recordlist>() {
+                values.extend(rl.iter().map(|r| r.category().as_str().to_string()));
+            }
+            if let Ok(config) = fs.read::<base::Config>() {
+                values.extend(config.aliases.into_keys());
+            }
+            values.into_iter().collect()
+        }
+        Kind::Templates => match fs.read::<base::Config>() {
+            Ok(config) => config.templates.into_keys().collect(),
+            Err(_) => Vec::new(),
+        },
+        Kind::AccountTypes => base::Limitkind::BUILTIN
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+    };
+    values
+        .into_iter()
+        .filter(|v| v.starts_with(current))
+        .map(|v| format!("{}{}", head, v))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(&["ledger"], "", None)]
+    #[case(&["ledger", "bogus"], "", None)]
+    #[case(&["ledger", "log"], "", Some((Kind::Categories, false, "")))]
+    #[case(&["ledger", "log", "-n", "x y"], "", Some((Kind::Categories, false, "")))]
+    #[case(&["ledger", "log", "--create"], "", Some((Kind::Categories, false, "")))]
+    #[case(&["ledger", "log", "aaa"], "", None)]
+    #[case(&["ledger", "log", "--", "-1"], "", None)]
+    #[case(&["ledger", "log", "aaa", "1", "-S"], "", None)]
+    #[case(&["ledger", "logt"], "", Some((Kind::Templates, false, "")))]
+    #[case(&["ledger", "logt", "t"], "", None)]
+    #[case(&["ledger", "view", "-c"], "", Some((Kind::Categories, true, "")))]
+    #[case(&["ledger", "sum", "2015", "--not-categories"], "", Some((Kind::Categories, true, "")))]
+    #[case(&["ledger", "sum", "-x", "aaa"], "", None)]
+    #[case(&["ledger", "lim", "--view"], "", Some((Kind::AccountTypes, false, "")))]
+    #[case(&["ledger", "lim", "-a"], "", None)]
+    #[case(&["ledger", "view"], "--categories=aaa,b", Some((Kind::Categories, true, "--categories=")))]
+    #[case(&["ledger", "view"], "-caaa", Some((Kind::Categories, true, "-c")))]
+    #[case(&["ledger", "lim"], "--view=tf", Some((Kind::AccountTypes, false, "--view=")))]
+    #[case(&["ledger", "view"], "--categories", None)]
+    #[case(&["ledger", "view"], "--bogus=aaa", None)]
+    #[case(&["ledger", "view", "-caaa"], "", None)]
+    #[case(&["ledger", "log", "--note=x"], "", Some((Kind::Categories, false, "")))]
+    fn test_kind(
+        #[case] before: &[&str],
+        #[case] current: &str,
+        #[case] want: Option<(Kind, bool, &str)>,
+    ) {
+        let before = before.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert_eq!(kind(&before, current), want);
+    }
+
+    #[rstest]
+    #[case(&["ledger", "log", ""], &["aaa", "aab", "ab", "bbb"])]
+    #[case(&["ledger", "log", "aa"], &["aaa", "aab"])]
+    #[case(&["ledger", "log", "ccc"], &[])]
+    #[case(&["ledger", "view", "-c", "aaa,b"], &["aaa,bbb"])]
+    #[case(&["ledger", "view", "--categories=aaa,b"], &["--categories=aaa,bbb"])]
+    #[case(&["ledger", "view", "-ca"], &["-caaa", "-caab", "-cab"])]
+    #[case(&["ledger", "logt", ""], &["t", "u"])]
+    #[case(&["ledger", "lim", "--view", "tf"], &["tfsa"])]
+    #[case(&["ledger", "view", ""], &[])]
+    fn test_complete(#[case] words: &[&str], #[case] want: &[&str]) {
+        let (fs, _td) = cli::testing::tempfs();
+        cli::testing::StrState::new()
+            .with_config(r#"{"templates": {"t": [], "u": []}, "aliases": {"ab": "bbb"}}"#)
+            .with_rl(
+                r#"
+                {"d":"2015-03-30","c":"bbb","a":100,"i":"000001"}
+                {"d":"2015-03-30","c":"aab","a":100,"i":"000002"}
+                {"d":"2015-03-30","c":"aaa","a":100,"i":"000003"}
+                "#,
+            )
+            .to_fs(&fs);
+        let words = words.iter().map(|s| s.to_string()).collect();
+        let got = Complete { words }.run(&fs).unwrap();
+        assert_eq!(got.to_string(), format!("{}\n", want.join("\n")));
+    }
+
+    #[rstest]
+    #[case(Shell::Bash, "complete -F _ledger_values")]
+    #[case(Shell::Zsh, "compdef _ledger_values ledger")]
+    #[case(Shell::Fish, "ledger __complete -- $tokens")]
+    fn test_completions(#[case] shell: Shell, #[case] want: &str) {
+        let got = Completions { shell }.run().unwrap().to_string();
+        assert!(got.contains(want), "{}", got);
+        assert!(got.contains("logt"), "{}", got);
+    }
+}
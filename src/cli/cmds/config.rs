@@ -0,0 +1,245 @@
+use anyhow::Context;
+
+use crate::base;
+use crate::cli;
+
+/// View and change repository settings
+///
+/// Keys are those of the config file, such as 'useColoredOutput'. Values are
+/// written as JSON, except that strings may be written without quotes. Changes
+/// are validated before the config is written.
+#[derive(clap::Parser)]
+pub struct Config {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(clap::Subcommand)]
+enum Commands {
+    Get(Get),
+    Set(Set),
+    Unset(Unset),
+    List(List),
+}
+
+/// Display the value of a setting
+#[derive(clap::Args)]
+struct Get {
+    /// Name of the setting
+    key: String,
+}
+
+/// Change the value of a setting
+#[derive(clap::Args)]
+struct Set {
+    /// Name of the setting
+    key: String,
+
+    /// New value of the setting
+    #[arg(allow_hyphen_values = true)]
+    value: String,
+}
+
+/// Restore a setting to its default value
+#[derive(clap::Args)]
+struct Unset {
+    /// Name of the setting
+    key: String,
+}
+
+/// Display every setting's default value and value
+#[derive(clap::Args)]
+struct List {}
+
+impl Config {
+    pub fn run(&self, config: &base::Config, fs: &cli::Fs) -> anyhow::Result<cli::Output> {
+        let mut values = to_map(config);
+        let (key, value) = match &self.command {
+            Commands::Get(cmd) => {
+                let value = get(&values, &cmd.key)?;
+                return Ok(cli::Output::Str(value.to_string()));
+            }
+            Commands::List(_) => return Ok(cli::Output::Str(list(&values))),
+            Commands::Set(cmd) => {
+                get(&values, &cmd.key)?;
+                let value = serde_json::from_str(&cmd.value)
+                    .unwrap_or_else(|_| serde_json::Value::String(cmd.value.clone()));
+                (&cmd.key, value)
+            }
+            Commands::Unset(cmd) => {
+                get(&values, &cmd.key)?;
+                let value = to_map(&base::Config::default())[&cmd.key].clone();
+                (&cmd.key, value)
+            }
+        };
+
+        values.insert(key.clone(), value);
+        let config = serde_json::from_value::<base::Config>(values.into())
+            .with_context(|| format!("invalid value for '{}'", key))?;
+        fs.write(&config).with_context(|| {
+            format!("failed to write '{}'", fs.path::<base::Config>().display())
+        })?;
+        let value = &to_map(&config)[key];
+        Ok(cli::Output::Str(format!("{} = {}", key, value)))
+    }
+}
+
+fn to_map(config: &base::Config) -> serde_json::Map<String, serde_json::Value> {
+    match serde_json::to_value(config).expect("config should be serializable") {
+        serde_json::Value::Object(map) => map,
+        _ => unreachable!("config should serialize to an object"),
+    }
+}
+
+fn get<'a>(
+    values: &'a serde_json::Map<String, serde_json::Value>,
+    key: &str,
+) -> anyhow::Result<&'a serde_json::Value> {
+    values
+        .get(key)
+        .with_context(|| format!("unknown key '{}'", key))
+}
+
+/// Returns one line per setting, with its default value and value aligned in
+/// columns. Values come last since templates can make them long.
+fn list(values: &serde_json::Map<String, serde_json::Value>) -> String {
+    let defaults = to_map(&base::Config::default());
+    let rows = values
+        .iter()
+        .map(|(key, value)| (key.as_str(), defaults[key].to_string(), value.to_string()))
+        .collect::<Vec<_>>();
+    let key_width = rows.iter().map(|(k, _, _)| k.chars().count()).max();
+    let default_width = rows.iter().map(|(_, d, _)| d.chars().count()).max();
+    let key_width = key_width.unwrap_or_default().max("KEY".len());
+    let default_width = default_width.unwrap_or_default().max("DEFAULT".len());
+    let mut s = format!(
+        "{:key_width$}  {:default_width$}  VALUE\n",
+        "KEY", "DEFAULT"
+    );
+    for (key, default, value) in rows {
+        s.push_str(&format!(
+            "{:key_width$}  {:default_width$}  {}\n",
+            key, default, value
+        ));
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    cli::testing::generate_testcases![
+        (
+            get,
+            cli::testing::Case {
+                invocations: &[
+                    cli::testing::Invocation {
+                        args: &["", "config", "get", "firstIndexInDate"],
+                        res: cli::testing::ResultMatcher::OkStrGlob("1"),
+                    },
+                    cli::testing::Invocation {
+                        args: &["", "config", "get", "limAccountType"],
+                        res: cli::testing::ResultMatcher::OkStrGlob("\"tfsa\""),
+                    },
+                    cli::testing::Invocation {
+                        args: &["", "config", "get", "bogus"],
+                        res: cli::testing::ResultMatcher::ErrGlob("unknown key 'bogus'"),
+                    },
+                ],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(r#"{"firstIndexInDate": 1, "limAccountType": "tfsa"}"#),
+            }
+        ),
+        (
+            set,
+            cli::testing::MutCase {
+                invocations: &[
+                    cli::testing::Invocation {
+                        args: &["", "config", "set", "useColoredOutput", "true"],
+                        res: cli::testing::ResultMatcher::OkStrGlob("useColoredOutput = true"),
+                    },
+                    cli::testing::Invocation {
+                        args: &["", "config", "set", "limAccountType", "rrsp"],
+                        res: cli::testing::ResultMatcher::OkStrGlob("limAccountType = \"rrsp\""),
+                    },
+                    cli::testing::Invocation {
+                        args: &["", "config", "set", "firstDayOfWeek", "\"monday\""],
+                        res: cli::testing::ResultMatcher::OkStrGlob("firstDayOfWeek = \"monday\""),
+                    },
+                    cli::testing::Invocation {
+                        args: &[
+                            "",
+                            "config",
+                            "set",
+                            "rrspIncomeCategories",
+                            "[\"income/*\"]"
+                        ],
+                        res: cli::testing::ResultMatcher::OkStrGlob(
+                            "rrspIncomeCategories = [\"income/*\"]"
+                        ),
+                    },
+                ],
+                initial_state: cli::testing::StrState::new().with_config("{}"),
+                final_state: cli::testing::State::new().with_config(
+                    r#"{
+                        "useColoredOutput": true,
+                        "limAccountType": "rrsp",
+                        "firstDayOfWeek": "monday",
+                        "rrspIncomeCategories": ["income/*"]
+                    }"#
+                ),
+            }
+        ),
+        (
+            set_invalid,
+            cli::testing::Case {
+                invocations: &[
+                    cli::testing::Invocation {
+                        args: &["", "config", "set", "limAccountType", "bogus"],
+                        res: cli::testing::ResultMatcher::ErrGlob(
+                            "invalid value for 'limAccountType'"
+                        ),
+                    },
+                    cli::testing::Invocation {
+                        args: &["", "config", "set", "firstIndexInDate", "-1"],
+                        res: cli::testing::ResultMatcher::ErrGlob(
+                            "invalid value for 'firstIndexInDate'"
+                        ),
+                    },
+                    cli::testing::Invocation {
+                        args: &["", "config", "set", "bogus", "1"],
+                        res: cli::testing::ResultMatcher::ErrGlob("unknown key 'bogus'"),
+                    },
+                ],
+                initial_state: cli::testing::StrState::new().with_config("{}"),
+            }
+        ),
+        (
+            unset,
+            cli::testing::MutCase {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "config", "unset", "firstIndexInDate"],
+                    res: cli::testing::ResultMatcher::OkStrGlob("firstIndexInDate = 0"),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(r#"{"firstIndexInDate": 1, "useUnicodeSymbols": true}"#),
+                final_state: cli::testing::State::new()
+                    .with_config(r#"{"useUnicodeSymbols": true}"#),
+            }
+        ),
+        (
+            list,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "config", "list"],
+                    res: cli::testing::ResultMatcher::OkStrGlob(
+                        "KEY *DEFAULT *VALUE\n*firstIndexInDate *0 *1\n*useUnicodeSymbols *false *false\n*"
+                    ),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(r#"{"firstIndexInDate": 1}"#),
+            }
+        ),
+    ];
+}
@@ -0,0 +1,128 @@
+use anyhow::Context;
+
+use crate::base;
+use crate::cli;
+
+/// Plot projected monthly totals
+///
+/// Each top-level category is projected to recur every month at its average
+/// monthly total over the trailing window of complete months. Transactions
+/// already logged for upcoming months are added on top of the projections.
+#[derive(clap::Parser)]
+pub struct Forecast {
+    /// Number of upcoming months to project
+    #[arg(short = 'n', long, default_value_t = 3)]
+    months: u16,
+
+    /// Number of complete months to average over
+    ///
+    /// Months before the first transaction in the ledger are not counted.
+    #[arg(short, long, default_value_t = 6)]
+    window: u16,
+
+    #[command(flatten)]
+    categories_opts: cli::sharedopts::CategoriesOpts,
+}
+
+impl Forecast {
+    pub fn run(&self, rl: base::Recordlist, config: &base::Config) -> anyhow::Result<cli::Output> {
+        let (categories, not_categories) = self.categories_opts.patterns(config);
+
+        let cal = cli::util::calendar_from_config(config);
+        let this_month =
+            base::Date::today().first_of(base::Datepart::Month, base::Calendar::default());
+        let window = base::Interval {
+            start: this_month
+                .shift(base::Datepart::Month, -i32::from(self.window))
+                .context("window is out of range")?
+                .max(
+                    rl.spanned_interval()
+                        .start
+                        .first_of(base::Datepart::Month, base::Calendar::default()),
+                ),
+            end: this_month
+                .shift(base::Datepart::Day, -1)
+                .context("window is out of range")?,
+        };
+        let projected = base::Interval {
+            start: this_month
+                .shift(base::Datepart::Month, 1)
+                .context("months are out of range")?,
+            end: this_month
+                .shift(base::Datepart::Month, i32::from(self.months) + 1)
+                .and_then(|dt| dt.shift(base::Datepart::Day, -1))
+                .context("months are out of range")?,
+        };
+        let bounds = base::Interval {
+            start: window.start.min(this_month),
+            end: projected.end,
+        };
+
+        let mut rl = base::filter_rl(&rl, bounds, &categories, &not_categories);
+        for r in base::forecast::project(&rl, window, projected).iter() {
+            rl.insert(r.clone());
+        }
+        let chart_config = base::barchart::Config {
+            charset: cli::util::charset_from_config(config),
+            bounds,
+            unit: base::Datepart::Month,
+            cal,
+            projected,
+            net: true,
+            term_width: terminal_size::terminal_size()
+                .map(|(w, _)| w.0)
+                .unwrap_or_default() as usize,
+            rl,
+        };
+        Ok(cli::Output::Barchart(chart_config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_run() {
+        let rl = r#"
+            {"d":"2015-01-10","c":"food/groceries","a":-10000}
+            {"d":"2015-02-10","c":"rent","a":-50000}
+            {"d":"2015-02-15","c":"salary","a":200000}
+            {"d":"2015-03-10","c":"food","a":-99999}
+            {"d":"2015-04-15","c":"rent","a":-1000,"n":"deposit"}
+            {"d":"2015-08-01","c":"rent","a":-99999}
+        "#
+        .parse()
+        .unwrap();
+        let forecast =
+            <Forecast as clap::Parser>::try_parse_from(["", "-n", "2", "-x", "salary"]).unwrap();
+        let Ok(cli::Output::Barchart(chart_config)) = forecast.run(rl, &base::Config::default())
+        else {
+            panic!("output should be a barchart");
+        };
+        assert_eq!(
+            chart_config.bounds,
+            "2015-01-01:2015-05-31".parse().unwrap()
+        );
+        assert_eq!(
+            chart_config.projected,
+            "2015-04-01:2015-05-31".parse().unwrap()
+        );
+        assert!(chart_config.net);
+        assert_eq!(
+            chart_config.rl,
+            r#"
+                {"d":"2015-01-10","c":"food/groceries","a":-10000}
+                {"d":"2015-02-10","c":"rent","a":-50000}
+                {"d":"2015-03-10","c":"food","a":-99999}
+                {"d":"2015-04-01","c":"food","a":-5000,"n":"projected"}
+                {"d":"2015-04-01","c":"rent","a":-25000,"n":"projected"}
+                {"d":"2015-04-15","c":"rent","a":-1000,"n":"deposit"}
+                {"d":"2015-05-01","c":"food","a":-5000,"n":"projected"}
+                {"d":"2015-05-01","c":"rent","a":-25000,"n":"projected"}
+            "#
+            .parse()
+            .unwrap()
+        );
+    }
+}
@@ -14,24 +14,82 @@ pub struct Lim {
     #[arg(default_value = "y", allow_negative_numbers = true)]
     year: YearArg,
 
+    /// Name of the account of interest
+    ///
+    /// Only applicable when the limits file defines named accounts. If
+    /// omitted, the remaining limit of every named account is shown.
+    #[arg(short, long, value_name = "NAME")]
+    account: Option<String>,
+
     #[command(flatten)]
     opts: Opts,
+
+    /// Fill in published yearly limits from FROM_YEAR up to and including YEAR
+    ///
+    /// Published limits are only known for TFSAs and RRSPs. The account type is
+    /// taken from '--view', '--account' or the 'limAccountType' config. Years
+    /// that already have a limit are left untouched.
+    ///
+    /// CAUTION: for RRSPs, the published dollar maximum is written as the
+    /// limit. This is not the room calculated from 18% of earned income, which
+    /// is lower for most people and which '--calc' writes instead.
+    #[arg(
+        long,
+        value_name = "FROM_YEAR",
+        allow_negative_numbers = true,
+        conflicts_with_all = ["set", "calc"],
+        group = "confirmable"
+    )]
+    fill: Option<YearArg>,
+
+    /// Break down every year's new room, contributions, withdrawals and
+    /// remaining room
+    ///
+    /// For TFSAs, withdrawals restored as room in the following year are also
+    /// shown.
+    #[arg(long, conflicts_with_all = ["set", "calc", "fill"])]
+    history: bool,
+
+    /// Pension adjustment to subtract from the calculated limit
+    #[arg(long, value_name = "AMOUNT", requires = "calc")]
+    pension_adjustment: Option<base::Cents>,
+
+    /// Write the calculated or filled in limits instead of displaying a dry run
+    #[arg(long, requires = "confirmable")]
+    confirm: bool,
 }
 
 #[derive(clap::Args)]
 #[group(required = false, multiple = false)]
+#[command(group = clap::ArgGroup::new("confirmable").multiple(false))]
 struct Opts {
     /// Set the contribution limit for YEAR
     #[arg(short, long, value_name = "AMOUNT", allow_negative_numbers = true)]
     set: Option<base::Cents>,
 
     /// View total and remaining limits for YEAR
+    ///
+    /// With '--fill', selects the account type to fill in instead. Account
+    /// types with user-defined rules can only be set through the
+    /// 'limAccountType' config.
     #[arg(long, value_name = "ACCOUNT_TYPE")]
     #[arg(value_parser(
-        clap::builder::PossibleValuesParser::new(<base::Limitkind as strum::VariantNames>::VARIANTS)
-            .map(|s| s.parse::<base::Limitkind>().expect("should be parseable"))
+        clap::builder::PossibleValuesParser::new(
+            base::Limitkind::BUILTIN.iter().filter_map(|kind| kind.name())
+        )
+        .map(|s| s.parse::<base::Limitkind>().expect("should be parseable"))
     ))]
     view: Option<base::Limitkind>,
+
+    /// Calculate the RRSP limit for YEAR from the previous year's earned income
+    ///
+    /// The limit is 18% of earned income, which is the total of transactions
+    /// in categories matching the 'rrspIncomeCategories' config. It is capped
+    /// at YEAR's amount in the 'rrspDeductionMaximums' config, or else YEAR's
+    /// published RRSP dollar limit, if any, and reduced by
+    /// '--pension-adjustment'.
+    #[arg(long, group = "confirmable")]
+    calc: bool,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -62,61 +120,225 @@ impl std::str::FromStr for YearArg {
 }
 
 impl Lim {
+    /// Returns whether this sets a contribution limit.
+    pub fn is_set(&self) -> bool {
+        self.opts.set.is_some()
+    }
+
     pub fn run(
         &self,
         rl: base::Recordlist,
         config: &base::Config,
-        fs: &base::Fs,
+        fs: &cli::Fs,
     ) -> anyhow::Result<cli::Output> {
         let year = self.year.0;
-        let limits = fs
-            .read::<base::Limits>()
-            .with_context(|| format!("failed to read '{}'", fs.path::<base::Limits>().display()))?;
+        let mut accounts = fs.read::<base::Limitaccounts>().with_context(|| {
+            format!(
+                "failed to read '{}'",
+                fs.path::<base::Limitaccounts>().display()
+            )
+        })?;
 
-        if let Some(amount) = self.opts.set {
-            return update_limits(limits, year, amount, fs);
+        match &mut accounts {
+            base::Limitaccounts::Single(limits) => {
+                if let Some(name) = &self.account {
+                    anyhow::bail!("no account named '{}'", name)
+                }
+                if let Some(amount) = self.opts.set {
+                    let (updated, output) = update_limits(limits, year, amount);
+                    return write_limits(&accounts, updated, output, fs);
+                }
+                if self.opts.calc {
+                    if let Some(kind) = config.lim_account_type
+                        && kind != base::Limitkind::Rrsp
+                    {
+                        anyhow::bail!("limits can only be calculated for rrsp accounts")
+                    }
+                    let deduction_config = self.deduction_config(&rl, config, year)?;
+                    if self.confirm {
+                        limits.set(year, deduction_config.limit());
+                        write_accounts(&accounts, fs)?;
+                    }
+                    return Ok(cli::Output::Limitdeduction(deduction_config, self.confirm));
+                }
+
+                let Some(kind) = self.opts.view.or(config.lim_account_type) else {
+                    anyhow::bail!("no default account type configured")
+                };
+                if let Some(from) = self.fill {
+                    let fill_config = fill_limits(limits, kind, from.0, year, config);
+                    if self.confirm && fill_config.filled_count() > 0 {
+                        write_accounts(&accounts, fs)?;
+                    }
+                    return Ok(cli::Output::Limitfill(fill_config, self.confirm));
+                }
+                let printer_config = base::limitprinter::Config {
+                    charset: cli::util::charset_from_config(config),
+                    year,
+                    rrsp_first_60_days: config.rrsp_first_60_days,
+                    history: self.history,
+                    kind,
+                    limits: limits.clone(),
+                    rl,
+                };
+                Ok(cli::Output::Limitprinter(printer_config))
+            }
+            base::Limitaccounts::Named(named) => {
+                if self.opts.view.is_some() {
+                    anyhow::bail!("account types are defined by named accounts")
+                }
+                let Some(name) = &self.account else {
+                    if self.opts.set.is_some()
+                        || self.opts.calc
+                        || self.fill.is_some()
+                        || self.history
+                    {
+                        anyhow::bail!("no account specified")
+                    }
+                    let summary_config = base::limitprinter::SummaryConfig {
+                        charset: cli::util::charset_from_config(config),
+                        year,
+                        rrsp_first_60_days: config.rrsp_first_60_days,
+                        accounts: std::mem::take(named),
+                        rl,
+                    };
+                    return Ok(cli::Output::Limitsummary(summary_config));
+                };
+                let Some(account) = named.get_mut(name) else {
+                    anyhow::bail!("no account named '{}'", name)
+                };
+                if let Some(amount) = self.opts.set {
+                    let (updated, output) = update_limits(&mut account.limits, year, amount);
+                    return write_limits(&accounts, updated, output, fs);
+                }
+                if self.opts.calc {
+                    if account.kind != base::Limitkind::Rrsp {
+                        anyhow::bail!("limits can only be calculated for rrsp accounts")
+                    }
+                    let deduction_config = self.deduction_config(&rl, config, year)?;
+                    if self.confirm {
+                        account.limits.set(year, deduction_config.limit());
+                        write_accounts(&accounts, fs)?;
+                    }
+                    return Ok(cli::Output::Limitdeduction(deduction_config, self.confirm));
+                }
+                if let Some(from) = self.fill {
+                    let fill_config =
+                        fill_limits(&mut account.limits, account.kind, from.0, year, config);
+                    if self.confirm && fill_config.filled_count() > 0 {
+                        write_accounts(&accounts, fs)?;
+                    }
+                    return Ok(cli::Output::Limitfill(fill_config, self.confirm));
+                }
+
+                let printer_config = base::limitprinter::Config {
+                    charset: cli::util::charset_from_config(config),
+                    year,
+                    rrsp_first_60_days: config.rrsp_first_60_days,
+                    history: self.history,
+                    kind: account.kind,
+                    limits: account.limits.clone(),
+                    rl: account.filter(&rl),
+                };
+                Ok(cli::Output::Limitprinter(printer_config))
+            }
         }
+    }
 
-        let Some(kind) = self.opts.view.or(config.lim_account_type) else {
-            anyhow::bail!("no default account type configured")
-        };
-        let printer_config = base::limitprinter::Config {
+    fn deduction_config(
+        &self,
+        rl: &base::Recordlist,
+        config: &base::Config,
+        year: u16,
+    ) -> anyhow::Result<base::limitprinter::DeductionConfig> {
+        if config.rrsp_income_categories.is_empty() {
+            anyhow::bail!("no rrspIncomeCategories configured")
+        }
+        let prev_year = year.saturating_sub(1);
+        let interval = format!("{:0>4}", prev_year)
+            .parse::<base::Interval>()
+            .expect("year should be convertible to Interval object");
+        let earned_income =
+            base::filter_rl::<_, &str>(rl, interval, &config.rrsp_income_categories, &[])
+                .iter()
+                .map(|r| r.amount())
+                .sum::<base::Cents>()
+                .max(base::Cents(0));
+        Ok(base::limitprinter::DeductionConfig {
             charset: cli::util::charset_from_config(config),
             year,
-            kind,
-            limits,
-            rl,
+            earned_income,
+            maximum: config
+                .rrsp_deduction_maximums
+                .get(year)
+                .or(base::Limitkind::Rrsp.official_limit(year)),
+            pension_adjustment: self.pension_adjustment.unwrap_or_default(),
+        })
+    }
+}
+
+/// Fills in published limits of `kind` in `limits` for every year from `from`
+/// up to and including `to` that does not already have a limit.
+fn fill_limits(
+    limits: &mut base::Limits,
+    kind: base::Limitkind,
+    from: u16,
+    to: u16,
+    config: &base::Config,
+) -> base::limitprinter::FillConfig {
+    let mut years = Vec::new();
+    for year in from..=to {
+        let Some(official) = kind.official_limit(year) else {
+            continue;
         };
-        Ok(cli::Output::Limitprinter(printer_config))
+        match limits.get(year) {
+            Some(existing) => years.push((year, existing, false)),
+            None => {
+                limits.set(year, official);
+                years.push((year, official, true));
+            }
+        }
+    }
+    base::limitprinter::FillConfig {
+        charset: cli::util::charset_from_config(config),
+        years,
     }
 }
 
-fn update_limits(
-    mut limits: base::Limits,
-    year: u16,
-    amount: base::Cents,
-    fs: &base::Fs,
-) -> anyhow::Result<cli::Output> {
-    let output: String;
-    let mut updated = true;
+/// Sets or removes the limit for `year`. Returns whether `limits` changed,
+/// along with a message describing the outcome.
+fn update_limits(limits: &mut base::Limits, year: u16, amount: base::Cents) -> (bool, String) {
     if amount != base::Cents(0) {
         limits.set(year, amount);
-        output = format!("{} limit set to {}", year, amount);
+        (true, format!("{} limit set to {}", year, amount))
     } else if limits.remove(year).is_some() {
-        limits.remove(year);
-        output = format!("{} limit removed.", year);
+        (true, format!("{} limit removed.", year))
     } else {
-        updated = false;
-        output = format!("{} has no limit.", year);
-    };
+        (false, format!("{} has no limit.", year))
+    }
+}
+
+fn write_limits(
+    accounts: &base::Limitaccounts,
+    updated: bool,
+    output: String,
+    fs: &cli::Fs,
+) -> anyhow::Result<cli::Output> {
     if updated {
-        fs.write(&limits).with_context(|| {
-            format!("failed to write '{}'", fs.path::<base::Limits>().display())
-        })?;
+        write_accounts(accounts, fs)?;
     }
     Ok(cli::Output::Str(output))
 }
 
+fn write_accounts(accounts: &base::Limitaccounts, fs: &cli::Fs) -> anyhow::Result<()> {
+    fs.write(accounts).with_context(|| {
+        format!(
+            "failed to write '{}'",
+            fs.path::<base::Limitaccounts>().display()
+        )
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -196,6 +418,8 @@ mod tests {
                         base::limitprinter::Config {
                             charset: Default::default(),
                             year: 2015,
+                            rrsp_first_60_days: false,
+                            history: false,
                             kind: base::Limitkind::Tfsa,
                             limits: r#"{
                                 "2014": 100,
@@ -237,6 +461,8 @@ mod tests {
                         base::limitprinter::Config {
                             charset: Default::default(),
                             year: base::Date::today().year(),
+                            rrsp_first_60_days: false,
+                            history: false,
                             kind: base::Limitkind::Tfsa,
                             limits: r#"{
                                 "2014": 100,
@@ -269,6 +495,28 @@ mod tests {
                     ),
             }
         ),
+        (
+            view_fhsa,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "lim", "2024", "--view", "fhsa"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::Limitprinter(
+                        base::limitprinter::Config {
+                            charset: Default::default(),
+                            year: 2024,
+                            rrsp_first_60_days: false,
+                            history: false,
+                            kind: base::Limitkind::Fhsa,
+                            limits: r#"{"2023": 800000, "2024": 800000}"#.parse().unwrap(),
+                            rl: base::Recordlist::new(),
+                        }
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(r#"{"limAccountType":"resp"}"#)
+                    .with_limits(r#"{"2023": 800000, "2024": 800000}"#),
+            }
+        ),
         (
             view_implicit_limitkind_without_one_being_configured,
             cli::testing::Case {
@@ -279,5 +527,376 @@ mod tests {
                 initial_state: cli::testing::StrState::new().with_config("{}"),
             }
         ),
+        (
+            named_summary,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "lim", "2015"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::Limitsummary(
+                        base::limitprinter::SummaryConfig {
+                            charset: Default::default(),
+                            year: 2015,
+                            rrsp_first_60_days: false,
+                            accounts: match r#"{
+                        "rrsp": {"kind": "rrsp", "category": "rrsp", "limits": {"2015": 1000}},
+                        "tfsa": {"kind": "tfsa", "category": "tfsa*", "limits": {"2015": 500}}
+                    }"#
+                            .parse()
+                            .unwrap()
+                            {
+                                base::Limitaccounts::Named(named) => named,
+                                base::Limitaccounts::Single(_) => unreachable!(),
+                            },
+                            rl: r#"{"d":"2015-01-01","c":"tfsa","a":100}"#.parse().unwrap(),
+                        }
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config("{}")
+                    .with_limits(r#"{
+                        "rrsp": {"kind": "rrsp", "category": "rrsp", "limits": {"2015": 1000}},
+                        "tfsa": {"kind": "tfsa", "category": "tfsa*", "limits": {"2015": 500}}
+                    }"#)
+                    .with_rl(r#"{"d":"2015-01-01","c":"tfsa","a":100}"#),
+            }
+        ),
+        (
+            named_view,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "lim", "2015", "--account", "tfsa"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::Limitprinter(
+                        base::limitprinter::Config {
+                            charset: Default::default(),
+                            year: 2015,
+                            rrsp_first_60_days: false,
+                            history: false,
+                            kind: base::Limitkind::Tfsa,
+                            limits: r#"{"2015": 500}"#.parse().unwrap(),
+                            rl: r#"{"d":"2015-01-02","c":"tfsa/cash","a":-100}"#
+                                .parse()
+                                .unwrap(),
+                        }
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(r#"{"limAccountType":"rrsp"}"#)
+                    .with_limits(r#"{
+                        "rrsp": {"kind": "rrsp", "category": "rrsp", "limits": {"2015": 1000}},
+                        "tfsa": {"kind": "tfsa", "category": "tfsa*", "limits": {"2015": 500}}
+                    }"#)
+                    .with_rl(
+                        r#"
+                            {"d":"2015-01-01","c":"rrsp","a":100}
+                            {"d":"2015-01-02","c":"tfsa/cash","a":-100}
+                        "#
+                    ),
+            }
+        ),
+        (
+            named_set,
+            cli::testing::MutCase {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "lim", "2016", "-a", "rrsp", "--set", "20"],
+                    res: cli::testing::ResultMatcher::OkStrGlob("2016 limit set to 20.00"),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config("{}")
+                    .with_limits(r#"{
+                        "rrsp": {"kind": "rrsp", "category": "rrsp", "limits": {"2015": 1000}},
+                        "tfsa": {"kind": "tfsa", "category": "tfsa*", "limits": {"2015": 500}}
+                    }"#),
+                final_state: cli::testing::State::new()
+                    .with_config(base::Config::default())
+                    .with_limits(
+                        r#"{
+                            "rrsp": {"kind": "rrsp", "category": "rrsp", "limits": {"2015": 1000, "2016": 2000}},
+                            "tfsa": {"kind": "tfsa", "category": "tfsa*", "limits": {"2015": 500}}
+                        }"#
+                    ),
+            }
+        ),
+        (
+            view_history,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "lim", "2015", "--view", "tfsa", "--history"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::Limitprinter(
+                        base::limitprinter::Config {
+                            charset: Default::default(),
+                            year: 2015,
+                            rrsp_first_60_days: false,
+                            history: true,
+                            kind: base::Limitkind::Tfsa,
+                            limits: r#"{"2015": 100}"#.parse().unwrap(),
+                            rl: base::Recordlist::new(),
+                        }
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config("{}")
+                    .with_limits(r#"{"2015": 100}"#),
+            }
+        ),
+        (
+            named_history_without_account,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "lim", "2015", "--history"],
+                    res: cli::testing::ResultMatcher::ErrGlob("no account specified"),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config("{}")
+                    .with_limits(r#"{
+                        "rrsp": {"kind": "rrsp", "category": "rrsp", "limits": {"2015": 1000}}
+                    }"#),
+            }
+        ),
+        (
+            named_set_without_account,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "lim", "2016", "--set", "20"],
+                    res: cli::testing::ResultMatcher::ErrGlob("no account specified"),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config("{}")
+                    .with_limits(r#"{
+                        "rrsp": {"kind": "rrsp", "category": "rrsp", "limits": {"2015": 1000}},
+                        "tfsa": {"kind": "tfsa", "category": "tfsa*", "limits": {"2015": 500}}
+                    }"#),
+            }
+        ),
+        (
+            named_nonexistent,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "lim", "-a", "fhsa"],
+                    res: cli::testing::ResultMatcher::ErrGlob("no account named 'fhsa'"),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config("{}")
+                    .with_limits(r#"{
+                        "rrsp": {"kind": "rrsp", "category": "rrsp", "limits": {"2015": 1000}},
+                        "tfsa": {"kind": "tfsa", "category": "tfsa*", "limits": {"2015": 500}}
+                    }"#),
+            }
+        ),
+        (
+            named_view_explicit_limitkind,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "lim", "--view", "tfsa"],
+                    res: cli::testing::ResultMatcher::ErrGlob(
+                        "account types are defined by named accounts"
+                    ),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config("{}")
+                    .with_limits(r#"{
+                        "rrsp": {"kind": "rrsp", "category": "rrsp", "limits": {"2015": 1000}},
+                        "tfsa": {"kind": "tfsa", "category": "tfsa*", "limits": {"2015": 500}}
+                    }"#),
+            }
+        ),
+        (
+            single_with_account,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "lim", "-a", "tfsa"],
+                    res: cli::testing::ResultMatcher::ErrGlob("no account named 'tfsa'"),
+                }],
+                initial_state: cli::testing::StrState::new().with_config("{}"),
+            }
+        ),
+        (
+            calc_dry_run,
+            cli::testing::MutCase {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "lim", "2015", "--calc", "--pension-adjustment", "1000"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::Limitdeduction(
+                        base::limitprinter::DeductionConfig {
+                            charset: Default::default(),
+                            year: 2015,
+                            earned_income: base::Cents(5000000),
+                            maximum: Some(base::Cents(800000)),
+                            pension_adjustment: base::Cents(100000),
+                        },
+                        false,
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(r#"{
+                        "rrspDeductionMaximums": {"2015": 800000},
+                        "rrspIncomeCategories": ["salary*", "bonus"]
+                    }"#)
+                    .with_rl(r#"
+                        {"d":"2013-12-31","c":"salary","a":9999999}
+                        {"d":"2014-01-31","c":"salary/base","a":3000000}
+                        {"d":"2014-06-30","c":"bonus","a":2000000}
+                        {"d":"2014-07-01","c":"food","a":-100000}
+                    "#),
+                final_state: cli::testing::State::new()
+                    .with_config(r#"{
+                        "rrspDeductionMaximums": {"2015": 800000},
+                        "rrspIncomeCategories": ["salary*", "bonus"]
+                    }"#)
+                    .with_rl(r#"
+                        {"d":"2013-12-31","c":"salary","a":9999999}
+                        {"d":"2014-01-31","c":"salary/base","a":3000000}
+                        {"d":"2014-06-30","c":"bonus","a":2000000}
+                        {"d":"2014-07-01","c":"food","a":-100000}
+                    "#),
+            }
+        ),
+        (
+            calc_wet_run,
+            cli::testing::MutCase {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "lim", "2015", "--calc", "--pension-adjustment", "1000", "--confirm"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::Limitdeduction(
+                        base::limitprinter::DeductionConfig {
+                            charset: Default::default(),
+                            year: 2015,
+                            earned_income: base::Cents(5000000),
+                            maximum: Some(base::Cents(800000)),
+                            pension_adjustment: base::Cents(100000),
+                        },
+                        true,
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(r#"{
+                        "rrspDeductionMaximums": {"2015": 800000},
+                        "rrspIncomeCategories": ["salary*", "bonus"]
+                    }"#)
+                    .with_limits(r#"{"2014": 100}"#)
+                    .with_rl(r#"
+                        {"d":"2013-12-31","c":"salary","a":9999999}
+                        {"d":"2014-01-31","c":"salary/base","a":3000000}
+                        {"d":"2014-06-30","c":"bonus","a":2000000}
+                        {"d":"2014-07-01","c":"food","a":-100000}
+                    "#),
+                final_state: cli::testing::State::new()
+                    .with_config(r#"{
+                        "rrspDeductionMaximums": {"2015": 800000},
+                        "rrspIncomeCategories": ["salary*", "bonus"]
+                    }"#)
+                    .with_limits(r#"{"2014": 100, "2015": 700000}"#)
+                    .with_rl(r#"
+                        {"d":"2013-12-31","c":"salary","a":9999999}
+                        {"d":"2014-01-31","c":"salary/base","a":3000000}
+                        {"d":"2014-06-30","c":"bonus","a":2000000}
+                        {"d":"2014-07-01","c":"food","a":-100000}
+                    "#),
+            }
+        ),
+        (
+            calc_without_income_categories,
+            cli::testing::MutCase {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "lim", "2015", "--calc", "--confirm"],
+                    res: cli::testing::ResultMatcher::ErrGlob("no rrspIncomeCategories configured"),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(r#"{"limAccountType":"rrsp"}"#)
+                    .with_limits(r#"{"2015": 100}"#)
+                    .with_rl(r#"{"d":"2014-06-30","c":"salary","a":5000000}"#),
+                final_state: cli::testing::State::new()
+                    .with_config(r#"{"limAccountType":"rrsp"}"#)
+                    .with_limits(r#"{"2015": 100}"#)
+                    .with_rl(r#"{"d":"2014-06-30","c":"salary","a":5000000}"#),
+            }
+        ),
+        (
+            calc_named_tfsa,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "lim", "--calc", "-a", "tfsa"],
+                    res: cli::testing::ResultMatcher::ErrGlob(
+                        "limits can only be calculated for rrsp accounts"
+                    ),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config("{}")
+                    .with_limits(r#"{"tfsa": {"kind": "tfsa", "category": "tfsa"}}"#),
+            }
+        ),
+        (
+            fill_dry_run,
+            cli::testing::MutCase {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "lim", "2016", "--fill", "2014"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::Limitfill(
+                        base::limitprinter::FillConfig {
+                            charset: Default::default(),
+                            years: vec![
+                                (2014, base::Cents(550000), true),
+                                (2015, base::Cents(100), false),
+                                (2016, base::Cents(550000), true),
+                            ],
+                        },
+                        false,
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(r#"{"limAccountType":"tfsa"}"#)
+                    .with_limits(r#"{"2015": 100}"#),
+                final_state: cli::testing::State::new()
+                    .with_config(r#"{"limAccountType":"tfsa"}"#)
+                    .with_limits(r#"{"2015": 100}"#),
+            }
+        ),
+        (
+            fill_view,
+            cli::testing::MutCase {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "lim", "2015", "--fill", "2015", "--view", "tfsa", "--confirm"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::Limitfill(
+                        base::limitprinter::FillConfig {
+                            charset: Default::default(),
+                            years: vec![(2015, base::Cents(1000000), true)],
+                        },
+                        true,
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new().with_config("{}"),
+                final_state: cli::testing::State::new()
+                    .with_config(base::Config::default())
+                    .with_limits(r#"{"2015": 1000000}"#),
+            }
+        ),
+        (
+            fill_named,
+            cli::testing::MutCase {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "lim", "2016", "--fill", "2015", "-a", "rrsp", "--confirm"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::Limitfill(
+                        base::limitprinter::FillConfig {
+                            charset: Default::default(),
+                            years: vec![
+                                (2015, base::Cents(1000), false),
+                                (2016, base::Cents(2537000), true),
+                            ],
+                        },
+                        true,
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new().with_config("{}").with_limits(
+                    r#"{
+                        "rrsp": {"kind": "rrsp", "category": "rrsp", "limits": {"2015": 1000}},
+                        "tfsa": {"kind": "tfsa", "category": "tfsa*", "limits": {"2015": 500}}
+                    }"#
+                ),
+                final_state: cli::testing::State::new()
+                    .with_config(base::Config::default())
+                    .with_limits(
+                        r#"{
+                            "rrsp": {"kind": "rrsp", "category": "rrsp", "limits": {"2015": 1000, "2016": 2537000}},
+                            "tfsa": {"kind": "tfsa", "category": "tfsa*", "limits": {"2015": 500}}
+                        }"#
+                    ),
+            }
+        ),
     ];
 }
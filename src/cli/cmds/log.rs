@@ -10,7 +10,8 @@ pub struct Log {
     ///
     /// Use '/' to indicate hierarchy. For example, in 'commute/car/gas',
     /// 'commute' is the top level category, 'commute/car' is the second level,
-    /// and 'commute/car/gas' is the leaf.
+    /// and 'commute/car/gas' is the leaf. May also be an alias from the
+    /// 'aliases' config.
     category: base::Category,
 
     /// Transaction amount
@@ -22,15 +23,28 @@ pub struct Log {
 
     /// Transaction date
     #[arg(default_value = "d")]
-    date: base::Date,
+    date: cli::sharedopts::DateArg,
 
     /// Optional comments about transaction
+    ///
+    /// With '--split', the note goes on the first record logged only: the one
+    /// in CATEGORY, or the first split if the splits account for the whole
+    /// amount.
     #[arg(short, long, default_value_t, hide_default_value = true)]
     note: String,
 
     /// Allow logging the entry if its category does not already exist
     #[arg(short, long)]
     create: bool,
+
+    /// Allocate part of the amount to another category
+    ///
+    /// May be repeated. The transaction is logged as one record per split,
+    /// plus a record in CATEGORY for whatever is left of AMOUNT, if anything.
+    /// For example, '150 --split household=30' logs 120 in CATEGORY and 30 in
+    /// 'household'.
+    #[arg(short = 'S', long, value_name = "CATEGORY=AMOUNT")]
+    split: Vec<SplitArg>,
 }
 
 impl Log {
@@ -38,52 +52,211 @@ impl Log {
         &self,
         mut rl: base::Recordlist,
         config: &base::Config,
-        fs: &base::Fs,
+        fs: &cli::Fs,
     ) -> anyhow::Result<cli::Output> {
-        if !self.create && !rl.iter().any(|r| r.category() == &self.category) {
-            anyhow::bail!("nonexistent category")
+        let date = self.date.resolve(cli::util::calendar_from_config(config))?;
+        let entries = self.entries(config)?;
+        if !self.create {
+            for (category, _) in &entries {
+                if !rl.iter().any(|r| r.category() == category) {
+                    anyhow::bail!("nonexistent category")
+                }
+            }
         }
 
-        let r = base::Record::new(
-            self.date,
-            self.category.clone(),
-            self.amount.to_cents(config.unsigned_is_negative),
-            self.note.clone(),
-        );
-        rl.insert(r);
+        let group = match self.split.is_empty() {
+            true => None,
+            false => Some(rl.next_group()),
+        };
+        let mut note = self.note.clone();
+        for (category, amount) in &entries {
+            let r = base::Record::new(date, category.clone(), *amount, std::mem::take(&mut note));
+            rl.insert(match group {
+                Some(group) => r.with_group(group),
+                None => r,
+            });
+        }
+        let warnings = tfsa_warnings(&rl, &entries, date, config, fs);
         fs.write(&rl).with_context(|| {
             format!(
                 "failed to write '{}'",
                 fs.path::<base::Recordlist>().display()
             )
         })?;
+
         let rl = rl
             .slice_spanning_interval(base::Interval {
-                start: self.date,
-                end: self.date,
+                start: date,
+                end: date,
             })
             .iter()
             .collect::<base::Recordlist>();
+        let leaf_string_postprocessor = if warnings.is_empty() {
+            None
+        } else {
+            // The last new record is the last one in its date.
+            let iid0 = rl.len() - 1;
+            let msg = warnings
+                .iter()
+                .map(|w| format!(" <- [{}]", w))
+                .collect::<String>();
+            let lspp = move |config: &base::tree::forview::Config,
+                             r: &base::Record,
+                             iid0_arg: usize,
+                             mut leaf_string: String|
+                  -> String {
+                if r.date() == date && iid0_arg == iid0 {
+                    if config.charset.color {
+                        leaf_string.push_str(&colored::Colorize::yellow(msg.as_str()).to_string());
+                    } else {
+                        leaf_string.push_str(&msg);
+                    }
+                }
+                leaf_string
+            };
+            Some(Box::new(lspp) as _)
+        };
         let tr_config = base::tree::forview::Config {
             charset: cli::util::charset_from_config(config),
             first_iid: config.first_index_in_date,
-            leaf_string_postprocessor: None,
+            leaf_string_postprocessor,
             rl,
         };
-        Ok(cli::Output::TreeForView(tr_config))
+        Ok(cli::Output::Logged(tr_config, warnings))
+    }
+}
+
+impl Log {
+    /// Returns the category and amount of every record to log. The record in
+    /// the transaction's own category is omitted if the splits account for the
+    /// whole amount.
+    fn entries(&self, config: &base::Config) -> anyhow::Result<Vec<(base::Category, base::Cents)>> {
+        let total = self.amount.to_cents(config.unsigned_is_negative);
+        let splits = self
+            .split
+            .iter()
+            .map(|split| {
+                let amount = split.amount.to_cents(config.unsigned_is_negative);
+                (config.expand_category(&split.category), amount)
+            })
+            .collect::<Vec<_>>();
+        let rest = total - splits.iter().map(|&(_, amount)| amount).sum();
+        if !splits.is_empty() && rest.0 != 0 && rest.0.signum() != total.0.signum() {
+            anyhow::bail!("splits exceed the amount")
+        }
+        let own = (config.expand_category(&self.category), rest);
+        Ok(match splits.is_empty() || rest.0 != 0 {
+            true => std::iter::once(own).chain(splits).collect(),
+            false => splits,
+        })
+    }
+}
+
+/// Returns the warnings of [`tfsa_excess_warnings`], reading the limits only if
+/// one of `entries` is a contribution. Failing to read them is itself reported
+/// as a warning, so that logging never depends on the limits file.
+fn tfsa_warnings(
+    rl: &base::Recordlist,
+    entries: &[(base::Category, base::Cents)],
+    date: base::Date,
+    config: &base::Config,
+    fs: &cli::Fs,
+) -> Vec<String> {
+    if !entries.iter().any(|(_, amount)| amount.0 > 0) {
+        return vec![];
+    }
+    match fs.read::<base::Limitaccounts>() {
+        Ok(accounts) => tfsa_excess_warnings(rl, entries, date, config, &accounts),
+        Err(e) => vec![format!(
+            "TFSA ROOM NOT CHECKED: failed to read '{}': {}",
+            fs.path::<base::Limitaccounts>().display(),
+            e
+        )],
+    }
+}
+
+/// Returns a warning for every TFSA account whose room is exceeded in the month
+/// of `date` after logging `entries`. Only accounts that one of the entries
+/// contributes to are checked.
+fn tfsa_excess_warnings(
+    rl: &base::Recordlist,
+    entries: &[(base::Category, base::Cents)],
+    date: base::Date,
+    config: &base::Config,
+    accounts: &base::Limitaccounts,
+) -> Vec<String> {
+    let mut contributions = entries
+        .iter()
+        .filter(|(_, amount)| amount.0 > 0)
+        .map(|(category, _)| category)
+        .peekable();
+    let excess = |limits: &base::Limits, rl: &base::Recordlist| {
+        base::Limitkind::tfsa_excesses(limits, rl, date)
+            .last()
+            .filter(|(month, _)| month.year() == date.year() && month.month() == date.month())
+            .map(|&(_, excess)| excess)
+    };
+    match accounts {
+        base::Limitaccounts::Single(limits) => {
+            if config.lim_account_type != Some(base::Limitkind::Tfsa)
+                || contributions.peek().is_none()
+            {
+                return vec![];
+            }
+            excess(limits, rl)
+                .map(|excess| format!("TFSA ROOM EXCEEDED BY {}", excess))
+                .into_iter()
+                .collect()
+        }
+        base::Limitaccounts::Named(named) => {
+            let contributions = contributions.collect::<Vec<_>>();
+            named
+                .iter()
+                .filter(|(_, account)| {
+                    account.kind == base::Limitkind::Tfsa
+                        && contributions.iter().any(|c| account.matches(c))
+                })
+                .filter_map(|(name, account)| {
+                    excess(&account.limits, &account.filter(rl))
+                        .map(|excess| format!("'{}' TFSA ROOM EXCEEDED BY {}", name, excess))
+                })
+                .collect()
+        }
     }
 }
 
+#[derive(Clone)]
+struct SplitArg {
+    category: base::Category,
+    amount: CentsArg,
+}
+
+impl std::str::FromStr for SplitArg {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((category, amount)) = s.rsplit_once('=') else {
+            anyhow::bail!("expected CATEGORY=AMOUNT")
+        };
+        Ok(Self {
+            category: category.parse()?,
+            amount: amount.parse()?,
+        })
+    }
+}
+
+/// Command line amount. Unsigned amounts take the sign configured by
+/// 'unsignedIsNegative'.
 #[derive(Clone, Copy)]
-enum CentsArg {
+pub enum CentsArg {
     Signed(base::Cents),
     Unsigned(base::Cents),
 }
 
 impl CentsArg {
-    fn to_cents(&self, unsigned_is_negative: bool) -> base::Cents {
+    pub fn to_cents(self, unsigned_is_negative: bool) -> base::Cents {
         match self {
-            CentsArg::Signed(x) => *x,
+            CentsArg::Signed(x) => x,
             CentsArg::Unsigned(x) => {
                 if unsigned_is_negative {
                     -x.abs()
@@ -131,6 +304,8 @@ mod tests {
         assert_eq!(arg.to_cents(unsigned_is_negative), want)
     }
 
+    const ALIASES: &str = r#"{"aliases": {"g": "groceries/supermarket", "h": "household"}}"#;
+
     cli::testing::generate_testcases![
         (
             nonexistent_category,
@@ -157,7 +332,7 @@ mod tests {
                             "qwerty",
                             "--create",
                         ],
-                        res: cli::testing::ResultMatcher::OkExact(cli::Output::TreeForView(
+                        res: cli::testing::ResultMatcher::OkExact(cli::Output::Logged(
                             base::tree::forview::Config {
                                 charset: Default::default(),
                                 first_iid: 0,
@@ -165,7 +340,8 @@ mod tests {
                                     .parse()
                                     .unwrap(),
                                 leaf_string_postprocessor: None,
-                            }
+                            },
+                            vec![],
                         )),
                     },
                     cli::testing::Invocation {
@@ -179,7 +355,7 @@ mod tests {
                             "qwerty",
                             "--create"
                         ],
-                        res: cli::testing::ResultMatcher::OkExact(cli::Output::TreeForView(
+                        res: cli::testing::ResultMatcher::OkExact(cli::Output::Logged(
                             base::tree::forview::Config {
                                 charset: Default::default(),
                                 first_iid: 0,
@@ -190,12 +366,13 @@ mod tests {
                                 .parse()
                                 .unwrap(),
                                 leaf_string_postprocessor: None,
-                            }
+                            },
+                            vec![],
                         )),
                     },
                     cli::testing::Invocation {
                         args: &["", "log", "aaa", "789", "2015-03-30", "--note", "qwerty"],
-                        res: cli::testing::ResultMatcher::OkExact(cli::Output::TreeForView(
+                        res: cli::testing::ResultMatcher::OkExact(cli::Output::Logged(
                             base::tree::forview::Config {
                                 charset: Default::default(),
                                 first_iid: 0,
@@ -207,7 +384,8 @@ mod tests {
                                 .parse()
                                 .unwrap(),
                                 leaf_string_postprocessor: None,
-                            }
+                            },
+                            vec![],
                         )),
                     },
                 ],
@@ -228,7 +406,7 @@ mod tests {
             cli::testing::MutCase {
                 invocations: &[cli::testing::Invocation {
                     args: &["", "log", "aaa", "1.23", "--create"],
-                    res: cli::testing::ResultMatcher::OkExact(cli::Output::TreeForView(
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::Logged(
                         base::tree::forview::Config {
                             charset: Default::default(),
                             first_iid: 0,
@@ -236,7 +414,8 @@ mod tests {
                                 .parse()
                                 .unwrap(),
                             leaf_string_postprocessor: None,
-                        }
+                        },
+                        vec![],
                     )),
                 }],
                 initial_state: cli::testing::StrState::new()
@@ -253,7 +432,7 @@ mod tests {
             cli::testing::MutCase {
                 invocations: &[cli::testing::Invocation {
                     args: &["", "log", "aaa", "1.23", "--create"],
-                    res: cli::testing::ResultMatcher::OkExact(cli::Output::TreeForView(
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::Logged(
                         base::tree::forview::Config {
                             charset: Default::default(),
                             first_iid: 0,
@@ -261,7 +440,8 @@ mod tests {
                                 .parse()
                                 .unwrap(),
                             leaf_string_postprocessor: None,
-                        }
+                        },
+                        vec![],
                     )),
                 }],
                 initial_state: cli::testing::StrState::new()
@@ -273,5 +453,273 @@ mod tests {
                     ),
             }
         ),
+        (
+            split,
+            cli::testing::MutCase {
+                invocations: &[cli::testing::Invocation {
+                    args: &[
+                        "",
+                        "log",
+                        "groceries",
+                        "-150",
+                        "2015-03-30",
+                        "--note",
+                        "costco",
+                        "--split",
+                        "household=-30",
+                        "-S",
+                        "pharmacy=-20",
+                        "--create",
+                    ],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::Logged(
+                        base::tree::forview::Config {
+                            charset: Default::default(),
+                            first_iid: 0,
+                            rl: r#"
+                                {"d":"2015-03-30","c":"aaa","a":100,"g":4}
+                                {"d":"2015-03-30","c":"groceries","a":-10000,"n":"costco","g":5}
+                                {"d":"2015-03-30","c":"household","a":-3000,"g":5}
+                                {"d":"2015-03-30","c":"pharmacy","a":-2000,"g":5}
+                            "#
+                            .parse()
+                            .unwrap(),
+                            leaf_string_postprocessor: None,
+                        },
+                        vec![],
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config("{}")
+                    .with_rl(r#"{"d":"2015-03-30","c":"aaa","a":100,"g":4}"#),
+                final_state: cli::testing::State::new()
+                    .with_config(base::Config::default())
+                    .with_rl(
+                        r#"
+                            {"d":"2015-03-30","c":"aaa","a":100,"g":4}
+                            {"d":"2015-03-30","c":"groceries","a":-10000,"n":"costco","g":5}
+                            {"d":"2015-03-30","c":"household","a":-3000,"g":5}
+                            {"d":"2015-03-30","c":"pharmacy","a":-2000,"g":5}
+                        "#
+                    ),
+            }
+        ),
+        (
+            split_whole_amount,
+            cli::testing::MutCase {
+                invocations: &[cli::testing::Invocation {
+                    args: &[
+                        "",
+                        "log",
+                        "aaa",
+                        "+5",
+                        "2015-03-30",
+                        "-S",
+                        "bbb=+2",
+                        "-S",
+                        "ccc=+3"
+                    ],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::Logged(
+                        base::tree::forview::Config {
+                            charset: Default::default(),
+                            first_iid: 0,
+                            rl: r#"
+                                {"d":"2015-03-30","c":"bbb","a":200,"g":0}
+                                {"d":"2015-03-30","c":"ccc","a":300,"g":0}
+                            "#
+                            .parse()
+                            .unwrap(),
+                            leaf_string_postprocessor: None,
+                        },
+                        vec![],
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new().with_config("{}").with_rl(
+                    r#"
+                        {"d":"2015-01-01","c":"bbb","a":0}
+                        {"d":"2015-01-01","c":"ccc","a":0}
+                    "#
+                ),
+                final_state: cli::testing::State::new()
+                    .with_config(base::Config::default())
+                    .with_rl(
+                        r#"
+                            {"d":"2015-01-01","c":"bbb","a":0}
+                            {"d":"2015-01-01","c":"ccc","a":0}
+                            {"d":"2015-03-30","c":"bbb","a":200,"g":0}
+                            {"d":"2015-03-30","c":"ccc","a":300,"g":0}
+                        "#
+                    ),
+            }
+        ),
+        (
+            split_exceeds_amount,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "log", "aaa", "+5", "-S", "bbb=+6", "--create"],
+                    res: cli::testing::ResultMatcher::ErrGlob("splits exceed the amount"),
+                }],
+                initial_state: cli::testing::StrState::new().with_config("{}"),
+            }
+        ),
+        (
+            split_nonexistent_category,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "log", "aaa", "+5", "-S", "bbb=+2"],
+                    res: cli::testing::ResultMatcher::ErrGlob("nonexistent category"),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config("{}")
+                    .with_rl(r#"{"d":"2015-01-01","c":"aaa","a":0}"#),
+            }
+        ),
+        (
+            tfsa_within_room,
+            cli::testing::MutCase {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "log", "tfsa", "+100", "2015-03-30"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::Logged(
+                        base::tree::forview::Config {
+                            charset: Default::default(),
+                            first_iid: 0,
+                            rl: r#"{"d":"2015-03-30","c":"tfsa","a":10000}"#.parse().unwrap(),
+                            leaf_string_postprocessor: None,
+                        },
+                        vec![],
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(r#"{"limAccountType":"tfsa"}"#)
+                    .with_limits(r#"{"2015": 10000}"#)
+                    .with_rl(r#"{"d":"2015-01-01","c":"tfsa","a":0}"#),
+                final_state: cli::testing::State::new()
+                    .with_config(r#"{"limAccountType":"tfsa"}"#)
+                    .with_limits(r#"{"2015": 10000}"#)
+                    .with_rl(
+                        r#"
+                            {"d":"2015-01-01","c":"tfsa","a":0}
+                            {"d":"2015-03-30","c":"tfsa","a":10000}
+                        "#
+                    ),
+            }
+        ),
+        (
+            alias,
+            cli::testing::MutCase {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "log", "g", "-1.23", "2015-03-30", "-S", "h=-1"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::Logged(
+                        base::tree::forview::Config {
+                            charset: Default::default(),
+                            first_iid: 0,
+                            rl: r#"
+                                {"d":"2015-03-30","c":"groceries/supermarket","a":-23,"g":0}
+                                {"d":"2015-03-30","c":"household","a":-100,"g":0}
+                            "#
+                            .parse()
+                            .unwrap(),
+                            leaf_string_postprocessor: None,
+                        },
+                        vec![],
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new().with_config(ALIASES).with_rl(
+                    r#"
+                            {"d":"2015-01-01","c":"groceries/supermarket","a":0}
+                            {"d":"2015-01-01","c":"household","a":0}
+                        "#
+                ),
+                final_state: cli::testing::State::new().with_config(ALIASES).with_rl(
+                    r#"
+                            {"d":"2015-01-01","c":"groceries/supermarket","a":0}
+                            {"d":"2015-01-01","c":"household","a":0}
+                            {"d":"2015-03-30","c":"groceries/supermarket","a":-23,"g":0}
+                            {"d":"2015-03-30","c":"household","a":-100,"g":0}
+                        "#
+                ),
+            }
+        ),
+        (
+            alias_nonexistent_category,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "log", "g", "1"],
+                    res: cli::testing::ResultMatcher::ErrGlob("nonexistent category"),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(ALIASES)
+                    .with_rl(r#"{"d":"2015-01-01","c":"g","a":0}"#),
+            }
+        ),
     ];
+
+    #[test]
+    fn test_unreadable_limits() {
+        let (fs, _td) = cli::testing::tempfs();
+        cli::testing::StrState::new()
+            .with_config("{}")
+            .with_rl(r#"{"d":"2015-01-01","c":"aaa","a":100,"i":"000001"}"#)
+            .to_fs(&fs);
+        std::fs::write(fs.path::<base::Limitaccounts>(), "bogus").unwrap();
+        let run = |amount| {
+            let root =
+                <cli::Root as clap::Parser>::try_parse_from(["", "log", "aaa", amount]).unwrap();
+            match root.run(&fs).unwrap() {
+                cli::Output::Logged(_, warnings) => warnings,
+                _ => panic!("log should return Output::Logged"),
+            }
+        };
+
+        // Expenses never read the limits.
+        assert_eq!(run("-5"), Vec::<String>::new());
+        // Contributions are logged anyway.
+        let warnings = run("+5");
+        assert_eq!(warnings.len(), 1);
+        assert!(
+            warnings[0].starts_with("TFSA ROOM NOT CHECKED: failed to read"),
+            "{}",
+            warnings[0]
+        );
+        assert_eq!(fs.read::<base::Recordlist>().unwrap().len(), 3);
+    }
+
+    #[rstest]
+    #[case::single(
+        r#"{"limAccountType":"tfsa"}"#,
+        r#"{"2015": 10000}"#,
+        "tfsa: note <- [TFSA ROOM EXCEEDED BY 50.00]"
+    )]
+    #[case::named(
+        "{}",
+        r#"{
+            "savings": {"kind": "tfsa", "category": "tfsa*", "limits": {"2015": 10000}},
+            "other": {"kind": "tfsa", "category": "other", "limits": {}}
+        }"#,
+        "tfsa: note <- ['savings' TFSA ROOM EXCEEDED BY 50.00]"
+    )]
+    fn test_tfsa_excess_warning(
+        #[case] config: base::Config,
+        #[case] limits: base::Limitaccounts,
+        #[case] want_in_output: &str,
+    ) {
+        let (fs, _td) = cli::testing::tempfs();
+        fs.write(&limits).unwrap();
+        let rl = r#"{"d":"2015-01-01","c":"tfsa","a":10000}"#.parse().unwrap();
+        let log = <Log as clap::Parser>::try_parse_from([
+            "",
+            "tfsa",
+            "+50",
+            "2015-03-30",
+            "--note",
+            "note",
+        ])
+        .unwrap();
+        let output = log.run(rl, &config, &fs).unwrap().to_string();
+        assert!(
+            output.contains(want_in_output),
+            "substring `{}` not found in `{}`",
+            want_in_output,
+            output,
+        );
+    }
 }
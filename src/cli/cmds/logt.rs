@@ -11,9 +11,13 @@ pub struct Logt {
     /// If omitted, displays the available templates.
     template: Option<String>,
 
-    /// Transaction date
-    #[arg(default_value = "d")]
-    date: base::Date,
+    /// Amounts to fill in the template with, followed by the transaction date
+    ///
+    /// Templates whose entries refer to parameters '$1', '$2', and so on take
+    /// that many amounts, in order. As with 'log', unsigned amounts take the
+    /// sign configured by 'unsignedIsNegative'. The date defaults to today.
+    #[arg(value_name = "ARGS", allow_negative_numbers = true)]
+    args: Vec<String>,
 }
 
 impl Logt {
@@ -21,7 +25,7 @@ impl Logt {
         &self,
         mut rl: base::Recordlist,
         config: &base::Config,
-        fs: &base::Fs,
+        fs: &cli::Fs,
     ) -> anyhow::Result<cli::Output> {
         let Some(tmpl_name) = &self.template else {
             let tr_config = base::tree::forlogt::Config {
@@ -33,12 +37,52 @@ impl Logt {
         let Some(tmpl) = config.templates.get(tmpl_name) else {
             anyhow::bail!("unknown template");
         };
+        let param_count = tmpl
+            .iter()
+            .filter_map(|entry| entry.amount.param())
+            .max()
+            .unwrap_or_default();
+        if self.args.len() < param_count {
+            match param_count {
+                1 => anyhow::bail!("template takes 1 amount"),
+                n => anyhow::bail!("template takes {} amounts", n),
+            }
+        }
+        let (params, rest) = self.args.split_at(param_count);
+        let params = params
+            .iter()
+            .map(|s| {
+                s.parse::<cli::cmds::log::CentsArg>()
+                    .map(|amount| amount.to_cents(config.unsigned_is_negative))
+                    .with_context(|| format!("invalid amount '{}'", s))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let date = match rest {
+            [] => "d".parse::<cli::sharedopts::DateArg>()?,
+            [date] => date
+                .parse::<cli::sharedopts::DateArg>()
+                .with_context(|| format!("invalid date '{}'", date))?,
+            [_, extra, ..] => anyhow::bail!("unexpected argument '{}'", extra),
+        };
+        let date = date.resolve(cli::util::calendar_from_config(config))?;
+        let existing_ids = rl
+            .iter()
+            .filter_map(base::Record::id)
+            .map(String::from)
+            .collect::<std::collections::HashSet<_>>();
         for entry in tmpl {
+            let amount = entry
+                .amount
+                .resolve(&params)
+                .expect("params should cover every entry");
+            let entry_date = date
+                .shift(base::Datepart::Day, entry.days)
+                .context("date is out of range")?;
             let r = base::Record::new(
-                self.date,
-                entry.category.clone(),
-                entry.amount,
-                String::new(),
+                entry_date,
+                config.expand_category(&entry.category),
+                amount,
+                entry.note.clone(),
             );
             rl.insert(r);
         }
@@ -49,11 +93,8 @@ impl Logt {
             )
         })?;
         let rl = rl
-            .slice_spanning_interval(base::Interval {
-                start: self.date,
-                end: self.date,
-            })
             .iter()
+            .filter(|r| r.id().is_none_or(|id| !existing_ids.contains(id)))
             .collect::<base::Recordlist>();
         let tr_config = base::tree::forview::Config {
             charset: cli::util::charset_from_config(config),
@@ -69,6 +110,17 @@ impl Logt {
 mod tests {
     use super::*;
 
+    const PAYCHECK: &str = r#"{
+        "templates": {
+            "paycheck": [
+                {"category": "salary", "amount": {"param": 1}},
+                {"category": "tax", "amount": {"param": 1, "percent": -20}},
+                {"category": "cpp", "amount": {"param": 1, "percent": -5.95}, "note": "pension"},
+                {"category": "rrsp", "amount": 10000, "note": "match", "days": 2}
+            ]
+        }
+    }"#;
+
     cli::testing::generate_testcases![
         (
             nonexistent_template,
@@ -128,5 +180,148 @@ mod tests {
                     ),
             }
         ),
+        (
+            parameterized,
+            cli::testing::MutCase {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "logt", "paycheck", "3,200", "2015-03-30"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::TreeForView(
+                        base::tree::forview::Config {
+                            charset: Default::default(),
+                            first_iid: 0,
+                            rl: r#"
+                                {"d":"2015-03-30","c":"salary","a":320000,"n":""}
+                                {"d":"2015-03-30","c":"tax","a":-64000,"n":""}
+                                {"d":"2015-03-30","c":"cpp","a":-19040,"n":"pension"}
+                                {"d":"2015-04-01","c":"rrsp","a":10000,"n":"match"}
+                            "#
+                            .parse()
+                            .unwrap(),
+                            leaf_string_postprocessor: None,
+                        }
+                    )),
+                },],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(PAYCHECK)
+                    .with_rl(r#"{"d":"2015-03-31","c":"food","a":-500,"n":""}"#),
+                final_state: cli::testing::State::new().with_config(PAYCHECK).with_rl(
+                    r#"
+                            {"d":"2015-03-30","c":"salary","a":320000,"n":""}
+                            {"d":"2015-03-30","c":"tax","a":-64000,"n":""}
+                            {"d":"2015-03-30","c":"cpp","a":-19040,"n":"pension"}
+                            {"d":"2015-03-31","c":"food","a":-500,"n":""}
+                            {"d":"2015-04-01","c":"rrsp","a":10000,"n":"match"}
+                        "#
+                ),
+            }
+        ),
+        (
+            unsigned_is_negative,
+            cli::testing::MutCase {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "logt", "t", "12", "+3", "2015-03-30"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::TreeForView(
+                        base::tree::forview::Config {
+                            charset: Default::default(),
+                            first_iid: 0,
+                            rl: r#"
+                                {"d":"2015-03-30","c":"food","a":-1200,"n":""}
+                                {"d":"2015-03-30","c":"refund","a":300,"n":""}
+                            "#
+                            .parse()
+                            .unwrap(),
+                            leaf_string_postprocessor: None,
+                        }
+                    )),
+                },],
+                initial_state: cli::testing::StrState::new().with_config(
+                    r#"{
+                        "unsignedIsNegative": true,
+                        "templates": {"t": [
+                            {"category": "food", "amount": {"param": 1}},
+                            {"category": "refund", "amount": {"param": 2}}
+                        ]}
+                    }"#
+                ),
+                final_state: cli::testing::State::new()
+                    .with_config(
+                        r#"{
+                            "unsignedIsNegative": true,
+                            "templates": {"t": [
+                                {"category": "food", "amount": {"param": 1}},
+                                {"category": "refund", "amount": {"param": 2}}
+                            ]}
+                        }"#
+                    )
+                    .with_rl(
+                        r#"
+                            {"d":"2015-03-30","c":"food","a":-1200,"n":""}
+                            {"d":"2015-03-30","c":"refund","a":300,"n":""}
+                        "#
+                    ),
+            }
+        ),
+        (
+            missing_amount,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "logt", "paycheck"],
+                    res: cli::testing::ResultMatcher::ErrGlob("template takes 1 amount"),
+                }],
+                initial_state: cli::testing::StrState::new().with_config(PAYCHECK),
+            }
+        ),
+        (
+            invalid_amount,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "logt", "paycheck", "2015-03-30"],
+                    res: cli::testing::ResultMatcher::ErrGlob("invalid amount '2015-03-30'"),
+                }],
+                initial_state: cli::testing::StrState::new().with_config(PAYCHECK),
+            }
+        ),
+        (
+            unexpected_argument,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "logt", "paycheck", "1", "d", "d"],
+                    res: cli::testing::ResultMatcher::ErrGlob("unexpected argument 'd'"),
+                }],
+                initial_state: cli::testing::StrState::new().with_config(PAYCHECK),
+            }
+        ),
+        (
+            alias,
+            cli::testing::MutCase {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "logt", "t", "2015-03-30"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::TreeForView(
+                        base::tree::forview::Config {
+                            charset: Default::default(),
+                            first_iid: 0,
+                            rl: r#"{"d":"2015-03-30","c":"groceries","a":-6000,"n":""}"#
+                                .parse()
+                                .unwrap(),
+                            leaf_string_postprocessor: None,
+                        }
+                    )),
+                },],
+                initial_state: cli::testing::StrState::new().with_config(
+                    r#"{
+                        "aliases": {"g": "groceries"},
+                        "templates": {"t": [{"category": "g", "amount": -6000}]}
+                    }"#
+                ),
+                final_state: cli::testing::State::new()
+                    .with_config(
+                        r#"{
+                            "aliases": {"g": "groceries"},
+                            "templates": {"t": [{"category": "g", "amount": -6000}]}
+                        }"#
+                    )
+                    .with_rl(r#"{"d":"2015-03-30","c":"groceries","a":-6000,"n":""}"#),
+            }
+        ),
     ];
 }
@@ -1,4 +1,8 @@
+pub mod batch;
 pub mod cats;
+pub mod completions;
+pub mod config;
+pub mod forecast;
 pub mod init;
 pub mod lim;
 pub mod log;
@@ -6,5 +10,10 @@ pub mod logt;
 pub mod plot;
 pub mod rm;
 pub mod root;
+pub mod serve;
+pub mod shell;
+pub mod stats;
 pub mod sum;
+pub mod tmpl;
+pub mod tui;
 pub mod view;
@@ -7,7 +7,7 @@ use crate::cli::util;
 #[derive(clap::Parser)]
 pub struct Plot {
     #[arg(help = sharedopts::INTERVAL_HELP, long_help = sharedopts::INTERVAL_HELP_LONG)]
-    interval: Option<base::Interval>,
+    interval: Option<sharedopts::IntervalArg>,
 
     #[command(flatten)]
     units: Units,
@@ -25,15 +25,28 @@ struct Units {
     #[arg(short)]
     d: bool,
 
+    /// Aggregate data by week
+    ///
+    /// The default interval is the past 12 weeks
+    #[arg(short)]
+    w: bool,
+
     /// Aggregate data by month [default]
     ///
     /// The default interval is the past 12 months
     #[arg(short)]
     m: bool,
 
+    /// Aggregate data by quarter
+    ///
+    /// The default interval is the past 8 quarters
+    #[arg(short)]
+    q: bool,
+
     /// Aggregate data by year
     ///
-    /// The default interval is the past 10 years
+    /// Years are fiscal years if a fiscal year start is configured. The default
+    /// interval is the past 10 years
     #[arg(short)]
     y: bool,
 }
@@ -42,36 +55,41 @@ impl Plot {
     pub fn run(&self, rl: base::Recordlist, config: &base::Config) -> anyhow::Result<Output> {
         let unit = if self.units.y {
             base::Datepart::Year
+        } else if self.units.q {
+            base::Datepart::Quarter
         } else if self.units.m {
             base::Datepart::Month
+        } else if self.units.w {
+            base::Datepart::Week
         } else if self.units.d {
             base::Datepart::Day
         } else {
             base::Datepart::Month
         };
-        let interval = self.interval.unwrap_or_else(|| {
-            let default = match unit {
-                base::Datepart::Year => "y-10:Y",
-                base::Datepart::Month => "m-12:M",
-                base::Datepart::Day => "d-14:D",
-            };
-            default
-                .parse()
-                .expect("value should be convertible to Interval object")
-        });
-        let categories = util::preprocess_categories(
-            &self.categories_opts.categories,
-            self.categories_opts.fullmatch,
-        );
-        let not_categories = util::preprocess_categories(
-            &self.categories_opts.not_categories,
-            self.categories_opts.fullmatch,
-        );
-        let rl = util::filter_rl(&rl, interval, &categories, &not_categories);
+        let cal = util::calendar_from_config(config);
+        let interval = match &self.interval {
+            Some(interval) => interval.resolve(cal)?,
+            None => {
+                let default = match unit {
+                    base::Datepart::Year => "y-10:Y",
+                    base::Datepart::Quarter => "q-8:Q",
+                    base::Datepart::Month => "m-12:M",
+                    base::Datepart::Week => "w-12:W",
+                    base::Datepart::Day => "d-14:D",
+                };
+                base::Interval::from_str_with(default, cal)
+                    .expect("value should be convertible to Interval object")
+            }
+        };
+        let (categories, not_categories) = self.categories_opts.patterns(config);
+        let rl = base::filter_rl(&rl, interval, &categories, &not_categories);
         let chart_config = base::barchart::Config {
             charset: util::charset_from_config(config),
             bounds: interval,
             unit,
+            cal,
+            projected: base::Interval::EMPTY,
+            net: false,
             term_width: terminal_size::terminal_size()
                 .map(|(w, _)| w.0)
                 .unwrap_or_default() as usize,
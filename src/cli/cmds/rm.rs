@@ -7,10 +7,18 @@ use crate::cli;
 #[derive(clap::Parser)]
 pub struct Rm {
     /// Transaction date
-    date: base::Date,
+    #[arg(required_unless_present = "id")]
+    date: Option<cli::sharedopts::DateArg>,
 
     /// Index of transaction in DATE
-    index: usize,
+    #[arg(required_unless_present = "id")]
+    index: Option<usize>,
+
+    /// ID of transaction, instead of DATE and INDEX
+    ///
+    /// Use 'view --ids' to show the IDs of transactions.
+    #[arg(long, conflicts_with_all = ["date", "index"])]
+    id: Option<String>,
 
     /// Execute the removal instead of displaying dry run changes
     #[arg(long)]
@@ -18,33 +26,49 @@ pub struct Rm {
 }
 
 impl Rm {
+    /// Returns whether this removes the transaction, rather than being a dry
+    /// run.
+    pub fn is_confirmed(&self) -> bool {
+        self.confirm
+    }
+
     pub fn run(
         &self,
         mut rl: base::Recordlist,
         config: &base::Config,
-        fs: &base::Fs,
+        fs: &cli::Fs,
     ) -> anyhow::Result<cli::Output> {
-        let iid0 = self.index.wrapping_sub(config.first_index_in_date);
-        if rl.get(self.date, iid0).is_none() {
-            anyhow::bail!("nonexistent transaction");
-        }
+        let (date, iid0) = match (&self.id, &self.date, self.index) {
+            (Some(id), _, _) => match rl.find(id) {
+                Some(found) => found,
+                None => anyhow::bail!("nonexistent transaction"),
+            },
+            (None, Some(date), Some(index)) => {
+                let date = date.resolve(cli::util::calendar_from_config(config))?;
+                let iid0 = index.wrapping_sub(config.first_index_in_date);
+                if rl.get(date, iid0).is_none() {
+                    anyhow::bail!("nonexistent transaction");
+                }
+                (date, iid0)
+            }
+            _ => unreachable!("clap should require either an ID or a date and index"),
+        };
 
         let rl_for_date = rl
             .slice_spanning_interval(base::Interval {
-                start: self.date,
-                end: self.date,
+                start: date,
+                end: date,
             })
             .iter()
             .collect::<base::Recordlist>();
 
-        let dt = self.date;
         let confirm = self.confirm;
         let lspp = move |config: &base::tree::forview::Config,
                          r: &base::Record,
                          iid0_arg: usize,
                          mut leaf_string: String|
               -> String {
-            if r.date() == dt && iid0_arg == iid0 {
+            if r.date() == date && iid0_arg == iid0 {
                 if confirm {
                     let mut msg = " <- [REMOVED]".to_string();
                     if config.charset.color {
@@ -69,7 +93,7 @@ impl Rm {
         };
 
         if self.confirm {
-            rl.remove(self.date, iid0)
+            rl.remove(date, iid0)
                 .expect("record should have already been verified to exist");
             fs.write(&rl).with_context(|| {
                 format!(
@@ -92,6 +116,7 @@ mod tests {
     /// `Some` payload of `leaf_string_postprocessor`. Rather, equality only
     /// requires either both sides to be `Some`, or both sides to be `None`.
     /// This function helps generate a dummy paylod for `Some`.
+    #[allow(clippy::type_complexity)]
     fn dummy_lspp()
     -> Box<dyn Fn(&base::tree::forview::Config, &base::Record, usize, String) -> String> {
         fn f(_: &base::tree::forview::Config, _: &base::Record, _: usize, _: String) -> String {
@@ -183,13 +208,56 @@ mod tests {
                     .with_rl(r#"{"d":"0000-01-01","c":"abc","a":111}"#),
             }
         ),
+        (
+            nonexistent_id,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "rm", "--id", "c"],
+                    res: cli::testing::ResultMatcher::ErrGlob("nonexistent transaction"),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config("{}")
+                    .with_rl(r#"{"d":"0000-01-01","c":"abc","a":111,"i":"a"}"#),
+            }
+        ),
+        (
+            wet_run_by_id,
+            cli::testing::MutCase {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "rm", "--id", "a", "--confirm"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::TreeForView(
+                        base::tree::forview::Config {
+                            charset: Default::default(),
+                            first_iid: 0,
+                            rl: r#"
+                                {"d":"0000-01-01","c":"abc","a":111,"i":"a"}
+                                {"d":"0000-01-01","c":"def","a":111,"i":"b"}
+                            "#
+                            .parse()
+                            .unwrap(),
+                            leaf_string_postprocessor: Some(dummy_lspp()),
+                        }
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new().with_config("{}").with_rl(
+                    r#"
+                        {"d":"0000-01-01","c":"abc","a":111,"i":"a"}
+                        {"d":"0000-01-01","c":"def","a":111,"i":"b"}
+                    "#
+                ),
+                final_state: cli::testing::State::new()
+                    .with_config(base::Config::default())
+                    .with_rl(r#"{"d":"0000-01-01","c":"def","a":111,"i":"b"}"#),
+            }
+        ),
     ];
 
     #[rstest]
     #[case::dry_run(
         Rm {
-            date: base::Date::MIN,
-            index: 1,
+            date: Some("0000-01-01".parse().unwrap()),
+            index: Some(1),
+            id: None,
             confirm: false,
         },
         r#"
@@ -200,8 +268,9 @@ mod tests {
     )]
     #[case::wet_run(
         Rm {
-            date: base::Date::MIN,
-            index: 1,
+            date: Some("0000-01-01".parse().unwrap()),
+            index: Some(1),
+            id: None,
             confirm: true,
         },
         r#"
@@ -210,6 +279,19 @@ mod tests {
         "#,
         "def: note <- [REMOVED]"
     )]
+    #[case::by_id(
+        Rm {
+            date: None,
+            index: None,
+            id: Some("b".to_string()),
+            confirm: false,
+        },
+        r#"
+            {"d":"0000-01-01","c":"abc","a":111,"i":"a"}
+            {"d":"0000-01-01","c":"def","a":111,"n":"note","i":"b"}
+        "#,
+        "def: note <- [WOULD BE REMOVED]"
+    )]
     fn test_leaf_string_postprocessor(
         #[case] rm: Rm,
         #[case] rl: base::Recordlist,
@@ -16,23 +16,44 @@ enum Commands {
     Init(cli::cmds::init::Init),
     Log(cli::cmds::log::Log),
     Logt(cli::cmds::logt::Logt),
+    Tmpl(cli::cmds::tmpl::Tmpl),
     Rm(cli::cmds::rm::Rm),
     View(cli::cmds::view::View),
     Cats(cli::cmds::cats::Cats),
     Sum(cli::cmds::sum::Sum),
+    Stats(cli::cmds::stats::Stats),
     Plot(cli::cmds::plot::Plot),
+    Forecast(cli::cmds::forecast::Forecast),
     Lim(cli::cmds::lim::Lim),
+    Config(cli::cmds::config::Config),
+    Tui(cli::cmds::tui::Tui),
+    Shell(cli::cmds::shell::Shell),
+    Batch(cli::cmds::batch::Batch),
+    Serve(cli::cmds::serve::Serve),
+    Completions(cli::cmds::completions::Completions),
+    #[command(name = cli::cmds::completions::COMPLETE_NAME, hide = true)]
+    Complete(cli::cmds::completions::Complete),
 }
 
 impl Root {
-    pub fn run(&self, fs: &base::Fs) -> anyhow::Result<cli::Output> {
-        if let Commands::Init(cmd) = &self.command {
-            return cmd.run(fs);
+    /// Runs the subcommand against the repository at `fs`.
+    pub fn run(&self, fs: &cli::Fs) -> anyhow::Result<cli::Output> {
+        match &self.command {
+            Commands::Init(cmd) => return cmd.run(fs),
+            Commands::Shell(cmd) => return cmd.run(fs),
+            Commands::Completions(cmd) => return cmd.run(),
+            Commands::Complete(cmd) => return cmd.run(fs),
+            _ => {}
         }
 
         if !fs.is_repo() {
             anyhow::bail!("not a repository")
         }
+        match &self.command {
+            Commands::Batch(cmd) => return cmd.run(fs),
+            Commands::Serve(cmd) => return cmd.run(fs),
+            _ => {}
+        }
         let config = fs
             .read::<base::Config>()
             .with_context(|| format!("failed to read '{}'", fs.path::<base::Config>().display()))?;
@@ -44,15 +65,36 @@ impl Root {
         })?;
 
         match &self.command {
-            Commands::Init(_) => unreachable!(),
+            Commands::Init(_)
+            | Commands::Shell(_)
+            | Commands::Batch(_)
+            | Commands::Serve(_)
+            | Commands::Completions(_)
+            | Commands::Complete(_) => unreachable!(),
             Commands::Log(cmd) => cmd.run(rl, &config, fs),
             Commands::Logt(cmd) => cmd.run(rl, &config, fs),
+            Commands::Tmpl(cmd) => cmd.run(rl, &config, fs),
             Commands::Rm(cmd) => cmd.run(rl, &config, fs),
             Commands::View(cmd) => cmd.run(rl, &config),
-            Commands::Cats(cmd) => cmd.run(rl),
+            Commands::Cats(cmd) => cmd.run(rl, &config),
             Commands::Sum(cmd) => cmd.run(rl, &config),
+            Commands::Stats(cmd) => cmd.run(rl, &config),
             Commands::Plot(cmd) => cmd.run(rl, &config),
+            Commands::Forecast(cmd) => cmd.run(rl, &config),
             Commands::Lim(cmd) => cmd.run(rl, &config, fs),
+            Commands::Config(cmd) => cmd.run(&config, fs),
+            Commands::Tui(cmd) => cmd.run(rl, &config, fs),
+        }
+    }
+
+    /// Returns whether the command may appear in 'batch' scripts. These are the
+    /// commands that change records or limits.
+    pub fn is_batchable(&self) -> bool {
+        match &self.command {
+            Commands::Log(_) | Commands::Logt(_) => true,
+            Commands::Rm(cmd) => cmd.is_confirmed(),
+            Commands::Lim(cmd) => cmd.is_set(),
+            _ => false,
         }
     }
 }
@@ -66,12 +108,19 @@ mod tests {
 
     #[rstest]
     #[case(&["", "log", "aaa", "123"])]
+    #[case(&["", "tmpl", "show"])]
     #[case(&["", "rm", "d", "0"])]
     #[case(&["", "view"])]
     #[case(&["", "cats"])]
     #[case(&["", "sum"])]
+    #[case(&["", "stats"])]
     #[case(&["", "plot"])]
+    #[case(&["", "forecast"])]
     #[case(&["", "lim", "--set", "0"])]
+    #[case(&["", "config", "list"])]
+    #[case(&["", "tui"])]
+    #[case(&["", "batch"])]
+    #[case(&["", "serve"])]
     fn test_error_if_not_a_repo(#[case] args: &[&str]) {
         let (fs, _td) = testing::tempfs();
         let root = match <Root as clap::Parser>::try_parse_from(args) {
@@ -0,0 +1,42 @@
+use anyhow::Context;
+
+use crate::cli;
+
+/// Serve a JSON API over HTTP
+///
+/// Read endpoints take the arguments of the command of the same name as query
+/// parameters:
+///   GET /view?interval=&categories=&notCategories=&fullmatch=
+///   GET /sum?interval=&level=&categories=&notCategories=&fullmatch=
+///   GET /plot?interval=&unit=&categories=&notCategories=&fullmatch=
+///   GET /lim?year=&account=
+/// where 'unit' is one of 'day', 'week', 'month', 'quarter' or 'year'.
+///
+/// Write endpoints take a JSON body:
+///   POST /log {"category", "amount", "date", "note", "create", "split"}
+///   POST /rm {"id"} or {"date", "index"}
+/// where amounts are strings written as on the command line, and 'split' is a
+/// list of {"category", "amount"}. Both respond with the records of the date
+/// of the change, and 'log' also with the list of "warnings" it raised, such as
+/// exceeded TFSA room. Bodies over 64 KiB are refused.
+///
+/// Amounts in responses are in cents. Errors are responded to with
+/// {"error": MESSAGE}.
+#[derive(clap::Parser)]
+#[command(verbatim_doc_comment)]
+pub struct Serve {
+    /// Address to listen on
+    #[arg(long, value_name = "ADDRESS", default_value = "127.0.0.1:8080")]
+    listen: std::net::SocketAddr,
+}
+
+impl Serve {
+    pub fn run(&self, fs: &cli::Fs) -> anyhow::Result<cli::Output> {
+        let server = tiny_http::Server::http(self.listen)
+            .map_err(|e| anyhow::anyhow!(e))
+            .with_context(|| format!("failed to listen on '{}'", self.listen))?;
+        println!("Listening on http://{}", self.listen);
+        cli::serve::serve(fs, &server);
+        Ok(cli::Output::Str("Server stopped.".to_string()))
+    }
+}
@@ -0,0 +1,196 @@
+use anyhow::Context;
+
+use crate::cli;
+
+const PROMPT: &str = "ledger> ";
+
+/// Run commands interactively
+///
+/// Accepts the same commands as 'ledger', one per line, and quotes words the
+/// way a POSIX shell does. Repository files are read once and kept in memory,
+/// and changes are written as soon as they are made. Use the up and down arrows
+/// to recall previous commands, and 'exit' or Ctrl-D to quit.
+#[derive(clap::Parser)]
+pub struct Shell {}
+
+impl Shell {
+    pub fn run(&self, fs: &cli::Fs) -> anyhow::Result<cli::Output> {
+        let fs = cli::Fs::new_cached(fs.dir());
+        let mut editor =
+            rustyline::DefaultEditor::new().context("failed to set up the line editor")?;
+        loop {
+            let line = match editor.readline(PROMPT) {
+                Ok(line) => line,
+                Err(rustyline::error::ReadlineError::Interrupted) => continue,
+                Err(rustyline::error::ReadlineError::Eof) => break,
+                Err(e) => return Err(e).context("failed to read line"),
+            };
+            if !line.trim().is_empty() {
+                editor
+                    .add_history_entry(line.as_str())
+                    .context("failed to add line to history")?;
+            }
+            let keep_going = eval(&fs, &line, &mut std::io::stdout(), &mut std::io::stderr())
+                .context("failed to write output")?;
+            if !keep_going {
+                break;
+            }
+        }
+        Ok(cli::Output::Str(String::new()))
+    }
+}
+
+/// Runs one line of input, writing to `out` and `err` what 'ledger' would write
+/// to stdout and stderr. Returns false if the shell should exit.
+fn eval(
+    fs: &cli::Fs,
+    line: &str,
+    out: &mut impl std::io::Write,
+    err: &mut impl std::io::Write,
+) -> std::io::Result<bool> {
+    let words = match cli::util::split_words(line) {
+        Ok(words) => words,
+        Err(e) => {
+            writeln!(err, "error: {}", e)?;
+            return Ok(true);
+        }
+    };
+    match words.first().map(String::as_str) {
+        None => return Ok(true),
+        Some("exit" | "quit") => return Ok(false),
+        Some("shell") => {
+            writeln!(err, "error: already in a shell")?;
+            return Ok(true);
+        }
+        _ => {}
+    }
+
+    let args = std::iter::once("ledger".to_string()).chain(words);
+    let root = match <cli::Root as clap::Parser>::try_parse_from(args) {
+        Ok(root) => root,
+        Err(e) => {
+            // Help and version requests are "errors" too, but go to stdout.
+            match e.use_stderr() {
+                true => write!(err, "{}", e.render())?,
+                false => write!(out, "{}", e.render())?,
+            }
+            return Ok(true);
+        }
+    };
+    match root.run(fs) {
+        Ok(output) => write!(out, "{}", output)?,
+        Err(e) => {
+            write!(err, "error")?;
+            for cause in e.chain() {
+                write!(err, ": {}", cause)?;
+            }
+            writeln!(err)?;
+        }
+    }
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::base;
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn test_eval() {
+        let (fs, _td) = cli::testing::tempfs();
+        cli::testing::StrState::new()
+            .with_config("{}")
+            .with_rl(r#"{"d":"2015-03-30","c":"aaa","a":100,"i":"000001"}"#)
+            .to_fs(&fs);
+        let fs = cli::Fs::new_cached(fs.dir());
+
+        let mut out = Vec::new();
+        let mut err = Vec::new();
+        let lines = [
+            "",
+            r#"log aaa 2 -n "x y""#,
+            "rm d 0 --confirm",
+            "view d",
+            "log bbb 3",
+            "log 'bbb",
+            "bogus",
+            "shell",
+            "exit",
+            "view d",
+        ];
+        let mut ran = 0;
+        for line in lines {
+            ran += 1;
+            if !eval(&fs, line, &mut out, &mut err).unwrap() {
+                break;
+            }
+        }
+        assert_eq!(ran, 9);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            indoc!(
+                "
+                2015
+                `-- Mar
+                    `-- 30th
+                        |-- 0 -- 1.00  aaa
+                        `-- 1 -- 2.00  aaa: x y
+                2015
+                `-- Mar
+                    `-- 30th
+                        |-- 0 -- 1.00  aaa <- [REMOVED]
+                        `-- 1 -- 2.00  aaa: x y
+                2015
+                `-- Mar
+                    `-- 30th
+                        `-- 0 -- 2.00  aaa: x y
+                "
+            )
+        );
+        let err = String::from_utf8(err).unwrap();
+        let mut err = err.lines();
+        assert_eq!(err.next(), Some("error: nonexistent category"));
+        assert_eq!(err.next(), Some("error: unterminated quote"));
+        assert_eq!(err.next(), Some("error: unrecognized subcommand 'bogus'"));
+        assert_eq!(err.last(), Some("error: already in a shell"));
+
+        let rl = cli::Fs::new(fs.dir()).read::<base::Recordlist>().unwrap();
+        assert_eq!(rl, fs.read::<base::Recordlist>().unwrap());
+        assert_eq!(rl.len(), 1);
+    }
+
+    #[test]
+    fn test_eval_batch() {
+        let (fs, td) = cli::testing::tempfs();
+        cli::testing::StrState::new()
+            .with_config("{}")
+            .with_rl(r#"{"d":"2015-03-30","c":"aaa","a":100,"i":"000001"}"#)
+            .to_fs(&fs);
+        let fs = cli::Fs::new_cached(fs.dir());
+        let script = td.path().join("script");
+        std::fs::write(&script, "log aaa 5").unwrap();
+
+        // The batch's changes must not be overwritten by the next command,
+        // even though the shell read the records before the batch ran.
+        let mut out = Vec::new();
+        let mut err = Vec::new();
+        let lines = [
+            "view d".to_string(),
+            format!("batch '{}'", script.display()),
+            "log aaa 7".to_string(),
+        ];
+        for line in &lines {
+            assert!(eval(&fs, line, &mut out, &mut err).unwrap());
+        }
+        assert_eq!(String::from_utf8(err).unwrap(), "");
+
+        let rl = cli::Fs::new(fs.dir()).read::<base::Recordlist>().unwrap();
+        assert_eq!(rl, fs.read::<base::Recordlist>().unwrap());
+        assert_eq!(
+            rl.iter().map(|r| r.amount().0).collect::<Vec<_>>(),
+            [100, 500, 700]
+        );
+    }
+}
@@ -0,0 +1,172 @@
+use crate::base;
+use crate::cli;
+
+/// View monthly statistics of transaction totals
+#[derive(clap::Parser)]
+pub struct Stats {
+    /// Interval to compute statistics over
+    ///
+    /// Statistics are computed from calendar months. Months before the first
+    /// transaction in the ledger are not counted, nor are the current month and
+    /// those after it, but months without any matching transactions in between
+    /// count as zero. The default interval is the past 12 complete months.
+    #[arg(
+        default_value = "m-12:M-1",
+        long_help = cli::sharedopts::INTERVAL_HELP_LONG,
+    )]
+    interval: cli::sharedopts::IntervalArg,
+
+    /// Category level to aggregate on
+    ///
+    /// Examples:
+    /// LEVEL = 2: commute/car/gas -> commute/car
+    /// LEVEL = 2: commute/car -> commute/car
+    /// LEVEL = 2: commute -> commute
+    /// LEVEL = 0: commute -> All
+    /// LEVEL = 0: some/other/category -> All
+    #[arg(short, long, default_value_t = 1, verbatim_doc_comment)]
+    level: usize,
+
+    #[command(flatten)]
+    categories_opts: cli::sharedopts::CategoriesOpts,
+}
+
+impl Stats {
+    pub fn run(&self, rl: base::Recordlist, config: &base::Config) -> anyhow::Result<cli::Output> {
+        let (categories, not_categories) = self.categories_opts.patterns(config);
+        let interval = self
+            .interval
+            .resolve(cli::util::calendar_from_config(config))?;
+        let bounds = base::Interval {
+            start: interval.start.max(
+                rl.spanned_interval()
+                    .start
+                    .first_of(base::Datepart::Month, base::Calendar::default()),
+            ),
+            end: base::Date::today()
+                .first_of(base::Datepart::Month, base::Calendar::default())
+                .shift(base::Datepart::Day, -1)
+                .map_or(base::Date::MIN, |end| end.min(interval.end)),
+        };
+        let rl = base::filter_rl(&rl, bounds, &categories, &not_categories);
+        let tr_config = base::tree::forstats::Config {
+            charset: cli::util::charset_from_config(config),
+            bounds,
+            level: self.level,
+            rl,
+        };
+        Ok(cli::Output::TreeForStats(tr_config, interval))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    cli::testing::generate_testcases![
+        (
+            no_matching_categories,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "stats", "-c", "rent"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::TreeForStats(
+                        base::tree::forstats::Config {
+                            charset: Default::default(),
+                            bounds: "2014-12-01:2015-02-28".parse().unwrap(),
+                            level: 1,
+                            rl: base::Recordlist::new(),
+                        },
+                        "2014-03-01:2015-02-28".parse().unwrap(),
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config("{}")
+                    .with_rl(r#"{"d":"2014-12-15","c":"food","a":-100}"#),
+            }
+        ),
+        (
+            bounded_by_ledger_start,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "stats", "-c", "food"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::TreeForStats(
+                        base::tree::forstats::Config {
+                            charset: Default::default(),
+                            bounds: "2014-12-01:2015-02-28".parse().unwrap(),
+                            level: 1,
+                            rl: r#"
+                                {"d":"2014-12-15","c":"food","a":-100}
+                                {"d":"2015-02-15","c":"food","a":-200}
+                            "#
+                            .parse()
+                            .unwrap(),
+                        },
+                        "2014-03-01:2015-02-28".parse().unwrap(),
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new().with_config("{}").with_rl(
+                    r#"
+                        {"d":"2014-12-15","c":"food","a":-100}
+                        {"d":"2015-01-15","c":"rent","a":-900}
+                        {"d":"2015-02-15","c":"food","a":-200}
+                        {"d":"2015-03-15","c":"food","a":-300}
+                    "#
+                ),
+            }
+        ),
+        (
+            bounded_by_current_month,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "stats", "2014-12:"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::TreeForStats(
+                        base::tree::forstats::Config {
+                            charset: Default::default(),
+                            bounds: "2014-12-01:2015-02-28".parse().unwrap(),
+                            level: 1,
+                            rl: r#"
+                                {"d":"2014-12-15","c":"food","a":-100}
+                                {"d":"2015-02-15","c":"food","a":-200}
+                            "#
+                            .parse()
+                            .unwrap(),
+                        },
+                        "2014-12-01:9999-12-31".parse().unwrap(),
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new().with_config("{}").with_rl(
+                    r#"
+                        {"d":"2014-12-15","c":"food","a":-100}
+                        {"d":"2015-02-15","c":"food","a":-200}
+                        {"d":"2015-03-15","c":"food","a":-300}
+                    "#
+                ),
+            }
+        ),
+        (
+            alias,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "stats", "-c", "f", "-x", "r"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::TreeForStats(
+                        base::tree::forstats::Config {
+                            charset: Default::default(),
+                            bounds: "2014-12-01:2015-02-28".parse().unwrap(),
+                            level: 1,
+                            rl: r#"{"d":"2014-12-15","c":"food","a":-100}"#.parse().unwrap(),
+                        },
+                        "2014-03-01:2015-02-28".parse().unwrap(),
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(r#"{"aliases": {"f": "food", "r": "food/rent"}}"#)
+                    .with_rl(
+                        r#"
+                            {"d":"2014-12-15","c":"food","a":-100}
+                            {"d":"2015-01-15","c":"food/rent","a":-900}
+                        "#
+                    ),
+            }
+        ),
+    ];
+}
@@ -9,7 +9,7 @@ pub struct Sum {
         help = cli::sharedopts::INTERVAL_HELP,
         long_help = cli::sharedopts::INTERVAL_HELP_LONG,
     )]
-    interval: base::Interval,
+    interval: cli::sharedopts::IntervalArg,
 
     /// Category level to aggregate on
     ///
@@ -28,20 +28,16 @@ pub struct Sum {
 
 impl Sum {
     pub fn run(&self, rl: base::Recordlist, config: &base::Config) -> anyhow::Result<cli::Output> {
-        let categories = cli::util::preprocess_categories(
-            &self.categories_opts.categories,
-            self.categories_opts.fullmatch,
-        );
-        let not_categories = cli::util::preprocess_categories(
-            &self.categories_opts.not_categories,
-            self.categories_opts.fullmatch,
-        );
-        let rl = cli::util::filter_rl(&rl, self.interval, &categories, &not_categories);
+        let (categories, not_categories) = self.categories_opts.patterns(config);
+        let interval = self
+            .interval
+            .resolve(cli::util::calendar_from_config(config))?;
+        let rl = base::filter_rl(&rl, interval, &categories, &not_categories);
         let tr_config = base::tree::forsum::Config {
             charset: cli::util::charset_from_config(config),
             level: self.level,
             rl,
         };
-        Ok(cli::Output::TreeForSum(tr_config, self.interval))
+        Ok(cli::Output::TreeForSum(tr_config, interval))
     }
 }
@@ -0,0 +1,472 @@
+use anyhow::Context;
+
+use crate::base;
+use crate::cli;
+
+/// Manage transaction templates
+///
+/// Templates are logged with 'logt'. Changes are validated before the config is
+/// written, so a mistake leaves the config as it was.
+#[derive(clap::Parser)]
+pub struct Tmpl {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(clap::Subcommand)]
+enum Commands {
+    Create(Create),
+    Capture(Capture),
+    Rename(Rename),
+    Delete(Delete),
+    Show(Show),
+}
+
+/// Create a template from entries given on the command line
+#[derive(clap::Args)]
+struct Create {
+    /// Name of the template
+    name: String,
+
+    /// Entry of the template, in the form CATEGORY=AMOUNT
+    ///
+    /// AMOUNT is either a fixed amount, '$N' for the Nth amount given to
+    /// 'logt', or 'P% of $N' for a percentage of it. For example, 'salary=$1'
+    /// or 'tax=-20% of $1'. Unsigned fixed amounts take the sign configured by
+    /// 'unsignedIsNegative'.
+    #[arg(required = true, value_name = "ENTRY", allow_negative_numbers = true)]
+    entries: Vec<EntryArg>,
+
+    /// Allow entries whose categories do not already exist
+    #[arg(short, long)]
+    create: bool,
+}
+
+/// Create a template from the records on a date
+///
+/// Each record becomes an entry with the record's category, amount and note.
+#[derive(clap::Args)]
+struct Capture {
+    /// Name of the template
+    name: String,
+
+    /// Date of the records
+    #[arg(default_value = "d")]
+    date: cli::sharedopts::DateArg,
+}
+
+/// Rename a template
+#[derive(clap::Args)]
+struct Rename {
+    /// Current name of the template
+    old: String,
+
+    /// New name of the template
+    new: String,
+}
+
+/// Delete a template
+#[derive(clap::Args)]
+struct Delete {
+    /// Name of the template
+    name: String,
+}
+
+/// Display templates
+#[derive(clap::Args)]
+struct Show {
+    /// Name of the template
+    ///
+    /// If omitted, displays all templates.
+    name: Option<String>,
+}
+
+impl Tmpl {
+    pub fn run(
+        &self,
+        rl: base::Recordlist,
+        config: &base::Config,
+        fs: &cli::Fs,
+    ) -> anyhow::Result<cli::Output> {
+        let mut config = config.clone();
+        let (output, name) = match &self.command {
+            Commands::Show(cmd) => {
+                if let Some(name) = &cmd.name {
+                    ensure_exists(&config, name)?;
+                }
+                return Ok(to_output(&config, cmd.name.as_deref()));
+            }
+            Commands::Create(cmd) => {
+                ensure_new(&config, &cmd.name)?;
+                let mut entries = Vec::with_capacity(cmd.entries.len());
+                for entry in &cmd.entries {
+                    let mut entry = entry.to_entry(config.unsigned_is_negative);
+                    entry.category = config.expand_category(&entry.category);
+                    if !cmd.create && !rl.iter().any(|r| r.category() == &entry.category) {
+                        anyhow::bail!("nonexistent category '{}'", entry.category)
+                    }
+                    entries.push(entry);
+                }
+                config.templates.insert(cmd.name.clone(), entries);
+                (None, &cmd.name)
+            }
+            Commands::Capture(cmd) => {
+                ensure_new(&config, &cmd.name)?;
+                let date = cmd.date.resolve(cli::util::calendar_from_config(&config))?;
+                let entries = rl
+                    .slice_spanning_interval(base::Interval {
+                        start: date,
+                        end: date,
+                    })
+                    .iter()
+                    .map(|r| base::config::TemplateEntry {
+                        category: r.category().clone(),
+                        amount: base::config::TemplateAmount::Fixed(r.amount()),
+                        note: r.note().to_string(),
+                        days: 0,
+                    })
+                    .collect::<Vec<_>>();
+                if entries.is_empty() {
+                    anyhow::bail!("no records on {}", date)
+                }
+                config.templates.insert(cmd.name.clone(), entries);
+                (None, &cmd.name)
+            }
+            Commands::Rename(cmd) => {
+                ensure_exists(&config, &cmd.old)?;
+                if cmd.old != cmd.new {
+                    ensure_new(&config, &cmd.new)?;
+                }
+                let entries = config.templates.remove(&cmd.old).expect("should exist");
+                config.templates.insert(cmd.new.clone(), entries);
+                (None, &cmd.new)
+            }
+            Commands::Delete(cmd) => {
+                ensure_exists(&config, &cmd.name)?;
+                config.templates.remove(&cmd.name);
+                let output = cli::Output::Str(format!("Template '{}' deleted.", cmd.name));
+                (Some(output), &cmd.name)
+            }
+        };
+        fs.write(&config).with_context(|| {
+            format!("failed to write '{}'", fs.path::<base::Config>().display())
+        })?;
+        Ok(output.unwrap_or_else(|| to_output(&config, Some(name))))
+    }
+}
+
+fn ensure_exists(config: &base::Config, name: &str) -> anyhow::Result<()> {
+    if !config.templates.contains_key(name) {
+        anyhow::bail!("unknown template '{}'", name)
+    }
+    Ok(())
+}
+
+fn ensure_new(config: &base::Config, name: &str) -> anyhow::Result<()> {
+    if name.is_empty() {
+        anyhow::bail!("template name is empty")
+    }
+    if config.templates.contains_key(name) {
+        anyhow::bail!("template '{}' already exists", name)
+    }
+    Ok(())
+}
+
+/// Returns the tree of the template named `name`, or of all templates.
+fn to_output(config: &base::Config, name: Option<&str>) -> cli::Output {
+    let templates = config
+        .templates
+        .iter()
+        .filter(|(k, _)| name.is_none_or(|name| name == k.as_str()))
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+    cli::Output::TreeForLogt(base::tree::forlogt::Config {
+        charset: cli::util::charset_from_config(config),
+        templates,
+    })
+}
+
+/// Command line template entry.
+#[derive(Clone)]
+struct EntryArg {
+    category: base::Category,
+    amount: AmountArg,
+}
+
+#[derive(Clone, Copy)]
+enum AmountArg {
+    Fixed(cli::cmds::log::CentsArg),
+    Param(base::config::TemplateParam),
+}
+
+impl EntryArg {
+    fn to_entry(&self, unsigned_is_negative: bool) -> base::config::TemplateEntry {
+        let amount = match self.amount {
+            AmountArg::Fixed(x) => {
+                base::config::TemplateAmount::Fixed(x.to_cents(unsigned_is_negative))
+            }
+            AmountArg::Param(p) => base::config::TemplateAmount::Param(p),
+        };
+        base::config::TemplateEntry {
+            category: self.category.clone(),
+            amount,
+            note: String::new(),
+            days: 0,
+        }
+    }
+}
+
+impl std::str::FromStr for EntryArg {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((category, amount)) = s.rsplit_once('=') else {
+            anyhow::bail!("expected CATEGORY=AMOUNT")
+        };
+        let amount = match amount.split_once('$') {
+            None => AmountArg::Fixed(amount.parse()?),
+            Some((percent, param)) => {
+                let percent = percent.trim();
+                let percent = percent.strip_suffix("of").unwrap_or(percent).trim_end();
+                let percent = match percent.strip_suffix('%') {
+                    Some(percent) => percent.trim_end().parse()?,
+                    None if percent.is_empty() => "100".parse()?,
+                    None => anyhow::bail!("expected '$N' or 'P% of $N'"),
+                };
+                let param = param
+                    .trim()
+                    .parse()
+                    .with_context(|| format!("invalid parameter '${}'", param))?;
+                AmountArg::Param(base::config::TemplateParam { param, percent })
+            }
+        };
+        Ok(Self {
+            category: category.parse()?,
+            amount,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    const CONFIG: &str = r#"{"templates": {"t": [{"category": "aaa", "amount": 100}]}}"#;
+    const RL: &str = r#"
+        {"d":"2015-03-29","c":"bbb","a":-200,"n":"x"}
+        {"d":"2015-03-30","c":"aaa","a":100,"n":""}
+        {"d":"2015-03-30","c":"bbb","a":-300,"n":"y z"}
+    "#;
+
+    cli::testing::generate_testcases![
+        (
+            create,
+            cli::testing::MutCase {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "tmpl", "create", "u", "aaa=$1", "bbb=-5.5% of $1", "bbb=-2"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::TreeForLogt(
+                        base::tree::forlogt::Config {
+                            charset: Default::default(),
+                            templates: [(
+                                "u".to_string(),
+                                serde_json::from_str(
+                                    r#"[
+                                        {"category": "aaa", "amount": {"param": 1}},
+                                        {"category": "bbb", "amount": {"param": 1, "percent": -5.5}},
+                                        {"category": "bbb", "amount": -200}
+                                    ]"#
+                                )
+                                .unwrap()
+                            )]
+                            .into(),
+                        }
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(CONFIG)
+                    .with_rl(RL),
+                final_state: cli::testing::State::new()
+                    .with_config(
+                        r#"{"templates": {
+                            "t": [{"category": "aaa", "amount": 100}],
+                            "u": [
+                                {"category": "aaa", "amount": {"param": 1}},
+                                {"category": "bbb", "amount": {"param": 1, "percent": -5.5}},
+                                {"category": "bbb", "amount": -200}
+                            ]
+                        }}"#
+                    )
+                    .with_rl(RL),
+            }
+        ),
+        (
+            create_nonexistent_category,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "tmpl", "create", "u", "aaa=1", "ccc=1"],
+                    res: cli::testing::ResultMatcher::ErrGlob("nonexistent category 'ccc'"),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(CONFIG)
+                    .with_rl(RL),
+            }
+        ),
+        (
+            create_existing,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "tmpl", "create", "t", "aaa=1"],
+                    res: cli::testing::ResultMatcher::ErrGlob("template 't' already exists"),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(CONFIG)
+                    .with_rl(RL),
+            }
+        ),
+        (
+            capture,
+            cli::testing::MutCase {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "tmpl", "capture", "u", "2015-03-30"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::TreeForLogt(
+                        base::tree::forlogt::Config {
+                            charset: Default::default(),
+                            templates: [(
+                                "u".to_string(),
+                                serde_json::from_str(
+                                    r#"[
+                                        {"category": "aaa", "amount": 100},
+                                        {"category": "bbb", "amount": -300, "note": "y z"}
+                                    ]"#
+                                )
+                                .unwrap()
+                            )]
+                            .into(),
+                        }
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(CONFIG)
+                    .with_rl(RL),
+                final_state: cli::testing::State::new()
+                    .with_config(
+                        r#"{"templates": {
+                            "t": [{"category": "aaa", "amount": 100}],
+                            "u": [
+                                {"category": "aaa", "amount": 100},
+                                {"category": "bbb", "amount": -300, "note": "y z"}
+                            ]
+                        }}"#
+                    )
+                    .with_rl(RL),
+            }
+        ),
+        (
+            capture_empty_date,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "tmpl", "capture", "u", "2015-03-28"],
+                    res: cli::testing::ResultMatcher::ErrGlob("no records on 2015-03-28"),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(CONFIG)
+                    .with_rl(RL),
+            }
+        ),
+        (
+            rename,
+            cli::testing::MutCase {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "tmpl", "rename", "t", "u"],
+                    res: cli::testing::ResultMatcher::OkExact(cli::Output::TreeForLogt(
+                        base::tree::forlogt::Config {
+                            charset: Default::default(),
+                            templates: [(
+                                "u".to_string(),
+                                serde_json::from_str(r#"[{"category": "aaa", "amount": 100}]"#)
+                                    .unwrap()
+                            )]
+                            .into(),
+                        }
+                    )),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(CONFIG)
+                    .with_rl(RL),
+                final_state: cli::testing::State::new()
+                    .with_config(r#"{"templates": {"u": [{"category": "aaa", "amount": 100}]}}"#)
+                    .with_rl(RL),
+            }
+        ),
+        (
+            rename_unknown,
+            cli::testing::Case {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "tmpl", "rename", "u", "v"],
+                    res: cli::testing::ResultMatcher::ErrGlob("unknown template 'u'"),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(CONFIG)
+                    .with_rl(RL),
+            }
+        ),
+        (
+            delete,
+            cli::testing::MutCase {
+                invocations: &[cli::testing::Invocation {
+                    args: &["", "tmpl", "delete", "t"],
+                    res: cli::testing::ResultMatcher::OkStrGlob("Template 't' deleted."),
+                }],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(CONFIG)
+                    .with_rl(RL),
+                final_state: cli::testing::State::new()
+                    .with_config(r#"{"templates": {}}"#)
+                    .with_rl(RL),
+            }
+        ),
+        (
+            show,
+            cli::testing::Case {
+                invocations: &[
+                    cli::testing::Invocation {
+                        args: &["", "tmpl", "show", "t"],
+                        res: cli::testing::ResultMatcher::OkExact(cli::Output::TreeForLogt(
+                            base::tree::forlogt::Config {
+                                charset: Default::default(),
+                                templates: [(
+                                    "t".to_string(),
+                                    serde_json::from_str(
+                                        r#"[{"category": "aaa", "amount": 100}]"#
+                                    )
+                                    .unwrap()
+                                )]
+                                .into(),
+                            }
+                        )),
+                    },
+                    cli::testing::Invocation {
+                        args: &["", "tmpl", "show", "u"],
+                        res: cli::testing::ResultMatcher::ErrGlob("unknown template 'u'"),
+                    },
+                ],
+                initial_state: cli::testing::StrState::new()
+                    .with_config(CONFIG)
+                    .with_rl(RL),
+            }
+        ),
+    ];
+
+    #[rstest]
+    #[case("aaa=x", "invalid digit found in string")]
+    #[case("aaa", "expected CATEGORY=AMOUNT")]
+    #[case("aaa=5 of $1", "expected '$N' or 'P% of $N'")]
+    #[case("aaa=5.123% of $1", "invalid percentage '5.123'")]
+    #[case("aaa=$0", "invalid parameter '$0'")]
+    fn test_entry_arg_err(#[case] s: &str, #[case] want: &str) {
+        let e = s.parse::<EntryArg>().err().unwrap();
+        assert_eq!(e.to_string(), want);
+    }
+}
@@ -0,0 +1,48 @@
+use anyhow::Context;
+
+use crate::base;
+use crate::cli;
+
+/// Browse and edit transactions in a full-screen terminal interface
+///
+/// Transactions are listed on the left, and their totals on the right. Press
+/// '/' to edit the filters, which update the list as you type. Press 'a', 'e',
+/// or 'd' to add, edit, or delete a transaction. Changes are written as soon as
+/// they are confirmed.
+#[derive(clap::Parser)]
+pub struct Tui {
+    #[arg(
+        default_value = "m",
+        help = cli::sharedopts::INTERVAL_HELP,
+        long_help = cli::sharedopts::INTERVAL_HELP_LONG,
+    )]
+    interval: cli::sharedopts::IntervalArg,
+
+    #[command(flatten)]
+    categories_opts: cli::sharedopts::CategoriesOpts,
+}
+
+impl Tui {
+    pub fn run(
+        &self,
+        rl: base::Recordlist,
+        config: &base::Config,
+        fs: &cli::Fs,
+    ) -> anyhow::Result<cli::Output> {
+        self.interval
+            .resolve(cli::util::calendar_from_config(config))?;
+        let filter = cli::tui::Filter {
+            interval: self.interval.as_str().to_string(),
+            categories: self.categories_opts.categories.join(","),
+            not_categories: self.categories_opts.not_categories.join(","),
+            fullmatch: self.categories_opts.fullmatch,
+        };
+        let mut app = cli::tui::App::new(rl, config, fs, filter);
+        {
+            let mut terminal =
+                cli::tui::Terminal::new().context("failed to set up the terminal")?;
+            app.run(&mut terminal)?;
+        }
+        Ok(cli::Output::Str(app.summary()))
+    }
+}
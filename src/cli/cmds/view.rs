@@ -9,28 +9,47 @@ pub struct View {
         help = cli::sharedopts::INTERVAL_HELP,
         long_help = cli::sharedopts::INTERVAL_HELP_LONG,
     )]
-    interval: base::Interval,
+    interval: cli::sharedopts::IntervalArg,
 
     #[command(flatten)]
     categories_opts: cli::sharedopts::CategoriesOpts,
+
+    /// Show the ID of every transaction
+    ///
+    /// IDs stay the same as other transactions are logged or removed, and may
+    /// be given to 'rm --id'.
+    #[arg(long)]
+    ids: bool,
 }
 
 impl View {
     pub fn run(&self, rl: base::Recordlist, config: &base::Config) -> anyhow::Result<cli::Output> {
-        let categories = cli::util::preprocess_categories(
-            &self.categories_opts.categories,
-            self.categories_opts.fullmatch,
-        );
-        let not_categories = cli::util::preprocess_categories(
-            &self.categories_opts.not_categories,
-            self.categories_opts.fullmatch,
-        );
-        let rl = cli::util::filter_rl(&rl, self.interval, &categories, &not_categories);
+        let (categories, not_categories) = self.categories_opts.patterns(config);
+        let interval = self
+            .interval
+            .resolve(cli::util::calendar_from_config(config))?;
+        let rl = base::filter_rl(&rl, interval, &categories, &not_categories);
+        let leaf_string_postprocessor = if self.ids {
+            let lspp = |_: &base::tree::forview::Config,
+                        r: &base::Record,
+                        _: usize,
+                        mut leaf_string: String|
+             -> String {
+                if let Some(id) = r.id() {
+                    leaf_string.push_str(" #");
+                    leaf_string.push_str(id);
+                }
+                leaf_string
+            };
+            Some(Box::new(lspp) as _)
+        } else {
+            None
+        };
         let tr_config = base::tree::forview::Config {
             charset: cli::util::charset_from_config(config),
             first_iid: config.first_index_in_date,
             rl,
-            leaf_string_postprocessor: None,
+            leaf_string_postprocessor,
         };
         Ok(cli::Output::TreeForView(tr_config))
     }
@@ -0,0 +1,291 @@
+use crate::base;
+
+/// Application filesystem as seen by commands. Wraps [`base::Fs`], optionally
+/// keeping what was read in memory and holding writes back until `flush`.
+pub struct Fs {
+    inner: base::Fs,
+    /// Objects last read or written, keyed by type, if caching is enabled.
+    cache: Option<std::cell::RefCell<Cache>>,
+    /// Writes waiting for `flush`, if writes are being deferred.
+    pending: std::cell::RefCell<Option<Pending>>,
+}
+
+type Cache = std::collections::HashMap<std::any::TypeId, Box<dyn std::any::Any>>;
+
+#[derive(Default)]
+struct Pending {
+    /// Contents of the files to write, keyed by path.
+    files: std::collections::BTreeMap<std::path::PathBuf, String>,
+    /// Objects written, keyed by type.
+    objects: Cache,
+}
+
+impl Fs {
+    /// Returns a filesystem anchored at `dir`, without caching.
+    pub fn new<P>(dir: P) -> Self
+    where
+        P: Into<std::path::PathBuf>,
+    {
+        Self {
+            inner: base::Fs::new(dir),
+            cache: None,
+            pending: Default::default(),
+        }
+    }
+
+    /// Like `new`, but each file is deserialized at most once. Later reads
+    /// return whatever was last read or written, so changes made to the files
+    /// by other processes go unnoticed.
+    pub fn new_cached<P>(dir: P) -> Self
+    where
+        P: Into<std::path::PathBuf>,
+    {
+        Self {
+            cache: Some(Default::default()),
+            ..Self::new(dir)
+        }
+    }
+
+    /// Returns the working directory.
+    pub fn dir(&self) -> &std::path::Path {
+        self.inner.dir()
+    }
+
+    /// Returns whether the working directory has a config file.
+    pub fn is_repo(&self) -> bool {
+        self.inner.is_repo()
+    }
+
+    /// Returns the path which `T` will be serialized to and deserialized from.
+    pub fn path<T>(&self) -> std::path::PathBuf
+    where
+        T: base::fs::Io,
+    {
+        self.inner.path::<T>()
+    }
+
+    /// Deserializes `T`. Deferred writes and the cache take precedence over
+    /// the disk.
+    pub fn read<T>(&self) -> Result<T, base::fs::ReadError>
+    where
+        T: base::fs::Io + Clone + 'static,
+        <T as std::str::FromStr>::Err: std::error::Error + Send + Sync + 'static,
+    {
+        if let Some(pending) = &*self.pending.borrow()
+            && let Some(obj) = lookup::<T>(&pending.objects)
+        {
+            return Ok(obj);
+        }
+        if let Some(obj) = self.cache.as_ref().and_then(|c| lookup::<T>(&c.borrow())) {
+            return Ok(obj);
+        }
+        let obj = self.inner.read::<T>()?;
+        self.cache(&obj);
+        Ok(obj)
+    }
+
+    /// Serializes `obj`, or holds it back if writes are being deferred.
+    pub fn write<T>(&self, obj: &T) -> std::io::Result<()>
+    where
+        T: base::fs::Io + Clone + 'static,
+    {
+        match &mut *self.pending.borrow_mut() {
+            Some(pending) => {
+                pending.files.insert(self.path::<T>(), obj.to_string());
+                pending
+                    .objects
+                    .insert(std::any::TypeId::of::<T>(), Box::new(obj.clone()));
+            }
+            None => {
+                self.inner.write(obj)?;
+                self.cache(obj);
+            }
+        }
+        Ok(())
+    }
+
+    /// Holds back writes until `flush` or `discard` is called. Until then,
+    /// they are only visible to reads through this object.
+    pub fn defer(&self) {
+        let mut pending = self.pending.borrow_mut();
+        assert!(pending.is_none(), "writes should not already be deferred");
+        *pending = Some(Default::default());
+    }
+
+    /// Writes everything that was deferred, and stops deferring. Either all of
+    /// the files are replaced or, if writing a temporary copy of any of them
+    /// fails, none are.
+    pub fn flush(&self) -> std::io::Result<()> {
+        let Some(pending) = self.pending.take() else {
+            return Ok(());
+        };
+        match replace_all(pending.files) {
+            Ok(()) => {
+                if let Some(cache) = &self.cache {
+                    cache.borrow_mut().extend(pending.objects);
+                }
+                Ok(())
+            }
+            Err(e) => {
+                // Some files may have been replaced anyway.
+                if let Some(cache) = &self.cache {
+                    cache.borrow_mut().clear();
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Drops everything that was deferred, and stops deferring.
+    pub fn discard(&self) {
+        self.pending.take();
+    }
+
+    fn cache<T>(&self, obj: &T)
+    where
+        T: Clone + 'static,
+    {
+        if let Some(cache) = &self.cache {
+            cache
+                .borrow_mut()
+                .insert(std::any::TypeId::of::<T>(), Box::new(obj.clone()));
+        }
+    }
+}
+
+fn lookup<T>(cache: &Cache) -> Option<T>
+where
+    T: Clone + 'static,
+{
+    let obj = cache.get(&std::any::TypeId::of::<T>())?;
+    Some(
+        obj.downcast_ref::<T>()
+            .expect("cache should be keyed by type")
+            .clone(),
+    )
+}
+
+/// Writes each of `files` to a temporary file next to it, then renames the
+/// temporary files into place once all of them have been written.
+fn replace_all(
+    files: std::collections::BTreeMap<std::path::PathBuf, String>,
+) -> std::io::Result<()> {
+    let mut temps = Vec::new();
+    let mut res = Ok(());
+    for (path, contents) in files {
+        let mut temp = path.clone().into_os_string();
+        temp.push(".tmp");
+        let temp = std::path::PathBuf::from(temp);
+        res = std::fs::write(&temp, contents);
+        temps.push((temp, path));
+        if res.is_err() {
+            break;
+        }
+    }
+    if res.is_ok() {
+        for (temp, path) in &temps {
+            res = std::fs::rename(temp, path);
+            if res.is_err() {
+                break;
+            }
+        }
+    }
+    if res.is_err() {
+        for (temp, _) in &temps {
+            let _ = std::fs::remove_file(temp);
+        }
+    }
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cached() {
+        let td = tempfile::TempDir::new().unwrap();
+        let fs = Fs::new_cached(td.path());
+        let rl = r#"{"d":"2015-03-30","c":"aaa","a":100,"i":"000001"}"#
+            .parse::<base::Recordlist>()
+            .unwrap();
+
+        // Read nonexistent file, then create it behind the cache's back.
+        assert_eq!(
+            fs.read::<base::Recordlist>().unwrap(),
+            base::Recordlist::new()
+        );
+        std::fs::write(fs.path::<base::Recordlist>(), rl.to_string()).unwrap();
+        assert_eq!(
+            fs.read::<base::Recordlist>().unwrap(),
+            base::Recordlist::new()
+        );
+
+        // Writes go through to disk and to the cache.
+        fs.write(&rl).unwrap();
+        assert_eq!(fs.read::<base::Recordlist>().unwrap(), rl);
+        assert_eq!(Fs::new(td.path()).read::<base::Recordlist>().unwrap(), rl);
+    }
+
+    #[test]
+    fn deferred() {
+        let td = tempfile::TempDir::new().unwrap();
+        let fs = Fs::new_cached(td.path());
+        let rl = r#"{"d":"2015-03-30","c":"aaa","a":100,"i":"000001"}"#
+            .parse::<base::Recordlist>()
+            .unwrap();
+        let accounts = r#"{"2015": 500000}"#.parse::<base::Limitaccounts>().unwrap();
+
+        // Discarded writes never reach the disk or the cache.
+        fs.defer();
+        fs.write(&rl).unwrap();
+        assert_eq!(fs.read::<base::Recordlist>().unwrap(), rl);
+        fs.discard();
+        assert_eq!(
+            fs.read::<base::Recordlist>().unwrap(),
+            base::Recordlist::new()
+        );
+
+        // Flushed writes reach both, without leaving temporary files behind.
+        fs.defer();
+        fs.write(&rl).unwrap();
+        fs.write(&accounts).unwrap();
+        assert!(!fs.path::<base::Recordlist>().exists());
+        fs.flush().unwrap();
+        assert_eq!(fs.read::<base::Recordlist>().unwrap(), rl);
+        assert_eq!(Fs::new(td.path()).read::<base::Recordlist>().unwrap(), rl);
+        assert_eq!(
+            Fs::new(td.path()).read::<base::Limitaccounts>().unwrap(),
+            accounts
+        );
+        assert_eq!(std::fs::read_dir(td.path()).unwrap().count(), 2);
+
+        // Writes go straight to disk again.
+        fs.write(&base::Recordlist::new()).unwrap();
+        assert_eq!(
+            Fs::new(td.path()).read::<base::Recordlist>().unwrap(),
+            base::Recordlist::new()
+        );
+    }
+
+    #[test]
+    fn flush_failure() {
+        let td = tempfile::TempDir::new().unwrap();
+        let fs = Fs::new(td.path());
+        let rl = r#"{"d":"2015-03-30","c":"aaa","a":100,"i":"000001"}"#
+            .parse::<base::Recordlist>()
+            .unwrap();
+        let accounts = r#"{"2015": 500000}"#.parse::<base::Limitaccounts>().unwrap();
+
+        // A directory in the way of one temporary file keeps the other file
+        // from being replaced too.
+        std::fs::create_dir(td.path().join("limits.json.tmp")).unwrap();
+        fs.defer();
+        fs.write(&rl).unwrap();
+        fs.write(&accounts).unwrap();
+        assert!(fs.flush().is_err());
+        assert!(!fs.path::<base::Recordlist>().exists());
+        assert!(!fs.path::<base::Limitaccounts>().exists());
+        assert_eq!(std::fs::read_dir(td.path()).unwrap().count(), 1);
+    }
+}
@@ -1,10 +1,38 @@
+//! Command line interface of the `ledger` binary.
+
+use anyhow::Context;
+
 mod cmds;
+mod fs;
 mod output;
+mod serve;
 mod sharedopts;
+mod tui;
 mod util;
 
 #[cfg(test)]
 mod testing;
 
 pub use cmds::root::Root;
+pub use fs::Fs;
 use output::Output;
+
+/// Runs the command given on the command line against the repository in the
+/// current working directory, and exits with status 1 if it fails.
+pub fn main() {
+    fn try_main() -> anyhow::Result<()> {
+        let root = <Root as clap::Parser>::parse();
+        let cwd = std::env::current_dir().context("failed to resolve current working directory")?;
+        let fs = Fs::new(cwd);
+        let output = root.run(&fs)?;
+        print!("{}", output);
+        Ok(())
+    }
+
+    if let Err(e) = try_main() {
+        eprint!("error");
+        e.chain().for_each(|cause| eprint!(": {}", cause));
+        eprintln!();
+        std::process::exit(1);
+    }
+}
@@ -13,9 +13,17 @@ pub enum Output {
     Str(String),
     TreeForLogt(base::tree::forlogt::Config),
     TreeForSum(base::tree::forsum::Config, base::Interval),
+    TreeForStats(base::tree::forstats::Config, base::Interval),
     TreeForView(base::tree::forview::Config),
+    /// The records in the date of a logged transaction, and warnings about it.
+    Logged(base::tree::forview::Config, Vec<String>),
     Barchart(base::barchart::Config),
     Limitprinter(base::limitprinter::Config),
+    Limitsummary(base::limitprinter::SummaryConfig),
+    /// The calculated limit, and whether it was set.
+    Limitdeduction(base::limitprinter::DeductionConfig, bool),
+    /// The filled in limits, and whether they were written.
+    Limitfill(base::limitprinter::FillConfig, bool),
 }
 
 impl std::fmt::Display for Output {
@@ -42,7 +50,20 @@ impl std::fmt::Display for Output {
                 interval.end,
                 config.to_tree(),
             ),
-            Output::TreeForView(config) => {
+            Output::TreeForStats(config, interval) => {
+                if config.rl.is_empty() {
+                    writeln!(f, "No transactions.")
+                } else {
+                    write!(
+                        f,
+                        "{} to {}\n{}",
+                        interval.start,
+                        interval.end,
+                        config.to_tree(),
+                    )
+                }
+            }
+            Output::TreeForView(config) | Output::Logged(config, _) => {
                 if config.rl.is_empty() {
                     writeln!(f, "No transactions.")
                 } else {
@@ -57,6 +78,23 @@ impl std::fmt::Display for Output {
                 }
             }
             Output::Limitprinter(config) => write!(f, "{}", config.to_limitprinter()),
+            Output::Limitsummary(config) => write!(f, "{}", config.to_limitprinter()),
+            Output::Limitdeduction(config, set) => {
+                write!(f, "{}", config.to_limitprinter())?;
+                if *set {
+                    writeln!(f, "{:0>4} limit set to {}", config.year, config.limit())
+                } else {
+                    writeln!(f, "Use '--confirm' to set the {:0>4} limit.", config.year)
+                }
+            }
+            Output::Limitfill(config, written) => {
+                write!(f, "{}", config.to_limitprinter())?;
+                match (config.filled_count(), written) {
+                    (0, _) => writeln!(f, "No limits to fill in."),
+                    (n, true) => writeln!(f, "{} limits filled in.", n),
+                    (n, false) => writeln!(f, "Use '--confirm' to fill in {} limits.", n),
+                }
+            }
         }
     }
 }
@@ -94,11 +132,26 @@ mod tests {
                     `-- 0 -- 1.11  abc: note
         "),
     )]
+    #[case::tree_for_stats_empty(
+        Output::TreeForStats(
+            base::tree::forstats::Config {
+                charset: Default::default(),
+                bounds: base::Interval::MAX,
+                level: 1,
+                rl: base::Recordlist::new(),
+            },
+            base::Interval::MAX,
+        ),
+        "No transactions.\n",
+    )]
     #[case::barchart_empty(
         Output::Barchart(base::barchart::Config {
             charset: Default::default(),
             bounds: base::Interval::MAX,
             unit: base::Datepart::Year,
+            cal: base::Calendar::default(),
+            projected: base::Interval::EMPTY,
+            net: false,
             term_width: 80,
             rl: base::Recordlist::new(),
         }),
@@ -109,6 +162,9 @@ mod tests {
             charset: Default::default(),
             bounds: base::Interval::MAX,
             unit: base::Datepart::Year,
+            cal: base::Calendar::default(),
+            projected: base::Interval::EMPTY,
+            net: false,
             term_width: 80,
             rl: r#"{"d":"0000-01-01","c":"abc","a":111,"n":"note"}"#.parse().unwrap(),
         }),
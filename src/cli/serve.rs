@@ -0,0 +1,608 @@
+use crate::base;
+use crate::cli;
+
+/// How a query parameter is passed on to a command.
+enum Arg {
+    Positional,
+    Option(&'static str),
+    /// Passes the flag if the parameter is 'true'.
+    Flag(&'static str),
+    /// Passes the flag paired with the parameter's value.
+    Choice(&'static [(&'static str, &'static str)]),
+}
+
+const CATEGORIES_PARAMS: [(&str, Arg); 3] = [
+    ("categories", Arg::Option("--categories")),
+    ("notCategories", Arg::Option("--not-categories")),
+    ("fullmatch", Arg::Flag("--fullmatch")),
+];
+
+const UNITS: &[(&str, &str)] = &[
+    ("day", "-d"),
+    ("week", "-w"),
+    ("month", "-m"),
+    ("quarter", "-q"),
+    ("year", "-y"),
+];
+
+/// Largest request body that is read, in bytes. Requests are small JSON
+/// objects, so anything bigger is refused.
+const MAX_BODY_LEN: usize = 64 * 1024;
+
+/// Serves requests from `server` until it is closed.
+pub fn serve(fs: &cli::Fs, server: &tiny_http::Server) {
+    for request in server.incoming_requests() {
+        handle(fs, request);
+    }
+}
+
+/// Answers a single request. Failures to read the request or send the
+/// response only affect this request, so they are ignored.
+pub fn handle(fs: &cli::Fs, mut request: tiny_http::Request) {
+    let reader = std::io::Read::take(request.as_reader(), MAX_BODY_LEN as u64 + 1);
+    let (status, json) = match std::io::read_to_string(reader) {
+        Ok(body) if body.len() > MAX_BODY_LEN => error(413, "request body is too large"),
+        Ok(body) => respond(fs, request.method().as_str(), request.url(), &body),
+        Err(_) => error(400, "request body is not valid UTF-8"),
+    };
+    let header = tiny_http::Header::from_bytes("Content-Type", "application/json")
+        .expect("header should be valid");
+    let response = tiny_http::Response::from_string(json.to_string())
+        .with_status_code(status)
+        .with_header(header);
+    let _ = request.respond(response);
+}
+
+/// Returns the status code and body of the response to a request.
+pub fn respond(fs: &cli::Fs, method: &str, url: &str, body: &str) -> (u16, serde_json::Value) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let query = match parse_query(query) {
+        Ok(query) => query,
+        Err(e) => return error(400, &e.to_string()),
+    };
+    let res = match (method, path) {
+        ("GET", "/view") => read(fs, "view", &query, &[("interval", Arg::Positional)]),
+        ("GET", "/sum") => read(
+            fs,
+            "sum",
+            &query,
+            &[
+                ("interval", Arg::Positional),
+                ("level", Arg::Option("--level")),
+            ],
+        ),
+        ("GET", "/plot") => read(
+            fs,
+            "plot",
+            &query,
+            &[("interval", Arg::Positional), ("unit", Arg::Choice(UNITS))],
+        ),
+        ("GET", "/lim") => read(
+            fs,
+            "lim",
+            &query,
+            &[
+                ("year", Arg::Positional),
+                ("account", Arg::Option("--account")),
+            ],
+        ),
+        ("POST", "/log") => log(fs, body),
+        ("POST", "/rm") => rm(fs, body),
+        (_, "/view" | "/sum" | "/plot" | "/lim" | "/log" | "/rm") => {
+            return error(405, "method not allowed");
+        }
+        _ => return error(404, "not found"),
+    };
+    match res {
+        Ok(json) => (200, json),
+        Err(e) => error(400, &cli::util::error_string(&e)),
+    }
+}
+
+fn error(status: u16, msg: &str) -> (u16, serde_json::Value) {
+    (status, serde_json::json!({ "error": msg }))
+}
+
+/// Splits a query string into its decoded parameters.
+fn parse_query(query: &str) -> anyhow::Result<Vec<(String, String)>> {
+    query
+        .split('&')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            let (k, v) = s.split_once('=').unwrap_or((s, ""));
+            Ok((percent_decode(k)?, percent_decode(v)?))
+        })
+        .collect()
+}
+
+fn percent_decode(s: &str) -> anyhow::Result<String> {
+    let mut bytes = Vec::new();
+    let mut iter = s.bytes();
+    while let Some(b) = iter.next() {
+        match b {
+            b'+' => bytes.push(b' '),
+            b'%' => {
+                let hex = [iter.next(), iter.next()];
+                let byte = match hex {
+                    [Some(hi), Some(lo)] => std::str::from_utf8(&[hi, lo])
+                        .ok()
+                        .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+                    _ => None,
+                };
+                match byte {
+                    Some(byte) => bytes.push(byte),
+                    None => anyhow::bail!("invalid percent-encoding in query"),
+                }
+            }
+            b => bytes.push(b),
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| anyhow::anyhow!("query is not valid UTF-8"))
+}
+
+/// Runs a command whose arguments are taken from the query, and converts its
+/// output to JSON.
+fn read(
+    fs: &cli::Fs,
+    cmd: &str,
+    query: &[(String, String)],
+    params: &[(&str, Arg)],
+) -> anyhow::Result<serde_json::Value> {
+    let params = params.iter().chain(match cmd {
+        "view" | "sum" | "plot" => CATEGORIES_PARAMS.as_slice(),
+        _ => &[],
+    });
+    let mut options = Vec::new();
+    let mut positionals = Vec::new();
+    let params = params.collect::<Vec<_>>();
+    for (key, value) in query {
+        let Some((_, arg)) = params.iter().find(|(name, _)| name == key) else {
+            anyhow::bail!("unknown parameter '{}'", key)
+        };
+        match arg {
+            Arg::Positional => positionals.push(value.clone()),
+            Arg::Option(flag) => options.push(format!("{}={}", flag, value)),
+            Arg::Flag(flag) => match value.as_str() {
+                "true" => options.push(flag.to_string()),
+                "false" => {}
+                _ => anyhow::bail!("'{}' must be 'true' or 'false'", key),
+            },
+            Arg::Choice(choices) => match choices.iter().find(|(name, _)| name == value) {
+                Some((_, flag)) => options.push(flag.to_string()),
+                None => anyhow::bail!("invalid '{}' '{}'", key, value),
+            },
+        }
+    }
+    let mut args = vec![cmd.to_string()];
+    args.extend(options);
+    args.push("--".to_string());
+    args.extend(positionals);
+    Ok(to_json(run(fs, args)?))
+}
+
+/// Request body of 'POST /log'. Amounts are given the same way as on the
+/// command line.
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+struct LogRequest {
+    category: String,
+    amount: String,
+    date: Option<String>,
+    #[serde(default)]
+    note: String,
+    #[serde(default)]
+    create: bool,
+    #[serde(default)]
+    split: Vec<SplitRequest>,
+}
+
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SplitRequest {
+    category: String,
+    amount: String,
+}
+
+/// Logs a transaction through 'log'. Responds with the records of its date.
+fn log(fs: &cli::Fs, body: &str) -> anyhow::Result<serde_json::Value> {
+    let req = serde_json::from_str::<LogRequest>(body)?;
+    let mut args = vec!["log".to_string(), format!("--note={}", req.note)];
+    if req.create {
+        args.push("--create".to_string());
+    }
+    for split in req.split {
+        args.push(format!("--split={}={}", split.category, split.amount));
+    }
+    args.push("--".to_string());
+    args.extend([req.category, req.amount]);
+    args.extend(req.date);
+    let cli::Output::Logged(config, warnings) = run(fs, args)? else {
+        unreachable!("log should output the logged records")
+    };
+    Ok(serde_json::json!({
+        "records": config.rl.iter().map(record_json).collect::<Vec<_>>(),
+        "warnings": warnings,
+    }))
+}
+
+/// Request body of 'POST /rm'. Either `id` or both `date` and `index` are
+/// required.
+#[derive(serde::Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RmRequest {
+    id: Option<String>,
+    date: Option<String>,
+    index: Option<usize>,
+}
+
+/// Removes a transaction through 'rm'. Responds with the records left in its
+/// date.
+fn rm(fs: &cli::Fs, body: &str) -> anyhow::Result<serde_json::Value> {
+    let req = serde_json::from_str::<RmRequest>(body)?;
+    let mut args = vec!["rm".to_string(), "--confirm".to_string()];
+    if let Some(id) = req.id {
+        args.push(format!("--id={}", id));
+    }
+    args.push("--".to_string());
+    args.extend(req.date);
+    args.extend(req.index.map(|index| index.to_string()));
+    let cli::Output::TreeForView(config) = run(fs, args)? else {
+        unreachable!("rm should output a tree")
+    };
+    let date = config
+        .rl
+        .iter()
+        .next()
+        .expect("removed record should be in its date")
+        .date();
+    let rl = fs.read::<base::Recordlist>()?;
+    let records = rl.slice_spanning_interval(base::Interval {
+        start: date,
+        end: date,
+    });
+    Ok(serde_json::json!({
+        "records": records.iter().map(record_json).collect::<Vec<_>>(),
+    }))
+}
+
+fn run(fs: &cli::Fs, args: Vec<String>) -> anyhow::Result<cli::Output> {
+    let args = std::iter::once("ledger".to_string()).chain(args);
+    match <cli::Root as clap::Parser>::try_parse_from(args) {
+        Ok(root) => root.run(fs),
+        Err(e) => anyhow::bail!("{}", cli::util::clap_error_message(&e)),
+    }
+}
+
+/// Converts the output of 'view', 'sum', 'plot' or 'lim' to the data it
+/// presents. Amounts are in cents.
+fn to_json(output: cli::Output) -> serde_json::Value {
+    use serde_json::json;
+
+    match output {
+        cli::Output::TreeForView(config) => json!({
+            "records": config.rl.iter().map(record_json).collect::<Vec<_>>(),
+        }),
+        cli::Output::TreeForSum(config, interval) => {
+            let (pos, neg) = config.totals();
+            let categories = |agg: &base::Aggregate<&str, base::Cents>| {
+                agg.iter()
+                    .map(|(category, total)| (category.to_string(), json!(total.0)))
+                    .collect::<serde_json::Map<_, _>>()
+            };
+            json!({
+                "start": interval.start.to_string(),
+                "end": interval.end.to_string(),
+                "in": pos.sum().0,
+                "out": neg.sum().0,
+                "net": (pos.sum() + neg.sum()).0,
+                "categories": { "in": categories(&pos), "out": categories(&neg) },
+            })
+        }
+        cli::Output::Barchart(config) => {
+            let periods = config
+                .to_barchart()
+                .periods()
+                .map(|(interval, pos, neg)| {
+                    // Reports whole periods, unless cut short by the bounds.
+                    let start = interval
+                        .start
+                        .first_of(config.unit, config.cal)
+                        .max(config.bounds.start);
+                    let end = interval
+                        .end
+                        .last_of(config.unit, config.cal)
+                        .min(config.bounds.end);
+                    json!({
+                        "start": start.to_string(),
+                        "end": end.to_string(),
+                        "in": pos.0,
+                        "out": neg.0,
+                        "net": (pos + neg).0,
+                    })
+                })
+                .collect::<Vec<_>>();
+            json!({
+                "start": config.bounds.start.to_string(),
+                "end": config.bounds.end.to_string(),
+                "periods": periods,
+            })
+        }
+        cli::Output::Limitprinter(config) => {
+            let limits = config
+                .limits
+                .range(..=config.year)
+                .map(|(year, limit)| (format!("{:0>4}", year), json!(limit.0)))
+                .collect::<serde_json::Map<_, _>>();
+            json!({
+                "year": config.year,
+                "kind": config.kind,
+                "limits": limits,
+                "total": config.limits.inception_to_year(config.year).0,
+                "remaining": config
+                    .kind
+                    .remaining(&config.limits, &config.rl, config.year, config.rrsp_first_60_days)
+                    .0,
+            })
+        }
+        cli::Output::Limitsummary(config) => {
+            let accounts = config
+                .accounts
+                .iter()
+                .map(|(name, account)| {
+                    let remaining = account.kind.remaining(
+                        &account.limits,
+                        &account.filter(&config.rl),
+                        config.year,
+                        config.rrsp_first_60_days,
+                    );
+                    let json = json!({ "kind": account.kind, "remaining": remaining.0 });
+                    (name.clone(), json)
+                })
+                .collect::<serde_json::Map<_, _>>();
+            json!({ "year": config.year, "accounts": accounts })
+        }
+        output => unreachable!("no endpoint should output {:?}", output),
+    }
+}
+
+fn record_json(r: &base::Record) -> serde_json::Value {
+    serde_json::json!({
+        "id": r.id(),
+        "date": r.date().to_string(),
+        "category": r.category().as_str(),
+        "amount": r.amount().0,
+        "note": r.note(),
+        "group": r.group(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use serde_json::json;
+
+    use super::*;
+
+    const CONFIG: &str = r#"{"limAccountType": "tfsa"}"#;
+    const RL: &str = r#"
+        {"d":"2015-03-01","c":"food/groceries","a":-5000,"i":"000001"}
+        {"d":"2015-03-01","c":"income","a":200000,"i":"000002"}
+        {"d":"2015-03-15","c":"food/dining","a":-2500,"n":"lunch","i":"000003"}
+        {"d":"2015-04-01","c":"food/groceries","a":-1000,"i":"000004"}
+    "#;
+
+    fn record(id: &str, date: &str, category: &str, amount: i64, note: &str) -> serde_json::Value {
+        json!({
+            "id": id,
+            "date": date,
+            "category": category,
+            "amount": amount,
+            "note": note,
+            "group": null,
+        })
+    }
+
+    #[rstest]
+    #[case::view(
+        "GET",
+        "/view?interval=2015-03&categories=food&notCategories=din",
+        "",
+        200,
+        json!({ "records": [record("000001", "2015-03-01", "food/groceries", -5000, "")] }),
+    )]
+    #[case::view_fullmatch(
+        "GET",
+        "/view?interval=2015&categories=food%2F*%2Cinc&fullmatch=true",
+        "",
+        200,
+        json!({ "records": [
+            record("000001", "2015-03-01", "food/groceries", -5000, ""),
+            record("000003", "2015-03-15", "food/dining", -2500, "lunch"),
+            record("000004", "2015-04-01", "food/groceries", -1000, ""),
+        ] }),
+    )]
+    #[case::sum(
+        "GET",
+        "/sum?interval=2015-03",
+        "",
+        200,
+        json!({
+            "start": "2015-03-01",
+            "end": "2015-03-31",
+            "in": 200000,
+            "out": -7500,
+            "net": 192500,
+            "categories": { "in": { "income": 200000 }, "out": { "food": -7500 } },
+        }),
+    )]
+    #[case::plot(
+        "GET",
+        "/plot?interval=2015&unit=month&categories=food",
+        "",
+        200,
+        json!({
+            "start": "2015-01-01",
+            "end": "2015-12-31",
+            "periods": [
+                { "start": "2015-03-01", "end": "2015-03-31", "in": 0, "out": -7500, "net": -7500 },
+                { "start": "2015-04-01", "end": "2015-04-30", "in": 0, "out": -1000, "net": -1000 },
+            ],
+        }),
+    )]
+    #[case::lim(
+        "GET",
+        "/lim?year=2015",
+        "",
+        200,
+        json!({
+            "year": 2015,
+            "kind": "tfsa",
+            "limits": { "2015": 1000000 },
+            "total": 1000000,
+            "remaining": 800000,
+        }),
+    )]
+    #[case::bad_interval(
+        "GET",
+        "/view?interval=abc",
+        "",
+        400,
+        json!({
+            "error": "invalid value 'abc' for '[INTERVAL]': 'abc' is not a recognized date name",
+        }),
+    )]
+    #[case::unknown_param("GET", "/sum?foo=1", "", 400, json!({ "error": "unknown parameter 'foo'" }))]
+    #[case::bad_unit("GET", "/plot?unit=decade", "", 400, json!({ "error": "invalid 'unit' 'decade'" }))]
+    #[case::bad_encoding("GET", "/view?interval=%zz", "", 400, json!({ "error": "invalid percent-encoding in query" }))]
+    #[case::not_found("GET", "/foo", "", 404, json!({ "error": "not found" }))]
+    #[case::wrong_method("GET", "/log", "", 405, json!({ "error": "method not allowed" }))]
+    #[case::log_nonexistent_category(
+        "POST",
+        "/log",
+        r#"{"category": "foo", "amount": "1"}"#,
+        400,
+        json!({ "error": "nonexistent category" }),
+    )]
+    #[case::log_bad_body(
+        "POST",
+        "/log",
+        r#"{"category": "income"}"#,
+        400,
+        json!({ "error": "missing field `amount` at line 1 column 22" }),
+    )]
+    #[case::rm_nonexistent("POST", "/rm", r#"{"id": "abc"}"#, 400, json!({ "error": "nonexistent transaction" }))]
+    fn test_respond(
+        #[case] method: &str,
+        #[case] url: &str,
+        #[case] body: &str,
+        #[case] want_status: u16,
+        #[case] want: serde_json::Value,
+    ) {
+        let (fs, _td) = cli::testing::tempfs();
+        let state = cli::testing::StrState::new()
+            .with_config(CONFIG)
+            .with_rl(RL)
+            .with_limits(r#"{"2015": 1000000}"#);
+        state.to_fs(&fs);
+        let (status, got) = respond(&fs, method, url, body);
+        assert_eq!(got, want);
+        assert_eq!(status, want_status);
+        assert_eq!(cli::testing::State::from_fs(&fs), state.to_state());
+    }
+
+    #[test]
+    fn test_log_warnings() {
+        let (fs, _td) = cli::testing::tempfs();
+        cli::testing::StrState::new()
+            .with_config(CONFIG)
+            .with_rl(RL)
+            .with_limits(r#"{"2015": 1000}"#)
+            .to_fs(&fs);
+        let body = r#"{"category": "income", "amount": "+5", "date": "2015-03-15"}"#;
+        let (status, got) = respond(&fs, "POST", "/log", body);
+        assert_eq!(status, 200);
+        assert_eq!(got["warnings"], json!(["TFSA ROOM EXCEEDED BY 1,990.00"]));
+    }
+
+    #[test]
+    fn test_log_and_rm() {
+        let (fs, _td) = cli::testing::tempfs();
+        cli::testing::StrState::new()
+            .with_config(CONFIG)
+            .with_rl(RL)
+            .to_fs(&fs);
+
+        let body = r#"{"category": "food/dining", "amount": "-12.5", "date": "2015-03-15", "note": "x y"}"#;
+        let (status, got) = respond(&fs, "POST", "/log", body);
+        assert_eq!(status, 200);
+        let records = got["records"].as_array().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[1]["amount"], json!(-1250));
+        assert_eq!(records[1]["note"], json!("x y"));
+        assert_eq!(got["warnings"], json!([]));
+
+        let body = r#"{"date": "2015-03-15", "index": 0}"#;
+        let (status, got) = respond(&fs, "POST", "/rm", body);
+        assert_eq!(status, 200);
+        let records = got["records"].as_array().unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["amount"], json!(-1250));
+        assert_eq!(fs.read::<base::Recordlist>().unwrap().len(), 4);
+    }
+
+    /// Sends a request with `head`, the request line, and `body` to a server
+    /// that handles it, and returns the raw response.
+    fn exchange(head: &str, body: &str) -> String {
+        use std::io::Read;
+        use std::io::Write;
+
+        let (fs, _td) = cli::testing::tempfs();
+        cli::testing::StrState::new()
+            .with_config(CONFIG)
+            .with_rl(RL)
+            .to_fs(&fs);
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let addr = server.server_addr().to_ip().unwrap();
+        let request = format!(
+            "{}\r\nHost: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            head,
+            addr,
+            body.len(),
+            body
+        );
+        let client = std::thread::spawn(move || {
+            let mut stream = std::net::TcpStream::connect(addr).unwrap();
+            stream.write_all(request.as_bytes()).unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            response
+        });
+        handle(&fs, server.recv().unwrap());
+        client.join().unwrap()
+    }
+
+    #[test]
+    fn test_serve() {
+        let response = exchange("GET /sum?interval=2015-04 HTTP/1.1", "");
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.contains("Content-Type: application/json\r\n"));
+        let (_, body) = response.split_once("\r\n\r\n").unwrap();
+        let got = serde_json::from_str::<serde_json::Value>(body).unwrap();
+        assert_eq!(got["out"], json!(-1000));
+    }
+
+    #[test]
+    fn test_serve_body_too_large() {
+        let body = format!(
+            r#"{{"category": "income", "amount": "1", "note": "{}"}}"#,
+            "x".repeat(MAX_BODY_LEN)
+        );
+        let response = exchange("POST /log HTTP/1.1", &body);
+        assert!(response.starts_with("HTTP/1.1 413 "), "{}", response);
+        assert!(
+            response.ends_with(r#"{"error":"request body is too large"}"#),
+            "{}",
+            response
+        );
+    }
+}
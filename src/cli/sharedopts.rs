@@ -1,3 +1,7 @@
+use anyhow::Context;
+
+use crate::base;
+
 pub const FULLMATCH_HELP: &str = "Include categories only if they fully match the given patterns";
 pub const FULLMATCH_HELP_LONG: &str =
     "Include categories only if they fully match the given patterns
@@ -7,19 +11,84 @@ Otherwise, '*' is implicitly prepended and appended to all patterns.";
 pub const INTERVAL_HELP: &str = "Interval of interest";
 pub const INTERVAL_HELP_LONG: &str = "Interval of interest
 
-Must be in the format 'A:B'. Each of 'A' or 'B' is either an ISO 8601 date (yyyy-mm-dd) or a relative date (see below). 'A' and 'B' are both optional, defaulting to 0000-01-01 and 9999-12-31 respectively.
+Must be in the format 'A:B'. Each of 'A' or 'B' is either an ISO 8601 date (yyyy-mm-dd), a month or year literal (yyyy-mm or yyyy), a relative date, or a named date (see below). 'A' and 'B' are both optional, defaulting to 0000-01-01 and 9999-12-31 respectively. A month or year literal refers to its first day in 'A' and its last day in 'B'.
 
 A relative date is one of the following ('n' is optional and defaults to 0):
 dn: n days from today
+wn: first day of the nth week from today
+Wn: last day of the nth week from today
 mn: first day of the nth month from today
 Mn: last day of the nth month from today
+qn: first day of the nth quarter from today
+Qn: last day of the nth quarter from today
 yn: first day of the nth year from today
 Yn: last day of the nth year from today
 
+Weeks start on the repository's configured first day of the week, and years start in its configured fiscal year start month. A year literal 'yyyy' refers to the year starting in that month of 'yyyy'. When a fiscal year start other than January is configured, calendar years cannot be referred to as such, but can still be written as intervals of months, such as '2015-01:2015-12'. Contribution limits shown by 'lim' always use calendar years.
+
+A named date is one of the following:
+today: today
+yesterday: the day before today
+mon, tue, ..., sun: the most recent such weekday, which may be today
+last-month: the month before this one
+
 The following shorthands are also available:
 dn = dn:dn
+wn = wn:Wn
 mn = mn:Mn
-yn = yn:Yn";
+qn = qn:Qn
+yn = yn:Yn
+yyyy-mm = yyyy-mm:yyyy-mm
+yyyy = yyyy:yyyy
+last-month = last-month:last-month
+Any other single date 'A' is equivalent to 'A:A'.";
+
+/// Command line interval whose relative dates are resolved only once the
+/// repository's calendar settings are known. Syntax is validated on parse.
+#[derive(Debug, Clone)]
+pub struct IntervalArg(String);
+
+impl IntervalArg {
+    pub fn resolve(&self, cal: base::Calendar) -> anyhow::Result<base::Interval> {
+        base::Interval::from_str_with(&self.0, cal)
+            .with_context(|| format!("invalid interval '{}'", self.0))
+    }
+
+    /// Returns the interval as given on the command line.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::str::FromStr for IntervalArg {
+    type Err = <base::Interval as std::str::FromStr>::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<base::Interval>()?;
+        Ok(Self(s.to_string()))
+    }
+}
+
+/// Command line date whose relative forms are resolved only once the
+/// repository's calendar settings are known. Syntax is validated on parse.
+#[derive(Debug, Clone)]
+pub struct DateArg(String);
+
+impl DateArg {
+    pub fn resolve(&self, cal: base::Calendar) -> anyhow::Result<base::Date> {
+        base::Date::from_str_with(&self.0, cal)
+            .with_context(|| format!("invalid date '{}'", self.0))
+    }
+}
+
+impl std::str::FromStr for DateArg {
+    type Err = <base::Date as std::str::FromStr>::Err;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.parse::<base::Date>()?;
+        Ok(Self(s.to_string()))
+    }
+}
 
 #[derive(clap::Args)]
 pub struct CategoriesOpts {
@@ -29,7 +98,8 @@ pub struct CategoriesOpts {
     /// Wildcard patterns to match categories of interest
     ///
     /// Use commas to separate multiple patterns. A transaction is included if
-    /// its category matches any pattern.
+    /// its category matches any pattern. Patterns that are aliases from the
+    /// 'aliases' config stand for their categories.
     #[arg(
         short,
         long,
@@ -53,3 +123,18 @@ pub struct CategoriesOpts {
     )]
     pub not_categories: Vec<String>,
 }
+
+impl CategoriesOpts {
+    /// Returns the patterns of categories to include and to exclude, with
+    /// aliases expanded and preprocessed according to '--fullmatch'.
+    pub fn patterns(&self, config: &base::Config) -> (Vec<String>, Vec<String>) {
+        let patterns = |patterns: &[String]| {
+            let patterns = patterns
+                .iter()
+                .map(|s| config.expand_alias(s).to_string())
+                .collect::<Vec<_>>();
+            base::preprocess_categories(&patterns, self.fullmatch).into_owned()
+        };
+        (patterns(&self.categories), patterns(&self.not_categories))
+    }
+}
@@ -1,4 +1,3 @@
-use crate::base;
 use crate::cli;
 
 /// A single command invocation.
@@ -27,7 +26,7 @@ impl MutCase<'_> {
     /// 1. Checks if files match `final_state`
     pub fn run(self) {
         let td = tempfile::TempDir::new().unwrap();
-        let fs = base::Fs::new(td.path());
+        let fs = cli::Fs::new(td.path());
         self.initial_state.to_fs(&fs);
 
         for inv in self.invocations {
@@ -40,7 +39,7 @@ impl MutCase<'_> {
         }
 
         let got_final_state = cli::testing::State::from_fs(&fs);
-        let want_final_state = self.final_state.into();
+        let want_final_state = self.final_state;
         assert_eq!(got_final_state, want_final_state);
     }
 }
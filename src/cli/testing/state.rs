@@ -1,10 +1,11 @@
 use crate::base;
+use crate::cli;
 
 /// Returns a filesystem object anchored at a temporary directory. The `Fs` must
 /// not outlive the returned `TempDir`.
-pub fn tempfs() -> (base::Fs, tempfile::TempDir) {
+pub fn tempfs() -> (cli::Fs, tempfile::TempDir) {
     let td = tempfile::TempDir::new().unwrap();
-    let fs = base::Fs::new(td.path());
+    let fs = cli::Fs::new(td.path());
     (fs, td)
 }
 
@@ -14,7 +15,7 @@ pub fn tempfs() -> (base::Fs, tempfile::TempDir) {
 pub struct State {
     config: Option<base::Config>,
     rl: Option<base::Recordlist>,
-    limits: Option<base::Limits>,
+    limits: Option<base::Limitaccounts>,
 }
 
 impl State {
@@ -43,18 +44,18 @@ impl State {
         self
     }
 
-    /// Sets repo's [`base::Limits`].
+    /// Sets repo's [`base::Limitaccounts`].
     pub fn with_limits<T>(mut self, limits: T) -> Self
     where
-        T: TryInto<base::Limits> + std::fmt::Debug,
-        <T as TryInto<base::Limits>>::Error: std::fmt::Debug,
+        T: TryInto<base::Limitaccounts> + std::fmt::Debug,
+        <T as TryInto<base::Limitaccounts>>::Error: std::fmt::Debug,
     {
         self.limits = Some(limits.try_into().unwrap());
         self
     }
 
     /// Deserializes objects from `fs`.
-    pub fn from_fs(fs: &base::Fs) -> Self {
+    pub fn from_fs(fs: &cli::Fs) -> Self {
         macro_rules! read {
             ($t:ty) => {{
                 let p = fs.path::<$t>();
@@ -69,7 +70,7 @@ impl State {
         Self {
             config: read!(base::Config),
             rl: read!(base::Recordlist),
-            limits: read!(base::Limits),
+            limits: read!(base::Limitaccounts),
         }
     }
 }
@@ -101,7 +102,7 @@ impl<'a> StrState<'a> {
         self
     }
 
-    /// Sets repo's [`base::Limits`] file contents.
+    /// Sets repo's [`base::Limitaccounts`] file contents.
     pub fn with_limits(mut self, s: &'a str) -> Self {
         self.limits = Some(s);
         self
@@ -109,10 +110,10 @@ impl<'a> StrState<'a> {
 
     /// Writes string contents verbatim to `fs`. Panics if any field is not a
     /// valid serialization of a real type.
-    pub fn to_fs(&self, fs: &base::Fs) {
-        fn write<T>(fs: &base::Fs, field: Option<&str>)
+    pub fn to_fs(&self, fs: &cli::Fs) {
+        fn write<T>(fs: &cli::Fs, field: Option<&str>)
         where
-            T: std::fmt::Debug + base::fs::Io,
+            T: std::fmt::Debug + base::fs::Io + Clone + 'static,
             <T as std::str::FromStr>::Err: std::error::Error,
         {
             if let Some(s) = field {
@@ -124,7 +125,7 @@ impl<'a> StrState<'a> {
 
         write::<base::Config>(fs, self.config);
         write::<base::Recordlist>(fs, self.rl);
-        write::<base::Limits>(fs, self.limits);
+        write::<base::Limitaccounts>(fs, self.limits);
     }
 
     pub fn to_state(&self) -> State {
@@ -0,0 +1,11 @@
+mod app;
+mod backend;
+
+pub use app::App;
+pub use app::Filter;
+pub use backend::Backend;
+pub use backend::Frame;
+pub use backend::Key;
+#[cfg(test)]
+pub use backend::Scripted;
+pub use backend::Terminal;
@@ -0,0 +1,757 @@
+use anyhow::Context;
+
+use crate::base;
+use crate::cli;
+use crate::cli::tui::Backend;
+use crate::cli::tui::Frame;
+use crate::cli::tui::Key;
+
+/// Appended to the leaves of the transaction tree so that their rows can be
+/// told apart from the rows of dates and splits.
+const LEAF_MARKER: char = '\0';
+
+/// Separates the transaction list from the side panel.
+const PANE_SEPARATOR: &str = " | ";
+
+const HELP: &str = "j/k move  a add  e edit  d delete  / filter  +/- level  q quit";
+
+/// Labels of the fields of the add and edit form.
+const FORM_LABELS: [&str; 4] = ["Date", "Category", "Amount", "Note"];
+
+/// Filter text as typed in the filter fields. Patterns are separated by commas,
+/// as in '--categories' and '--not-categories'.
+pub struct Filter {
+    pub interval: String,
+    pub categories: String,
+    pub not_categories: String,
+    pub fullmatch: bool,
+}
+
+/// Full-screen transaction browser and editor. Changes are written to `fs` as
+/// soon as they are confirmed.
+pub struct App<'a> {
+    config: &'a base::Config,
+    fs: &'a cli::Fs,
+    rl: base::Recordlist,
+    filter: Filter,
+    /// The most recent valid interval in `filter`.
+    interval: base::Interval,
+    /// Records passing the filter.
+    view: base::Recordlist,
+    /// Index of the selected record in `view`.
+    selected: usize,
+    /// Index of the first row of the transaction list that is on screen.
+    scroll: usize,
+    /// Number of rows of the transaction list that were last on screen.
+    page: usize,
+    /// Category level to aggregate on in the side panel.
+    level: usize,
+    mode: Mode,
+    status: String,
+    counts: Counts,
+}
+
+enum Mode {
+    Browse,
+    Filter(Field),
+    Form(Form),
+    Confirm(Change),
+}
+
+/// Filter fields, in the order that Tab cycles through them.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Field {
+    Interval,
+    Categories,
+    NotCategories,
+}
+
+struct Form {
+    /// ID of the transaction being edited, or `None` if adding one.
+    id: Option<String>,
+    /// Field values, in the order of `FORM_LABELS`.
+    values: [String; 4],
+    /// Index of the focused field.
+    focus: usize,
+}
+
+enum Change {
+    Add(base::Record),
+    /// Replaces the record with the given ID.
+    Edit(String, base::Record),
+    /// Removes the record with the given ID.
+    Remove(String),
+}
+
+#[derive(Default)]
+struct Counts {
+    added: usize,
+    edited: usize,
+    removed: usize,
+}
+
+impl<'a> App<'a> {
+    pub fn new(
+        rl: base::Recordlist,
+        config: &'a base::Config,
+        fs: &'a cli::Fs,
+        filter: Filter,
+    ) -> Self {
+        let mut app = Self {
+            config,
+            fs,
+            rl,
+            filter,
+            interval: base::Interval::MAX,
+            view: base::Recordlist::new(),
+            selected: 0,
+            scroll: 0,
+            page: 0,
+            level: 1,
+            mode: Mode::Browse,
+            status: String::new(),
+            counts: Counts::default(),
+        };
+        app.refilter();
+        app
+    }
+
+    /// Draws frames and handles keys until the user quits or `backend` runs out
+    /// of keys.
+    pub fn run(&mut self, backend: &mut impl Backend) -> anyhow::Result<()> {
+        loop {
+            let (width, height) = backend.size().context("failed to get terminal size")?;
+            let frame = self.frame(width, height);
+            backend.draw(&frame).context("failed to draw")?;
+            let Some(key) = backend.read_key().context("failed to read key")? else {
+                return Ok(());
+            };
+            if !self.handle(key)? {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Describes the changes written so far.
+    pub fn summary(&self) -> String {
+        let Counts {
+            added,
+            edited,
+            removed,
+        } = self.counts;
+        if added + edited + removed == 0 {
+            "No changes.".to_string()
+        } else {
+            format!("{} added, {} edited, {} removed.", added, edited, removed)
+        }
+    }
+
+    /// Returns false if the app should quit.
+    fn handle(&mut self, key: Key) -> anyhow::Result<bool> {
+        if key == Key::Interrupt {
+            return Ok(false);
+        }
+        self.status.clear();
+        match std::mem::replace(&mut self.mode, Mode::Browse) {
+            Mode::Browse => return Ok(self.handle_browse(key)),
+            Mode::Filter(field) => self.handle_filter(field, key),
+            Mode::Form(form) => self.handle_form(form, key),
+            Mode::Confirm(change) => {
+                if key == Key::Char('y') {
+                    self.apply(change)?;
+                } else {
+                    self.status = "Cancelled.".to_string();
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    fn handle_browse(&mut self, key: Key) -> bool {
+        let last = self.view.len().saturating_sub(1);
+        match key {
+            Key::Char('q') | Key::Esc => return false,
+            Key::Char('j') | Key::Down => self.selected = (self.selected + 1).min(last),
+            Key::Char('k') | Key::Up => self.selected = self.selected.saturating_sub(1),
+            Key::PageDown => self.selected = (self.selected + self.page.max(1)).min(last),
+            Key::PageUp => self.selected = self.selected.saturating_sub(self.page.max(1)),
+            Key::Char('/') | Key::Tab => self.mode = Mode::Filter(Field::Interval),
+            Key::Char('+') => self.level += 1,
+            Key::Char('-') => self.level = self.level.saturating_sub(1),
+            Key::Char('a') => {
+                self.mode = Mode::Form(Form {
+                    id: None,
+                    values: [
+                        base::Date::today().to_string(),
+                        String::new(),
+                        String::new(),
+                        String::new(),
+                    ],
+                    focus: 1,
+                })
+            }
+            Key::Char('e') => {
+                if let Some(r) = self.view.iter().nth(self.selected) {
+                    // Signed, so that 'unsignedIsNegative' does not flip it.
+                    let cents = r.amount().0;
+                    let amount = format!(
+                        "{}{}.{:02}",
+                        if cents < 0 { '-' } else { '+' },
+                        cents.abs() / 100,
+                        cents.abs() % 100,
+                    );
+                    self.mode = Mode::Form(Form {
+                        id: r.id().map(str::to_string),
+                        values: [
+                            r.date().to_string(),
+                            r.category().to_string(),
+                            amount,
+                            r.note().to_string(),
+                        ],
+                        focus: 0,
+                    })
+                }
+            }
+            Key::Char('d') => {
+                if let Some(id) = self.view.iter().nth(self.selected).and_then(|r| r.id()) {
+                    self.mode = Mode::Confirm(Change::Remove(id.to_string()))
+                }
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn handle_filter(&mut self, field: Field, key: Key) {
+        let text = match field {
+            Field::Interval => &mut self.filter.interval,
+            Field::Categories => &mut self.filter.categories,
+            Field::NotCategories => &mut self.filter.not_categories,
+        };
+        match key {
+            Key::Char(c) => text.push(c),
+            Key::Backspace => {
+                text.pop();
+            }
+            Key::Tab => {
+                self.mode = match field {
+                    Field::Interval => Mode::Filter(Field::Categories),
+                    Field::Categories => Mode::Filter(Field::NotCategories),
+                    Field::NotCategories => Mode::Browse,
+                };
+                return;
+            }
+            Key::Enter | Key::Esc => return,
+            _ => {}
+        }
+        self.mode = Mode::Filter(field);
+        self.refilter();
+    }
+
+    fn handle_form(&mut self, mut form: Form, key: Key) {
+        let last = FORM_LABELS.len() - 1;
+        match key {
+            Key::Esc => {
+                self.status = "Cancelled.".to_string();
+                return;
+            }
+            Key::Char(c) => form.values[form.focus].push(c),
+            Key::Backspace => {
+                form.values[form.focus].pop();
+            }
+            Key::Up => form.focus = form.focus.saturating_sub(1),
+            Key::Down => form.focus = (form.focus + 1).min(last),
+            Key::Tab => form.focus = (form.focus + 1) % FORM_LABELS.len(),
+            Key::Enter if form.focus < last => form.focus += 1,
+            Key::Enter => match self.parse_form(&form) {
+                Ok(r) => {
+                    self.mode = Mode::Confirm(match form.id {
+                        Some(id) => Change::Edit(id, r),
+                        None => Change::Add(r),
+                    });
+                    return;
+                }
+                Err(e) => self.status = cli::util::error_string(&e),
+            },
+            _ => {}
+        }
+        self.mode = Mode::Form(form);
+    }
+
+    /// Validates the form's fields the same way that 'log' validates its
+    /// arguments.
+    fn parse_form(&self, form: &Form) -> anyhow::Result<base::Record> {
+        let [date, category, amount, note] = &form.values;
+        let date = base::Date::from_str_with(date, cli::util::calendar_from_config(self.config))
+            .with_context(|| format!("invalid date '{}'", date))?;
+        let category = category
+            .parse::<base::Category>()
+            .with_context(|| format!("invalid category '{}'", category))?;
+        let category = self.config.expand_category(&category);
+        let amount = amount
+            .parse::<cli::cmds::log::CentsArg>()
+            .with_context(|| format!("invalid amount '{}'", amount))?
+            .to_cents(self.config.unsigned_is_negative);
+        let mut r = base::Record::new(date, category, amount, note.clone());
+        if let Some(id) = &form.id {
+            let (date, iid0) = self.rl.find(id).context("nonexistent transaction")?;
+            let old = self.rl.get(date, iid0).expect("found record should exist");
+            if let Some(group) = old.group() {
+                r = r.with_group(group);
+            }
+            r.set_id(id.clone());
+        }
+        Ok(r)
+    }
+
+    fn apply(&mut self, change: Change) -> anyhow::Result<()> {
+        let remove = |rl: &mut base::Recordlist, id: &str| {
+            if let Some((date, iid0)) = rl.find(id) {
+                rl.remove(date, iid0);
+            }
+        };
+        match change {
+            Change::Add(r) => {
+                self.rl.insert(r);
+                self.counts.added += 1;
+                self.status = "Added.".to_string();
+            }
+            Change::Edit(id, r) => {
+                remove(&mut self.rl, &id);
+                self.rl.insert(r);
+                self.counts.edited += 1;
+                self.status = "Saved.".to_string();
+            }
+            Change::Remove(id) => {
+                remove(&mut self.rl, &id);
+                self.counts.removed += 1;
+                self.status = "Removed.".to_string();
+            }
+        }
+        self.fs.write(&self.rl).with_context(|| {
+            format!(
+                "failed to write '{}'",
+                self.fs.path::<base::Recordlist>().display()
+            )
+        })?;
+        self.refilter();
+        Ok(())
+    }
+
+    /// Recomputes `view` from `filter`. Keeps the previous view if the interval
+    /// is invalid.
+    fn refilter(&mut self) {
+        let cal = cli::util::calendar_from_config(self.config);
+        match base::Interval::from_str_with(&self.filter.interval, cal) {
+            Ok(interval) => self.interval = interval,
+            Err(_) => {
+                self.status = format!("invalid interval '{}'", self.filter.interval);
+                return;
+            }
+        }
+        let patterns = |s: &str| {
+            let patterns = s
+                .split(',')
+                .map(|s| self.config.expand_alias(s).to_string())
+                .collect::<Vec<_>>();
+            base::preprocess_categories(&patterns, self.filter.fullmatch).into_owned()
+        };
+        self.view = base::filter_rl(
+            &self.rl,
+            self.interval,
+            &patterns(&self.filter.categories),
+            &patterns(&self.filter.not_categories),
+        );
+        self.selected = self.selected.min(self.view.len().saturating_sub(1));
+    }
+
+    fn charset(&self) -> base::Charset {
+        base::Charset {
+            color: false,
+            ..cli::util::charset_from_config(self.config)
+        }
+    }
+
+    /// Returns the rows of the transaction list, and the row of each record in
+    /// `view`.
+    fn list_rows(&self) -> (Vec<String>, Vec<usize>) {
+        if self.view.is_empty() {
+            return (vec!["No transactions.".to_string()], Vec::new());
+        }
+        let lspp = |_: &base::tree::forview::Config,
+                    _: &base::Record,
+                    _: usize,
+                    mut leaf_string: String|
+         -> String {
+            leaf_string.push(LEAF_MARKER);
+            leaf_string
+        };
+        let tr_config = base::tree::forview::Config {
+            charset: self.charset(),
+            first_iid: self.config.first_index_in_date,
+            rl: self.view.iter().collect(),
+            leaf_string_postprocessor: Some(Box::new(lspp)),
+        };
+        let mut leaf_rows = Vec::new();
+        let rows = tr_config
+            .to_tree()
+            .to_string()
+            .lines()
+            .enumerate()
+            .map(|(i, line)| match line.strip_suffix(LEAF_MARKER) {
+                Some(line) => {
+                    leaf_rows.push(i);
+                    line.to_string()
+                }
+                None => line.to_string(),
+            })
+            .collect();
+        (rows, leaf_rows)
+    }
+
+    /// Returns the rows of the side panel, which sums the filtered records.
+    fn panel_rows(&self) -> Vec<String> {
+        let tr_config = base::tree::forsum::Config {
+            charset: self.charset(),
+            level: self.level,
+            rl: self.view.iter().collect(),
+        };
+        std::iter::once(format!("{} to {}", self.interval.start, self.interval.end))
+            .chain(tr_config.to_tree().to_string().lines().map(str::to_string))
+            .collect()
+    }
+
+    fn filter_bar(&self) -> String {
+        let focus = match self.mode {
+            Mode::Filter(field) => Some(field),
+            _ => None,
+        };
+        let field = |label: &str, field: Field, text: &str| {
+            let cursor = if focus == Some(field) { "_" } else { "" };
+            format!("{} [{}{}]", label, text, cursor)
+        };
+        [
+            field("Interval", Field::Interval, &self.filter.interval),
+            field("Categories", Field::Categories, &self.filter.categories),
+            field("Exclude", Field::NotCategories, &self.filter.not_categories),
+        ]
+        .join("  ")
+    }
+
+    fn status_line(&self) -> String {
+        if let Mode::Confirm(change) = &self.mode {
+            let describe =
+                |r: &base::Record| format!("{} {} {}", r.date(), r.amount(), r.category());
+            return match change {
+                Change::Add(r) if !self.rl.iter().any(|r2| r2.category() == r.category()) => {
+                    format!("Add {} in a new category? (y/n)", describe(r))
+                }
+                Change::Add(r) => format!("Add {}? (y/n)", describe(r)),
+                Change::Edit(_, r) => format!("Save {}? (y/n)", describe(r)),
+                Change::Remove(id) => match self.rl.find(id) {
+                    Some((date, iid0)) => format!(
+                        "Remove {}? (y/n)",
+                        describe(self.rl.get(date, iid0).expect("found record should exist"))
+                    ),
+                    None => "Remove? (y/n)".to_string(),
+                },
+            };
+        }
+        match self.status.is_empty() {
+            true => HELP.to_string(),
+            false => self.status.clone(),
+        }
+    }
+
+    /// Lays out the screen, scrolling the transaction list so that the selected
+    /// record is visible.
+    fn frame(&mut self, width: usize, height: usize) -> Frame {
+        let body_height = height.saturating_sub(2);
+        let mut body = vec![String::new(); body_height];
+
+        let form_rows = match &self.mode {
+            Mode::Form(form) => {
+                let title = match form.id {
+                    Some(_) => "Edit transaction",
+                    None => "Add transaction",
+                };
+                std::iter::once(title.to_string())
+                    .chain(FORM_LABELS.iter().zip(&form.values).enumerate().map(
+                        |(i, (label, value))| match i == form.focus {
+                            true => format!("> {}: {}_", label, value),
+                            false => format!("  {}: {}", label, value),
+                        },
+                    ))
+                    .collect()
+            }
+            _ => Vec::new(),
+        };
+        let list_height = body_height.saturating_sub(form_rows.len());
+
+        let panel = self.panel_rows();
+        let panel_width = panel
+            .iter()
+            .map(|s| s.chars().count())
+            .max()
+            .unwrap_or_default()
+            .min(width / 2);
+        let list_width = width.saturating_sub(panel_width + PANE_SEPARATOR.len());
+
+        let (list, leaf_rows) = self.list_rows();
+        let selected_row = leaf_rows.get(self.selected).copied();
+        if let Some(row) = selected_row {
+            if row < self.scroll {
+                self.scroll = row;
+            } else if row >= self.scroll + list_height {
+                self.scroll = row + 1 - list_height;
+            }
+        }
+        self.scroll = self.scroll.min(list.len().saturating_sub(list_height));
+        self.page = list_height;
+
+        for (i, line) in body.iter_mut().enumerate() {
+            let left = match i < list_height {
+                true => list.get(self.scroll + i).map_or("", String::as_str),
+                false => form_rows[i - list_height].as_str(),
+            };
+            let right = panel.get(i).map_or("", String::as_str);
+            *line = format!(
+                "{:<list_width$}{}{}",
+                truncate(left, list_width),
+                PANE_SEPARATOR,
+                truncate(right, panel_width),
+            );
+        }
+
+        let highlighted = match self.mode {
+            Mode::Browse | Mode::Confirm(_) => selected_row
+                .filter(|row| (self.scroll..self.scroll + list_height).contains(row))
+                .map(|row| row - self.scroll + 1),
+            _ => None,
+        };
+        let lines = std::iter::once(self.filter_bar())
+            .chain(body)
+            .chain(std::iter::once(self.status_line()))
+            .take(height)
+            .map(|line| truncate(&line, width).to_string())
+            .collect();
+        Frame { lines, highlighted }
+    }
+}
+
+/// Returns at most the first `width` characters of `s`.
+fn truncate(s: &str, width: usize) -> &str {
+    match s.char_indices().nth(width) {
+        Some((i, _)) => &s[..i],
+        None => s,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+    use crate::cli::tui::Scripted;
+
+    const RL: &str = r#"
+        {"d":"2015-03-01","c":"food/groceries","a":-5000,"i":"000001"}
+        {"d":"2015-03-01","c":"income","a":200000,"i":"000002"}
+        {"d":"2015-03-15","c":"food/dining","a":-2500,"n":"lunch","i":"000003"}
+    "#;
+
+    /// Runs the app on `rl` with the given keys, and returns the frames drawn
+    /// and the resulting record list.
+    fn run(rl: &str, keys: Vec<Key>) -> (Vec<Frame>, String, base::Recordlist) {
+        run_with_config(base::Config::default(), rl, keys)
+    }
+
+    fn run_with_config(
+        config: base::Config,
+        rl: &str,
+        keys: Vec<Key>,
+    ) -> (Vec<Frame>, String, base::Recordlist) {
+        let (fs, _td) = cli::testing::tempfs();
+        let filter = Filter {
+            interval: "2015-03".to_string(),
+            categories: "*".to_string(),
+            not_categories: String::new(),
+            fullmatch: false,
+        };
+        let mut app = App::new(rl.parse().unwrap(), &config, &fs, filter);
+        let mut backend = Scripted::new(80, 12, keys);
+        app.run(&mut backend).unwrap();
+        (backend.frames, app.summary(), fs.read().unwrap())
+    }
+
+    #[test]
+    fn test_browse() {
+        let (frames, summary, _) = run(RL, vec![Key::Down, Key::Char('+')]);
+        assert_eq!(
+            frames[0].to_string(),
+            indoc!(
+                "
+                Interval [2015-03]  Categories [*]  Exclude []
+                2015                                                  | 2015-03-01 to 2015-03-31
+                `-- Mar                                               | In
+                    |-- 1st                                           | `-- income -- 2,000.00
+                    |   |-- 0 ---- (50.00) food/groceries             | Out
+                    |   `-- 1 -- 2,000.00  income                     | `-- food ------ (75.00)
+                    `-- 15th                                          | Net
+                        `-- 0 ---- (25.00) food/dining: lunch         | |-- In ------ 2,000.00
+                                                                      | |-- Out ------- (75.00)
+                                                                      | `-- Total --- 1,925.00
+                                                                      |
+                j/k move  a add  e edit  d delete  / filter  +/- level  q quit
+                "
+            )
+        );
+        assert_eq!(frames[0].highlighted, Some(4));
+        assert_eq!(frames[1].highlighted, Some(5));
+        assert!(frames[2].to_string().contains("food/groceries"));
+        assert_eq!(summary, "No changes.");
+    }
+
+    #[test]
+    fn test_filter() {
+        let keys = [Key::Tab, Key::Tab, Key::Backspace]
+            .into_iter()
+            .chain(Scripted::typed("food"))
+            .chain([Key::Tab])
+            .chain(Scripted::typed("din"))
+            .collect();
+        let (frames, _, _) = run(RL, keys);
+        let last = frames.last().unwrap().to_string();
+        assert!(last.starts_with("Interval [2015-03]  Categories [food]  Exclude [din_]\n"));
+        assert!(last.contains("(50.00) food/groceries"));
+        assert!(!last.contains("income"));
+        assert!(!last.contains("food/dining"));
+    }
+
+    #[test]
+    fn test_invalid_interval() {
+        let keys = vec![Key::Tab, Key::Backspace];
+        let (frames, _, _) = run(RL, keys);
+        let last = frames.last().unwrap().to_string();
+        assert!(last.ends_with("invalid interval '2015-0'\n"));
+        assert!(last.contains("income"));
+    }
+
+    #[test]
+    fn test_add() {
+        let keys = [Key::Char('a')]
+            .into_iter()
+            .chain(Scripted::typed("food/snacks"))
+            .chain([Key::Enter])
+            .chain(Scripted::typed("-3"))
+            .chain([Key::Enter, Key::Enter])
+            .chain([Key::Char('y')])
+            .collect();
+        let (frames, summary, rl) = run(RL, keys);
+        assert!(
+            frames[frames.len() - 2]
+                .to_string()
+                .ends_with("Add 2015-03-30 (3.00) food/snacks in a new category? (y/n)\n")
+        );
+        assert!(frames.last().unwrap().to_string().ends_with("Added.\n"));
+        assert_eq!(summary, "1 added, 0 edited, 0 removed.");
+        let r = rl.iter().last().unwrap();
+        assert_eq!(r.category().as_str(), "food/snacks");
+        assert_eq!(r.amount(), base::Cents(-300));
+    }
+
+    #[test]
+    fn test_add_alias() {
+        let config = r#"{"aliases": {"g": "food/groceries"}}"#.parse().unwrap();
+        let keys = [Key::Char('a')]
+            .into_iter()
+            .chain(Scripted::typed("g"))
+            .chain([Key::Enter])
+            .chain(Scripted::typed("-3"))
+            .chain([Key::Enter, Key::Enter])
+            .chain([Key::Char('y')])
+            .collect();
+        let (frames, summary, rl) = run_with_config(config, RL, keys);
+        assert!(
+            frames[frames.len() - 2]
+                .to_string()
+                .ends_with("Add 2015-03-30 (3.00) food/groceries? (y/n)\n")
+        );
+        assert!(frames.last().unwrap().to_string().ends_with("Added.\n"));
+        assert_eq!(summary, "1 added, 0 edited, 0 removed.");
+        let r = rl.iter().last().unwrap();
+        assert_eq!(r.category().as_str(), "food/groceries");
+    }
+
+    #[test]
+    fn test_add_invalid_amount() {
+        let keys = [Key::Char('a')]
+            .into_iter()
+            .chain(Scripted::typed("income"))
+            .chain([Key::Enter])
+            .chain(Scripted::typed("abc"))
+            .chain([Key::Enter, Key::Enter])
+            .collect();
+        let (frames, summary, _) = run(RL, keys);
+        let last = frames.last().unwrap().to_string();
+        assert!(last.contains("> Note: _"));
+        assert!(last.starts_with("Interval"));
+        assert!(
+            last.lines()
+                .last()
+                .unwrap()
+                .starts_with("invalid amount 'abc'")
+        );
+        assert_eq!(summary, "No changes.");
+    }
+
+    #[test]
+    fn test_edit() {
+        let keys = [Key::Down, Key::Down, Key::Char('e'), Key::Down, Key::Down]
+            .into_iter()
+            .chain(std::iter::repeat_n(Key::Backspace, 6))
+            .chain(Scripted::typed("-30"))
+            .chain([Key::Enter, Key::Enter, Key::Char('y')])
+            .collect();
+        let (_, summary, rl) = run(RL, keys);
+        assert_eq!(summary, "0 added, 1 edited, 0 removed.");
+        assert_eq!(
+            rl,
+            r#"
+                {"d":"2015-03-01","c":"food/groceries","a":-5000,"i":"000001"}
+                {"d":"2015-03-01","c":"income","a":200000,"i":"000002"}
+                {"d":"2015-03-15","c":"food/dining","a":-3000,"n":"lunch","i":"000003"}
+            "#
+            .parse()
+            .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_remove() {
+        let keys = vec![
+            Key::Char('d'),
+            Key::Char('n'),
+            Key::Down,
+            Key::Char('d'),
+            Key::Char('y'),
+        ];
+        let (frames, summary, rl) = run(RL, keys);
+        assert!(
+            frames[1]
+                .to_string()
+                .ends_with("Remove 2015-03-01 (50.00) food/groceries? (y/n)\n")
+        );
+        assert!(frames[2].to_string().ends_with("Cancelled.\n"));
+        assert_eq!(summary, "0 added, 0 edited, 1 removed.");
+        assert_eq!(
+            rl,
+            r#"
+                {"d":"2015-03-01","c":"food/groceries","a":-5000,"i":"000001"}
+                {"d":"2015-03-15","c":"food/dining","a":-2500,"n":"lunch","i":"000003"}
+            "#
+            .parse()
+            .unwrap()
+        );
+    }
+}
@@ -0,0 +1,184 @@
+/// Key presses understood by the terminal UI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Enter,
+    Esc,
+    Backspace,
+    Tab,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    /// Ctrl-C, which quits from anywhere.
+    Interrupt,
+}
+
+/// Contents of the whole screen.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Frame {
+    /// One string per row. Rows do not exceed the screen width.
+    pub lines: Vec<String>,
+    /// Row to draw in reverse video, if any.
+    pub highlighted: Option<usize>,
+}
+
+impl std::fmt::Display for Frame {
+    /// Writes a terminating newline after every row, and strips trailing
+    /// whitespace.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for line in &self.lines {
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+/// Where the terminal UI draws frames and reads keys from.
+pub trait Backend {
+    /// Returns the screen's width and height, in characters.
+    fn size(&self) -> std::io::Result<(usize, usize)>;
+
+    /// Replaces the contents of the screen.
+    fn draw(&mut self, frame: &Frame) -> std::io::Result<()>;
+
+    /// Waits for the next key press. Returns `None` if there will be no more.
+    fn read_key(&mut self) -> std::io::Result<Option<Key>>;
+}
+
+/// Backend for an interactive terminal. The terminal is put in raw mode and
+/// switched to its alternate screen until this is dropped.
+pub struct Terminal {
+    out: std::io::Stdout,
+}
+
+impl Terminal {
+    pub fn new() -> std::io::Result<Self> {
+        let mut out = std::io::stdout();
+        crossterm::terminal::enable_raw_mode()?;
+        crossterm::execute!(
+            out,
+            crossterm::terminal::EnterAlternateScreen,
+            crossterm::cursor::Hide,
+        )?;
+        Ok(Self { out })
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        // Errors are ignored since there is nothing left to do about them.
+        let _ = crossterm::execute!(
+            self.out,
+            crossterm::cursor::Show,
+            crossterm::terminal::LeaveAlternateScreen,
+        );
+        let _ = crossterm::terminal::disable_raw_mode();
+    }
+}
+
+impl Backend for Terminal {
+    fn size(&self) -> std::io::Result<(usize, usize)> {
+        let (width, height) = crossterm::terminal::size()?;
+        Ok((width as usize, height as usize))
+    }
+
+    fn draw(&mut self, frame: &Frame) -> std::io::Result<()> {
+        use crossterm::style::Attribute;
+        use std::io::Write;
+
+        crossterm::queue!(
+            self.out,
+            crossterm::terminal::Clear(crossterm::terminal::ClearType::All)
+        )?;
+        for (row, line) in frame.lines.iter().enumerate() {
+            crossterm::queue!(self.out, crossterm::cursor::MoveTo(0, row as u16))?;
+            if frame.highlighted == Some(row) {
+                crossterm::queue!(
+                    self.out,
+                    crossterm::style::SetAttribute(Attribute::Reverse),
+                    crossterm::style::Print(line),
+                    crossterm::style::SetAttribute(Attribute::Reset),
+                )?;
+            } else {
+                crossterm::queue!(self.out, crossterm::style::Print(line))?;
+            }
+        }
+        self.out.flush()
+    }
+
+    fn read_key(&mut self) -> std::io::Result<Option<Key>> {
+        use crossterm::event::Event;
+        use crossterm::event::KeyCode;
+        use crossterm::event::KeyEventKind;
+        use crossterm::event::KeyModifiers;
+
+        loop {
+            let Event::Key(event) = crossterm::event::read()? else {
+                continue;
+            };
+            if event.kind == KeyEventKind::Release {
+                continue;
+            }
+            let key = match event.code {
+                KeyCode::Char('c') if event.modifiers.contains(KeyModifiers::CONTROL) => {
+                    Key::Interrupt
+                }
+                KeyCode::Char(c) => Key::Char(c),
+                KeyCode::Enter => Key::Enter,
+                KeyCode::Esc => Key::Esc,
+                KeyCode::Backspace => Key::Backspace,
+                KeyCode::Tab => Key::Tab,
+                KeyCode::Up => Key::Up,
+                KeyCode::Down => Key::Down,
+                KeyCode::PageUp => Key::PageUp,
+                KeyCode::PageDown => Key::PageDown,
+                _ => continue,
+            };
+            return Ok(Some(key));
+        }
+    }
+}
+
+/// Backend which replays a fixed sequence of keys and records every frame
+/// drawn.
+#[cfg(test)]
+pub struct Scripted {
+    pub width: usize,
+    pub height: usize,
+    pub keys: std::collections::VecDeque<Key>,
+    pub frames: Vec<Frame>,
+}
+
+#[cfg(test)]
+impl Scripted {
+    pub fn new(width: usize, height: usize, keys: impl IntoIterator<Item = Key>) -> Self {
+        Self {
+            width,
+            height,
+            keys: keys.into_iter().collect(),
+            frames: Vec::new(),
+        }
+    }
+
+    /// Returns script keys for typing `s`.
+    pub fn typed(s: &str) -> impl Iterator<Item = Key> + '_ {
+        s.chars().map(Key::Char)
+    }
+}
+
+#[cfg(test)]
+impl Backend for Scripted {
+    fn size(&self) -> std::io::Result<(usize, usize)> {
+        Ok((self.width, self.height))
+    }
+
+    fn draw(&mut self, frame: &Frame) -> std::io::Result<()> {
+        self.frames.push(frame.clone());
+        Ok(())
+    }
+
+    fn read_key(&mut self) -> std::io::Result<Option<Key>> {
+        Ok(self.keys.pop_front())
+    }
+}
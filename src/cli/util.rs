@@ -11,91 +11,89 @@ pub fn charset_from_config(config: &base::Config) -> base::Charset {
     charset
 }
 
-/// If `fullmatch` is false, ensures all categories starts with and ends with
-/// `*`, except for empty categories which are left alone. If `fullmatch` is
-/// true, does not modify categories.
-pub fn preprocess_categories<'a>(
-    categories: &'a [String],
-    fullmatch: bool,
-) -> std::borrow::Cow<'a, [String]> {
-    if fullmatch {
-        return categories.into();
+pub fn calendar_from_config(config: &base::Config) -> base::Calendar {
+    base::Calendar {
+        first_weekday: config.first_day_of_week,
+        first_month: config.fiscal_year_start,
     }
-    categories
-        .iter()
-        .map(|s| {
-            let mut s2 = s.clone();
-            if s2.is_empty() {
-                return s2;
-            }
-            if !s2.starts_with('*') {
-                s2.insert(0, '*');
+}
+
+/// Splits a line into words the way a POSIX shell would, minus expansions.
+/// Words are separated by whitespace. Single quotes preserve everything up to
+/// the next single quote, and double quotes preserve everything but
+/// backslash-escaped double quotes and backslashes. Elsewhere, a backslash
+/// preserves the next character.
+pub fn split_words(line: &str) -> anyhow::Result<Vec<String>> {
+    let mut words = Vec::new();
+    // The word being built, if any. Distinct from an empty word, which quotes
+    // can produce.
+    let mut word: Option<String> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => words.extend(word.take()),
+            '\\' => match chars.next() {
+                Some(c) => word.get_or_insert_default().push(c),
+                None => anyhow::bail!("trailing backslash"),
+            },
+            '\'' => {
+                let word = word.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => word.push(c),
+                        None => anyhow::bail!("unterminated quote"),
+                    }
+                }
             }
-            if !s2.ends_with('*') {
-                s2.push('*');
+            '"' => {
+                let word = word.get_or_insert_default();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\')) => word.push(c),
+                            Some(c) => {
+                                word.push('\\');
+                                word.push(c);
+                            }
+                            None => anyhow::bail!("unterminated quote"),
+                        },
+                        Some(c) => word.push(c),
+                        None => anyhow::bail!("unterminated quote"),
+                    }
+                }
             }
-            s2
-        })
+            c => word.get_or_insert_default().push(c),
+        }
+    }
+    words.extend(word);
+    Ok(words)
+}
+
+/// Formats an error and its causes the same way as when a command fails, minus
+/// the leading 'error: '.
+pub fn error_string(e: &anyhow::Error) -> String {
+    e.chain()
+        .map(ToString::to_string)
         .collect::<Vec<_>>()
-        .into()
+        .join(": ")
 }
 
-/// Returns a new record list such that each record:
-/// - Is in 'interval'
-/// - Matches any wildcard pattern in 'categories'
-/// - Does not match any wildcard pattern in 'not_categories'
-pub fn filter_rl<T, U>(
-    rl: &base::Recordlist,
-    interval: base::Interval,
-    categories: &[T],
-    not_categories: &[U],
-) -> base::Recordlist
-where
-    T: AsRef<str>,
-    U: AsRef<str>,
-{
-    let incl = categories
-        .iter()
-        .map(|s| wildmatch::WildMatch::new(s.as_ref()))
-        .collect::<Vec<_>>();
-    let excl = not_categories
-        .iter()
-        .map(|s| wildmatch::WildMatch::new(s.as_ref()))
-        .collect::<Vec<_>>();
-    rl.slice_spanning_interval(interval)
-        .iter()
-        .filter({
-            |r| {
-                incl.iter().any(|p| p.matches(r.category().as_str()))
-                    && !excl.iter().any(|p| p.matches(r.category().as_str()))
-            }
-        })
-        .collect::<base::Recordlist>()
+/// Returns the gist of a command line parse error, without the usage and tips
+/// that clap would print along with it.
+pub fn clap_error_message(e: &clap::Error) -> String {
+    let msg = e.to_string();
+    let msg = msg.lines().next().unwrap_or_default();
+    msg.strip_prefix("error: ").unwrap_or(msg).to_string()
 }
 
 #[cfg(test)]
 mod tests {
-    use rstest::fixture;
     use rstest::rstest;
 
     use super::*;
 
-    #[fixture]
-    fn rl() -> base::Recordlist {
-        r#"
-            {"d":"2015-03-01","c":"aaa","a":10000}
-            {"d":"2015-03-30","c":"aaa","a":10000}
-            {"d":"2015-03-31","c":"bbb","a":5000}
-            {"d":"2015-04-15","c":"ccc","a":-2000}
-            {"d":"2015-04-29","c":"aaa","a":-2000}
-            {"d":"2015-05-02","c":"bbb","a":-2000}
-            {"d":"2015-05-05","c":"ccc","a":2000}
-            {"d":"2015-05-20","c":"aaa","a":2000}
-        "#
-        .parse()
-        .unwrap()
-    }
-
     #[rstest]
     #[case(
         base::Config {
@@ -135,51 +133,40 @@ mod tests {
     }
 
     #[rstest]
-    #[case(&[], /*fullmatch=*/true, &[])]
-    #[case(&[], /*fullmatch=*/false, &[])]
-    #[case(
-        &["1".into(), "".into(), "2*".into(), "**3*3".into()],
-        /*fullmatch=*/true,
-        &["1", "", "2*", "**3*3"]
-    )]
+    #[case(base::Config::default(), base::Calendar::default())]
     #[case(
-        &["1".into(), "".into(), "2*".into(), "**3*3".into()],
-        /*fullmatch=*/false,
-        &["*1*", "","*2*", "**3*3*"]
+        base::Config {
+            first_day_of_week: base::Weekday::Sunday,
+            fiscal_year_start: base::Month::April,
+            ..base::Config::default()
+        },
+        base::Calendar {
+            first_weekday: base::Weekday::Sunday,
+            first_month: base::Month::April,
+        },
     )]
-    fn test_preprocess_categories(
-        #[case] categories: &[String],
-        #[case] fullmatch: bool,
-        #[case] want: &[&str],
-    ) {
-        let got = preprocess_categories(categories, fullmatch);
+    fn test_calendar_from_config(#[case] config: base::Config, #[case] want: base::Calendar) {
+        let got = calendar_from_config(&config);
         assert_eq!(got, want);
     }
 
     #[rstest]
-    #[case(base::Interval::EMPTY, &["*"], &[], "")]
-    #[case(base::Interval::MAX, &[], &[], "")]
-    #[case(base::Interval::MAX, &["*"], &["*"], "")]
-    #[case(base::Interval::MAX, &["*"], &[], self::rl())]
-    #[case(
-        "2015-03-30:2015-05-10",
-        &["*b*", "c*"],
-        &["*a"],
-        r#"
-            {"d":"2015-03-31","c":"bbb","a":5000}
-            {"d":"2015-04-15","c":"ccc","a":-2000}
-            {"d":"2015-05-02","c":"bbb","a":-2000}
-            {"d":"2015-05-05","c":"ccc","a":2000}
-        "#
-    )]
-    fn test_filter_rl(
-        rl: base::Recordlist,
-        #[case] interval: base::Interval,
-        #[case] categories: &[&str],
-        #[case] not_categories: &[&str],
-        #[case] want: base::Recordlist,
-    ) {
-        let got = filter_rl(&rl, interval, categories, not_categories);
-        assert_eq!(got, want);
+    #[case("", &[])]
+    #[case("  ", &[])]
+    #[case("log aaa  1", &["log", "aaa", "1"])]
+    #[case(r#"log aaa 1 -n "x y""#, &["log", "aaa", "1", "-n", "x y"])]
+    #[case(r#"-n 'it"s' "it's""#, &["-n", r#"it"s"#, "it's"])]
+    #[case(r#"a\ b "c\"\\\d" ''"#, &["a b", r#"c"\\d"#, ""])]
+    #[case(r#"a"b"'c'd"#, &["abcd"])]
+    fn test_split_words(#[case] line: &str, #[case] want: &[&str]) {
+        assert_eq!(split_words(line).unwrap(), want)
+    }
+
+    #[rstest]
+    #[case(r#"a "b"#, "unterminated quote")]
+    #[case("a 'b", "unterminated quote")]
+    #[case(r"a\", "trailing backslash")]
+    fn test_split_words_err(#[case] line: &str, #[case] want: &str) {
+        assert_eq!(split_words(line).unwrap_err().to_string(), want)
     }
 }
@@ -0,0 +1,36 @@
+//! Cash flow tracking on top of plain JSON files.
+//!
+//! A repository is a directory holding a config file, a list of transaction
+//! records, and a file of contribution limits. [`base::Fs`] reads and writes
+//! these as [`base::Config`], [`base::Recordlist`] and
+//! [`base::Limitaccounts`]. The remaining types of [`base`] filter records,
+//! resolve dates and intervals, and build the reports that the `ledger`
+//! binary prints.
+//!
+//! ```
+//! use ledger::base;
+//!
+//! let rl = r#"
+//!     {"d":"2015-03-01","c":"food/groceries","a":-5000}
+//!     {"d":"2015-03-15","c":"income","a":200000}
+//!     {"d":"2015-04-01","c":"food/dining","a":-2500}
+//! "#
+//! .parse::<base::Recordlist>()?;
+//! let interval = "2015-03".parse::<base::Interval>()?;
+//! let food = base::filter_rl::<_, &str>(&rl, interval, &["food/*"], &[]);
+//! assert_eq!(food.len(), 1);
+//! assert_eq!(food.iter().map(|r| r.amount()).sum::<base::Cents>(), base::Cents(-5000));
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! Errors are reported through the error types of the modules they come from,
+//! such as [`base::recordlist::ParseError`] and [`base::fs::ReadError`].
+//!
+//! The command line interface lives in `cli`, behind the default `cli`
+//! feature. Embedders can turn it off to avoid its dependencies.
+
+#![warn(missing_docs)]
+
+pub mod base;
+#[cfg(feature = "cli")]
+pub mod cli;